@@ -1,5 +1,7 @@
+use crate::storage::event_store::{self, EventFilter, EventRow};
+use crate::storage::repo_snapshots::{self, SnapshotKind};
 use opscinema_types::{AnchorCandidate, AnchorId, EvidenceLocator, StepId};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use uuid::Uuid;
 
@@ -29,14 +31,14 @@ struct AnchorManuallySetPayload {
     manual_note: Option<String>,
 }
 
-pub fn replay_session(
-    conn: &crate::storage::DbConn,
-    session_id: Uuid,
-) -> anyhow::Result<Vec<AnchorCandidate>> {
-    let events = crate::storage::event_store::query_events(conn, session_id, None, 100_000)?;
-    let mut anchors: BTreeMap<AnchorId, AnchorCandidate> = BTreeMap::new();
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AnchorsReplayState {
+    anchors: BTreeMap<AnchorId, AnchorCandidate>,
+}
 
-    for event in events {
+impl AnchorsReplayState {
+    fn apply(&mut self, event: &EventRow) -> anyhow::Result<()> {
+        let anchors = &mut self.anchors;
         match event.event_type.as_str() {
             "AnchorCandidatesGenerated" => {
                 let payload: AnchorCandidatesGeneratedPayload =
@@ -74,9 +76,59 @@ pub fn replay_session(
             }
             _ => {}
         }
+        Ok(())
+    }
+}
+
+/// Replays anchors from the latest valid checkpoint, applying only events after
+/// it, and advances the checkpoint to the last replayed event.
+pub fn replay_session(
+    conn: &crate::storage::DbConn,
+    session_id: Uuid,
+) -> anyhow::Result<Vec<AnchorCandidate>> {
+    let (mut state, from_seq) =
+        match repo_snapshots::load_checkpoint(conn, SnapshotKind::Anchors, session_id)? {
+            Some(checkpoint) => (checkpoint.state, checkpoint.head_seq),
+            None => (AnchorsReplayState::default(), 0),
+        };
+    let mut last = None;
+    for event in event_store::stream_events(conn, session_id, replay_filter().after(from_seq)) {
+        let event = event?;
+        state.apply(&event)?;
+        last = Some(event);
+    }
+    if let Some(last) = last {
+        repo_snapshots::save_checkpoint(
+            conn,
+            SnapshotKind::Anchors,
+            session_id,
+            last.seq,
+            &last.event_hash,
+            &state,
+        )?;
+    }
+    Ok(state.anchors.into_values().collect())
+}
+
+/// Replays anchors from the first event without reading or writing checkpoints.
+pub fn rebuild_session(
+    conn: &crate::storage::DbConn,
+    session_id: Uuid,
+) -> anyhow::Result<Vec<AnchorCandidate>> {
+    let mut state = AnchorsReplayState::default();
+    for event in event_store::stream_events(conn, session_id, replay_filter()) {
+        state.apply(&event?)?;
     }
+    Ok(state.anchors.into_values().collect())
+}
 
-    Ok(anchors.into_values().collect())
+fn replay_filter() -> EventFilter {
+    EventFilter::types(&[
+        "AnchorCandidatesGenerated",
+        "AnchorResolved",
+        "AnchorDegraded",
+        "AnchorManuallySet",
+    ])
 }
 
 pub fn list_for_step(
@@ -88,6 +140,6 @@ pub fn list_for_step(
         .into_iter()
         .filter(|a| a.step_id == step_id)
         .collect::<Vec<_>>();
-    anchors.sort_by(|a, b| a.anchor_id.cmp(&b.anchor_id));
+    anchors.sort_by_key(|a| a.anchor_id);
     Ok(anchors)
 }
@@ -1,5 +1,6 @@
 use crate::agent_plant::{dag::PipelineDag, diagnostics, transforms};
 use crate::api::Backend;
+use crate::storage::event_store::{stream_events, EventFilter};
 use crate::storage::{repo_jobs, repo_sessions};
 use crate::util::canon_json::to_canonical_json;
 use opscinema_types::{
@@ -118,13 +119,9 @@ pub fn agent_pipeline_report(
     let sessions =
         crate::storage::repo_sessions::list_sessions(&conn, 10_000).map_err(internal_anyhow)?;
     for session in sessions {
-        let events =
-            crate::storage::event_store::query_events(&conn, session.session_id, None, 100_000)
-                .map_err(internal_anyhow)?;
-        for event in events {
-            if event.event_type != "AgentPipelineRunCompleted" {
-                continue;
-            }
+        let filter = EventFilter::types(&["AgentPipelineRunCompleted"]);
+        for event in stream_events(&conn, session.session_id, filter) {
+            let event = event.map_err(internal_anyhow)?;
             let payload: AgentPipelineRunCompletedPayload =
                 serde_json::from_str(&event.payload_canon_json)
                     .map_err(|e| internal(&e.to_string()))?;
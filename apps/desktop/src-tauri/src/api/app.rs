@@ -59,6 +59,21 @@ pub fn settings_get(backend: &Backend) -> AppResult<AppSettings> {
 }
 
 pub fn settings_set(backend: &Backend, req: AppSettings) -> AppResult<AppSettings> {
+    if !crate::ocr::registry::is_available(req.ocr_provider) {
+        return Err(AppError {
+            code: opscinema_types::AppErrorCode::Unsupported,
+            message: "OCR provider is not available in this build".to_string(),
+            details: Some(format!(
+                "{:?}; available: {:?}",
+                req.ocr_provider,
+                crate::ocr::registry::available()
+            )),
+            recoverable: true,
+            action_hint: Some(
+                "Choose auto, or rebuild with the ocr-tesseract feature for Tesseract".to_string(),
+            ),
+        });
+    }
     backend
         .settings
         .lock()
@@ -1,6 +1,9 @@
 use crate::api::Backend;
+use crate::image::dhash;
+use crate::image::thumbnail::THUMBNAIL_MAX_EDGE;
 use crate::platform::macos::screencapturekit;
 use crate::policy::permissions::require_screen_permission;
+use crate::steps::segment::FRAME_CHANGE_BITS;
 use crate::storage::event_store::append_event;
 use opscinema_types::{
     AppError, AppErrorCode, AppResult, CaptureConfig, CaptureStartRequest, CaptureStatus,
@@ -12,6 +15,10 @@ use std::sync::{Mutex, OnceLock};
 use std::time::Duration;
 use uuid::Uuid;
 
+/// dHash bits a frame may differ from the last kept keyframe and still be
+/// suppressed as a near-duplicate.
+pub const DEFAULT_DEDUP_MAX_HASH_DISTANCE: u32 = 4;
+
 fn capture_config_store() -> &'static Mutex<CaptureConfig> {
     static STORE: OnceLock<Mutex<CaptureConfig>> = OnceLock::new();
     STORE.get_or_init(|| {
@@ -19,6 +26,7 @@ fn capture_config_store() -> &'static Mutex<CaptureConfig> {
             keyframe_interval_ms: 400,
             include_input: false,
             include_window_meta: false,
+            dedup_max_hash_distance: Some(DEFAULT_DEDUP_MAX_HASH_DISTANCE),
         })
     })
 }
@@ -31,6 +39,20 @@ pub fn capture_get_config() -> AppResult<CaptureConfig> {
 }
 
 pub fn capture_set_config(cfg: CaptureConfig) -> AppResult<CaptureConfig> {
+    if cfg
+        .dedup_max_hash_distance
+        .is_some_and(|max| max >= FRAME_CHANGE_BITS)
+    {
+        return Err(AppError {
+            code: AppErrorCode::ValidationFailed,
+            message: format!(
+                "dedup_max_hash_distance must be below {FRAME_CHANGE_BITS}, the step segmentation threshold"
+            ),
+            details: None,
+            recoverable: true,
+            action_hint: Some("Lower the threshold or disable deduplication".to_string()),
+        });
+    }
     let mut guard = capture_config_store()
         .lock()
         .map_err(|_| internal("capture config lock poisoned"))?;
@@ -46,6 +68,17 @@ struct KeyframeCaptured {
     pixel_w: u32,
     pixel_h: u32,
     scale_factor: String,
+    thumbnail_asset_id: String,
+    dhash: String,
+}
+
+#[derive(Debug, Serialize)]
+struct KeyframeSuppressed {
+    frame_ms: i64,
+    retained_frame_event_id: Uuid,
+    retained_asset_id: String,
+    dhash: String,
+    hash_distance: u32,
 }
 
 #[derive(Debug, Serialize)]
@@ -110,7 +143,8 @@ pub fn capture_start(backend: &Backend, req: CaptureStartRequest) -> AppResult<C
     }
 
     let start_ms = frame_ms_seed(0);
-    capture_single_frame(backend, req.session_id, start_ms)?;
+    let mut retained = None;
+    capture_single_frame(backend, req.session_id, start_ms, &mut retained)?;
 
     let mut status = backend
         .capture_status
@@ -127,7 +161,14 @@ pub fn capture_start(backend: &Backend, req: CaptureStartRequest) -> AppResult<C
 
     let max_frames = capture_loop_max_frames();
     if max_frames != Some(1) {
-        spawn_capture_loop(backend, req.session_id, start_ms, interval_ms, max_frames)?;
+        spawn_capture_loop(
+            backend,
+            req.session_id,
+            start_ms,
+            interval_ms,
+            max_frames,
+            retained,
+        )?;
     }
 
     Ok(response)
@@ -246,6 +287,7 @@ fn spawn_capture_loop(
     start_ms: i64,
     interval_ms: u32,
     max_frames: Option<usize>,
+    mut retained: Option<RetainedFrame>,
 ) -> AppResult<()> {
     let stop = std::sync::Arc::new(AtomicBool::new(false));
     {
@@ -279,7 +321,7 @@ fn spawn_capture_loop(
                 break;
             }
             let frame_ms = start_ms + (idx as i64 * i64::from(interval_ms));
-            if capture_single_frame(&backend_clone, session_id, frame_ms).is_err() {
+            if capture_single_frame(&backend_clone, session_id, frame_ms, &mut retained).is_err() {
                 break;
             }
             let capturing = capture_status_snapshot(
@@ -339,28 +381,81 @@ fn emit_capture_status_hook(backend: &Backend, status: &CaptureStatus) {
     }
 }
 
-fn capture_single_frame(backend: &Backend, session_id: Uuid, frame_ms: i64) -> AppResult<()> {
+/// Last keyframe written for the running capture; later frames are compared
+/// against it rather than against their immediate predecessor, so a slow
+/// drift still produces a new keyframe once it adds up.
+#[derive(Debug, Clone)]
+struct RetainedFrame {
+    event_id: Uuid,
+    asset_id: String,
+    dhash: u64,
+}
+
+fn capture_single_frame(
+    backend: &Backend,
+    session_id: Uuid,
+    frame_ms: i64,
+    retained: &mut Option<RetainedFrame>,
+) -> AppResult<()> {
     let provider = screencapturekit::provider();
     let keyframe = provider
         .capture_keyframe(frame_ms)
         .map_err(|e| internal(&format!("capture failed: {e}")))?;
 
+    // Providers report dimensions from display metadata; the decoded PNG is
+    // authoritative and a frame that does not decode is never stored.
+    let image = crate::image::decode_png(&keyframe.png_bytes)
+        .map_err(|e| internal(&format!("capture produced an invalid PNG: {e}")))?;
+    let hash = dhash::dhash(&image);
+    let max_distance = capture_get_config()?.dedup_max_hash_distance;
+
     let mut conn = backend.storage.conn().map_err(db_err)?;
-    let asset_id = backend
-        .assets
-        .put(&conn, &keyframe.png_bytes, None)
-        .map_err(|e| internal(&e.to_string()))?;
-    let display_id = keyframe.display_id.clone();
-    let payload = KeyframeCaptured {
-        frame_ms: keyframe.frame_ms,
-        asset_id,
-        display_id,
-        pixel_w: keyframe.pixel_w,
-        pixel_h: keyframe.pixel_h,
-        scale_factor: keyframe.scale_factor,
-    };
-    append_event(&mut conn, session_id, "KeyframeCaptured", &payload, None)
-        .map_err(|e| internal(&e.to_string()))?;
+    match (retained.as_ref(), max_distance) {
+        (Some(kept), Some(max)) if (kept.dhash ^ hash).count_ones() <= max => {
+            let payload = KeyframeSuppressed {
+                frame_ms: keyframe.frame_ms,
+                retained_frame_event_id: kept.event_id,
+                retained_asset_id: kept.asset_id.clone(),
+                dhash: dhash::to_hex(hash),
+                hash_distance: (kept.dhash ^ hash).count_ones(),
+            };
+            append_event(&mut conn, session_id, "KeyframeSuppressed", &payload, None)
+                .map_err(|e| internal(&e.to_string()))?;
+        }
+        _ => {
+            let thumbnail = crate::image::encode_png(
+                &crate::image::thumbnail::downscale(&image, THUMBNAIL_MAX_EDGE),
+                &[],
+            )
+            .map_err(|e| internal(&format!("thumbnail encoding failed: {e}")))?;
+            let asset_id = backend
+                .assets
+                .put(&conn, &keyframe.png_bytes, None)
+                .map_err(|e| internal(&e.to_string()))?;
+            let thumbnail_asset_id = backend
+                .assets
+                .put(&conn, &thumbnail, None)
+                .map_err(|e| internal(&e.to_string()))?;
+            let payload = KeyframeCaptured {
+                frame_ms: keyframe.frame_ms,
+                asset_id: asset_id.clone(),
+                display_id: keyframe.display_id.clone(),
+                pixel_w: image.width,
+                pixel_h: image.height,
+                scale_factor: keyframe.scale_factor.clone(),
+                thumbnail_asset_id,
+                dhash: dhash::to_hex(hash),
+            };
+            let (event_id, _, _) =
+                append_event(&mut conn, session_id, "KeyframeCaptured", &payload, None)
+                    .map_err(|e| internal(&e.to_string()))?;
+            *retained = Some(RetainedFrame {
+                event_id,
+                asset_id,
+                dhash: hash,
+            });
+        }
+    }
 
     let settings = backend
         .settings
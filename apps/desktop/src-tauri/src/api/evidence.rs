@@ -1,10 +1,15 @@
 use crate::api::Backend;
 use crate::evidence::coverage;
+use crate::evidence::graph::{block_node_id, EvidenceGraph, DEFAULT_PATH_MAX_DEPTH};
+use crate::storage::index_fts;
 use opscinema_types::{
     AppError, AppErrorCode, AppResult, EvidenceCoverageRequest, EvidenceCoverageResponse,
-    EvidenceFindTextRequest, EvidenceFindTextResponse, EvidenceForStepRequest,
-    EvidenceForTimeRangeRequest, EvidenceSet,
+    EvidenceEdgeDirection, EvidenceFindTextRequest, EvidenceFindTextResponse,
+    EvidenceForStepRequest, EvidenceForTimeRangeRequest, EvidenceGraphNeighborsRequest,
+    EvidenceGraphPathRequest, EvidenceGraphSupportsRequest, EvidenceGroundingRequest,
+    EvidenceGroundingResponse, EvidenceSet, EvidenceSubgraph,
 };
+use uuid::Uuid;
 
 pub fn evidence_for_time_range(
     backend: &Backend,
@@ -52,10 +57,20 @@ pub fn evidence_for_step(backend: &Backend, req: EvidenceForStepRequest) -> AppR
     Ok(crate::evidence::query::for_step(&step, &all))
 }
 
+/// OCR spans come from the FTS index in rank order; other evidence (window
+/// titles, notes) follows when its note contains every query term.
 pub fn evidence_find_text(
     backend: &Backend,
     req: EvidenceFindTextRequest,
 ) -> AppResult<EvidenceFindTextResponse> {
+    let Some(query) = crate::ocr::index::parse_query(&req.query) else {
+        return Ok(EvidenceFindTextResponse { evidence: vec![] });
+    };
+    let hits = {
+        let conn = backend.storage.conn().map_err(db_err)?;
+        index_fts::search(&conn, req.session_id, &query, index_fts::SEARCH_HIT_LIMIT)
+            .map_err(internal_anyhow)?
+    };
     let all = evidence_for_time_range(
         backend,
         EvidenceForTimeRangeRequest {
@@ -64,18 +79,25 @@ pub fn evidence_find_text(
             end_ms: i64::MAX,
         },
     )?;
-    let q = req.query.to_lowercase();
-    let evidence = all
+    let mut ocr = all
         .evidence
-        .into_iter()
-        .filter(|e| {
-            e.locators.iter().any(|l| {
-                l.note
-                    .as_ref()
-                    .map(|n| n.to_lowercase().contains(&q))
-                    .unwrap_or(false)
-            })
+        .iter()
+        .filter(|e| e.kind == "OcrSpan")
+        .filter_map(|e| {
+            let rank = hits.iter().position(|h| h.block_id == e.source_id)?;
+            Some((rank, e.clone()))
         })
+        .collect::<Vec<_>>();
+    ocr.sort_by_key(|(rank, _)| *rank);
+    let evidence = ocr
+        .into_iter()
+        .map(|(_, e)| e)
+        .chain(all.evidence.into_iter().filter(|e| {
+            e.kind != "OcrSpan"
+                && e.locators
+                    .iter()
+                    .any(|l| l.note.as_deref().is_some_and(|n| query.matches_text(n)))
+        }))
         .collect();
     Ok(EvidenceFindTextResponse { evidence })
 }
@@ -91,7 +113,82 @@ pub fn evidence_get_coverage(
         },
     )?
     .steps;
-    Ok(coverage::evaluate(&steps))
+    let conn = backend.storage.conn().map_err(db_err)?;
+    let refs = coverage::SessionEvidenceRefs::load(&conn, req.session_id, &steps)
+        .map_err(internal_anyhow)?;
+    Ok(coverage::evaluate(&steps, &refs))
+}
+
+pub fn evidence_get_grounding(
+    backend: &Backend,
+    req: EvidenceGroundingRequest,
+) -> AppResult<EvidenceGroundingResponse> {
+    let steps = crate::api::steps::steps_list(
+        backend,
+        opscinema_types::StepsListRequest {
+            session_id: req.session_id,
+        },
+    )?
+    .steps;
+    let conn = backend.storage.conn().map_err(db_err)?;
+    crate::evidence::grounding::load(&conn, req.session_id, &steps).map_err(internal_anyhow)
+}
+
+pub fn evidence_graph_neighbors(
+    backend: &Backend,
+    req: EvidenceGraphNeighborsRequest,
+) -> AppResult<EvidenceSubgraph> {
+    let graph = load_graph(backend, req.session_id)?;
+    ensure_node(&graph, &req.node_id)?;
+    Ok(graph.neighbors(
+        &req.node_id,
+        req.direction.unwrap_or(EvidenceEdgeDirection::Both),
+        &req.edge_types,
+    ))
+}
+
+/// An empty subgraph means the nodes are not connected within `max_depth`.
+pub fn evidence_graph_path(
+    backend: &Backend,
+    req: EvidenceGraphPathRequest,
+) -> AppResult<EvidenceSubgraph> {
+    let graph = load_graph(backend, req.session_id)?;
+    ensure_node(&graph, &req.from_node_id)?;
+    ensure_node(&graph, &req.to_node_id)?;
+    Ok(graph.shortest_path(
+        &req.from_node_id,
+        &req.to_node_id,
+        req.max_depth.unwrap_or(DEFAULT_PATH_MAX_DEPTH),
+    ))
+}
+
+pub fn evidence_graph_supports(
+    backend: &Backend,
+    req: EvidenceGraphSupportsRequest,
+) -> AppResult<EvidenceSubgraph> {
+    let graph = load_graph(backend, req.session_id)?;
+    let node_id = block_node_id(req.step_id, &req.block_id);
+    ensure_node(&graph, &node_id)?;
+    Ok(graph.supports(&node_id))
+}
+
+fn load_graph(backend: &Backend, session_id: Uuid) -> AppResult<EvidenceGraph> {
+    let steps =
+        crate::api::steps::steps_list(backend, opscinema_types::StepsListRequest { session_id })?
+            .steps;
+    let conn = backend.storage.conn().map_err(db_err)?;
+    let evidence = crate::evidence::graph::derive_from_event_log(&conn, session_id)
+        .map_err(internal_anyhow)?;
+    let anchors =
+        crate::anchors::cache::replay_session(&conn, session_id).map_err(internal_anyhow)?;
+    Ok(EvidenceGraph::build(&evidence, &steps, &anchors))
+}
+
+fn ensure_node(graph: &EvidenceGraph, node_id: &str) -> AppResult<()> {
+    match graph.node(node_id) {
+        Some(_) => Ok(()),
+        None => Err(not_found("evidence graph node not found")),
+    }
 }
 
 fn db_err<E: std::fmt::Display>(e: E) -> AppError {
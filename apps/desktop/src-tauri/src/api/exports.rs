@@ -1,10 +1,12 @@
 use crate::api::steps::steps_list;
 use crate::api::Backend;
-use crate::exports::{tutorial_pack, verify};
-use crate::storage::repo_exports;
+use crate::exports::{signing, tutorial_pack, verify};
+use crate::storage::{repo_exports, repo_timeline, signing_keys};
+use opscinema_export_manifest::EventSeqRange;
 use opscinema_types::{
-    AppError, AppErrorCode, AppResult, ExportResult, ExportVerifyRequest, ExportVerifyResponse,
-    ExportsListRequest, ExportsListResponse, StepsListRequest, TutorialExportRequest,
+    AppError, AppErrorCode, AppResult, ExportResult, ExportTrustedKeys, ExportTrustedKeysUpdate,
+    ExportVerifyRequest, ExportVerifyResponse, ExportsListRequest, ExportsListResponse,
+    StepsListRequest, TutorialExportRequest,
 };
 use serde::Serialize;
 
@@ -26,37 +28,139 @@ pub fn exports_list(backend: &Backend, req: ExportsListRequest) -> AppResult<Exp
 }
 
 pub fn export_verify_bundle(
-    _backend: &Backend,
+    backend: &Backend,
     req: ExportVerifyRequest,
 ) -> AppResult<ExportVerifyResponse> {
-    verify::verify_bundle(std::path::Path::new(&req.bundle_path)).map_err(internal_anyhow)
+    let trusted = export_trusted_keys_get(backend)?;
+    let mut keys = trusted.trusted_public_keys;
+    keys.push(trusted.local_public_key);
+    verify::verify_bundle(std::path::Path::new(&req.bundle_path), &keys).map_err(internal_anyhow)
+}
+
+pub fn export_trusted_keys_get(backend: &Backend) -> AppResult<ExportTrustedKeys> {
+    let key =
+        signing_keys::load_or_create_signing_key(&backend.storage).map_err(internal_anyhow)?;
+    let conn = backend.storage.conn().map_err(db_err)?;
+    Ok(ExportTrustedKeys {
+        local_public_key: signing_keys::public_key_hex(&key),
+        trusted_public_keys: signing_keys::list_trusted_keys(&conn).map_err(internal_anyhow)?,
+    })
+}
+
+pub fn export_trusted_keys_set(
+    backend: &Backend,
+    req: ExportTrustedKeysUpdate,
+) -> AppResult<ExportTrustedKeys> {
+    let mut keys = Vec::with_capacity(req.trusted_public_keys.len());
+    for key in &req.trusted_public_keys {
+        let normalized = key.trim().to_ascii_lowercase();
+        let decodes = crate::util::hash::hex_decode(&normalized)
+            .and_then(|b| <[u8; 32]>::try_from(b).ok())
+            .is_some_and(|b| ed25519_dalek::VerifyingKey::from_bytes(&b).is_ok());
+        if !decodes {
+            return Err(AppError {
+                code: AppErrorCode::ValidationFailed,
+                message: "trusted key must be a hex-encoded Ed25519 public key".to_string(),
+                details: Some(key.clone()),
+                recoverable: true,
+                action_hint: None,
+            });
+        }
+        keys.push(normalized);
+    }
+    let mut conn = backend.storage.conn().map_err(db_err)?;
+    signing_keys::set_trusted_keys(&mut conn, &keys).map_err(internal_anyhow)?;
+    export_trusted_keys_get(backend)
+}
+
+/// Seq range of a session log read through `head_seq`; empty logs yield `0..=0`.
+pub(crate) fn seq_range_through(head_seq: i64) -> EventSeqRange {
+    EventSeqRange {
+        first_seq: head_seq.min(1),
+        last_seq: head_seq,
+    }
+}
+
+/// Signs the bundle manifest with this workstation's key. Must run before the
+/// manifest is persisted as an asset so the stored copy carries the signature.
+pub(crate) fn sign_export(backend: &Backend, output_path: &str) -> AppResult<()> {
+    let key =
+        signing_keys::load_or_create_signing_key(&backend.storage).map_err(internal_anyhow)?;
+    signing::sign_bundle(std::path::Path::new(output_path), &key).map_err(internal_anyhow)?;
+    Ok(())
 }
 
 pub fn tutorial_export_pack(
     backend: &Backend,
     req: TutorialExportRequest,
 ) -> AppResult<ExportResult> {
-    let steps = steps_list(
+    let listed = steps_list(
         backend,
         StepsListRequest {
             session_id: req.session_id,
         },
-    )?
-    .steps;
+    )?;
+    let steps = listed.steps;
     let mut conn = backend.storage.conn().map_err(db_err)?;
-    let degraded_anchor_ids = crate::anchors::cache::replay_session(&conn, req.session_id)
-        .map_err(internal_anyhow)?
+    let evidence = crate::evidence::graph::derive_from_event_log(&conn, req.session_id)
+        .map_err(internal_anyhow)?;
+    let anchors =
+        crate::anchors::cache::replay_session(&conn, req.session_id).map_err(internal_anyhow)?;
+    let keyframes = repo_timeline::get_keyframes(&conn, req.session_id, i64::MIN, i64::MAX)
+        .map_err(internal_anyhow)?;
+    let step_frames = tutorial_pack::resolve_step_frames(&steps, &evidence, &anchors, &keyframes);
+    let mut assets = crate::evidence::query::assets_for_steps(&steps, &evidence);
+    for frame in &step_frames {
+        assets
+            .entry(frame.asset_id.clone())
+            .or_default()
+            .extend(frame.evidence_ids.iter().copied());
+    }
+    let redactor = crate::api::redaction::session_redactor(&conn, req.session_id)?;
+    let evidence_assets = assets
         .into_iter()
+        .map(|(asset_id, evidence_ids)| {
+            let redact_boxes = keyframes
+                .iter()
+                .find(|k| k.asset.asset_id == asset_id)
+                .map(|k| redactor.frame_boxes(k.frame_ms, &evidence.evidence))
+                .unwrap_or_default();
+            tutorial_pack::EvidenceAsset {
+                source_path: backend.assets.path_for(&asset_id),
+                asset_id,
+                evidence_ids: evidence_ids.into_iter().collect(),
+                redact_boxes,
+            }
+        })
+        .collect();
+    let referenced = crate::evidence::query::referenced_ids(&steps);
+    let step_evidence = evidence
+        .evidence
+        .iter()
+        .filter(|e| referenced.contains(&e.evidence_id))
+        .cloned()
+        .collect();
+    let degraded_anchor_ids = anchors
+        .iter()
         .filter(|a| a.degraded)
         .map(|a| a.anchor_id.to_string())
         .collect::<Vec<_>>();
-    let coverage = crate::evidence::coverage::evaluate(&steps);
+    let refs = crate::evidence::coverage::SessionEvidenceRefs::load(&conn, req.session_id, &steps)
+        .map_err(internal_anyhow)?;
+    let coverage = crate::evidence::coverage::evaluate(&steps, &refs);
+    let grounding =
+        crate::evidence::grounding::load(&conn, req.session_id, &steps).map_err(internal_anyhow)?;
     let offline_policy_enforced = backend
         .settings
         .lock()
         .map_err(|_| internal("settings lock poisoned"))?
         .offline_mode;
     let model_pins = crate::api::model_dock::collect_model_pins(backend)?;
+    let mut warnings = crate::evidence::grounding::warnings(&grounding);
+    warnings.extend(crate::api::model_dock::model_pin_warnings(
+        backend,
+        &model_pins,
+    )?);
     let export = tutorial_pack::export_tutorial_pack(
         req.session_id,
         &steps,
@@ -66,14 +170,21 @@ pub fn tutorial_export_pack(
                 .iter()
                 .map(ToString::to_string)
                 .collect(),
+            invalid_evidence_refs: coverage.invalid_refs,
             degraded_anchor_ids,
-            warnings: vec![],
+            warnings,
             model_pins,
             offline_policy_enforced,
+            source_seq_range: seq_range_through(listed.head_seq),
+            evidence_assets,
+            step_frames,
+            evidence: step_evidence,
+            redactor: Some(redactor),
         },
         std::path::Path::new(&req.output_dir),
     )
     .map_err(internal_anyhow)?;
+    sign_export(backend, &export.output_path)?;
 
     let manifest_path = std::path::Path::new(&export.output_path).join("manifest.json");
     let manifest_bytes = std::fs::read(&manifest_path).map_err(|e| internal(&e.to_string()))?;
@@ -8,8 +8,10 @@ pub mod jobs;
 pub mod model_dock;
 pub mod ocr;
 pub mod proof;
+pub mod redaction;
 #[cfg(feature = "runtime")]
 pub mod runtime_events;
+pub mod search;
 pub mod sessions;
 pub mod slicer;
 pub mod steps;
@@ -56,6 +58,7 @@ impl Backend {
                 offline_mode: true,
                 allow_input_capture: false,
                 allow_window_metadata: false,
+                ocr_provider: opscinema_types::OcrProviderKind::Auto,
             })),
             network_policy: Arc::new(Mutex::new(NetworkPolicy::default())),
             capture_status: Arc::new(Mutex::new(CaptureStatus {
@@ -1,24 +1,61 @@
 use crate::api::Backend;
-use crate::model_dock::{adapters, bench, registry, roles};
-use crate::storage::{repo_jobs, repo_models};
+use crate::model_dock::{adapters, bench, registry, roles, verify};
+use crate::policy::provider_schema::{self, ProviderOutput, ProviderOutputError};
+use crate::storage::{repo_jobs, repo_models, DbConn};
+use crate::util::canon_json::to_canonical_json;
 use opscinema_export_manifest::ModelPin;
 use opscinema_types::{
-    AppError, AppErrorCode, AppResult, BenchListRequest, BenchListResponse, BenchRunRequest,
-    JobHandle, JobStatus, MlxRunRequest, ModelProfile, ModelRegisterRequest, ModelRoles,
-    ModelRolesUpdate, ModelsListRequest, ModelsListResponse, ModelsRemoveRequest,
-    ModelsRemoveResponse, OllamaListRequest, OllamaListResponse, OllamaPullRequest,
-    OllamaRunRequest,
+    AppError, AppErrorCode, AppResult, BenchListRequest, BenchListResponse, BenchRecord,
+    BenchRunRequest, ExportWarning, JobCounters, JobHandle, JobProgress, JobStatus, MlxRunRequest,
+    ModelProfile, ModelRegisterRequest, ModelRoles, ModelRolesUpdate, ModelsListRequest,
+    ModelsListResponse, ModelsRemoveRequest, ModelsRemoveResponse, OllamaListRequest,
+    OllamaListResponse, OllamaPullRequest, OllamaRunRequest,
 };
+use serde::Serialize;
 use std::collections::BTreeMap;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize)]
+struct ProviderOutputRejectedPayload {
+    output_kind: String,
+    attempt: u32,
+    max_attempts: u32,
+    raw_asset_id: String,
+    errors_asset_id: String,
+    violation_count: usize,
+}
 
 pub fn models_list(backend: &Backend, _req: ModelsListRequest) -> AppResult<ModelsListResponse> {
     let conn = backend.storage.conn().map_err(db_err)?;
     registry::list(&conn).map_err(internal_anyhow)
 }
 
+/// Registers a model only once the provider confirms the digest. A blank
+/// digest takes the installed one; a typed digest must match it, and the
+/// provider's spelling is what gets stored.
 pub fn models_register(backend: &Backend, req: ModelRegisterRequest) -> AppResult<ModelProfile> {
+    let model_name = match req.model_name.trim() {
+        "" => req.label.trim(),
+        name => name,
+    };
+    let installed =
+        verify::installed_digest(&provider_targets(backend)?, &req.provider, model_name)
+            .map_err(probe_err)?;
+    let typed = req.digest.trim();
+    if !typed.is_empty() && !verify::same_digest(typed, &installed) {
+        return Err(AppError {
+            code: AppErrorCode::ValidationFailed,
+            message: format!("digest does not match the installed {model_name}"),
+            details: Some(format!("registered={typed} installed={installed}")),
+            recoverable: true,
+            action_hint: Some(
+                "Register the installed digest, or reinstall the pinned model".to_string(),
+            ),
+        });
+    }
     let conn = backend.storage.conn().map_err(db_err)?;
-    registry::register(&conn, &req.provider, &req.label, &req.digest).map_err(internal_anyhow)
+    registry::register(&conn, &req.provider, &req.label, model_name, &installed)
+        .map_err(internal_anyhow)
 }
 
 pub fn models_remove(
@@ -49,8 +86,10 @@ pub fn ollama_list(backend: &Backend, req: OllamaListRequest) -> AppResult<Ollam
         .clone();
     policy.check_host(&host)?;
     adapters::ollama::list_models(&policy, &host)
-        .map(|models| OllamaListResponse { models })
-        .map_err(internal_anyhow)
+        .map(|models| OllamaListResponse {
+            models: models.into_iter().map(|m| m.name).collect(),
+        })
+        .map_err(ollama_err)
 }
 
 pub fn ollama_pull(backend: &Backend, req: OllamaPullRequest) -> AppResult<JobHandle> {
@@ -66,23 +105,40 @@ pub fn ollama_pull(backend: &Backend, req: OllamaPullRequest) -> AppResult<JobHa
     let job_id = repo_jobs::create_job(&conn, "ollama_pull", None).map_err(internal_anyhow)?;
     let _ = repo_jobs::update_job_status(&conn, job_id, JobStatus::Running, None, None);
 
-    let pull_res = adapters::ollama::pull_model(&policy, &host, &req.model);
+    let mut last_progress = None;
+    let pull_res = adapters::ollama::pull_model(&policy, &host, &req.model, |progress| {
+        let progress = pull_job_progress(progress);
+        let _ = repo_jobs::update_job_status(
+            &conn,
+            job_id,
+            JobStatus::Running,
+            Some(progress.clone()),
+            None,
+        );
+        last_progress = Some(progress);
+    })
+    .map_err(ollama_err)
+    .and_then(|model| {
+        registry::register(&conn, "ollama", &model.name, &model.name, &model.digest)
+            .map_err(internal_anyhow)
+    });
     match pull_res {
         Ok(_) => {
-            let digest = blake3::hash(format!("{host}:{}", req.model).as_bytes())
-                .to_hex()
-                .to_string();
-            let _ = registry::register(&conn, "ollama", &req.model, &digest);
-            let _ = repo_jobs::update_job_status(&conn, job_id, JobStatus::Succeeded, None, None);
+            let _ = repo_jobs::update_job_status(
+                &conn,
+                job_id,
+                JobStatus::Succeeded,
+                last_progress,
+                None,
+            );
             Ok(JobHandle { job_id })
         }
-        Err(e) => {
-            let app_err = internal(&e.to_string());
+        Err(app_err) => {
             let _ = repo_jobs::update_job_status(
                 &conn,
                 job_id,
                 JobStatus::Failed,
-                None,
+                last_progress,
                 Some(app_err.clone()),
             );
             Err(app_err)
@@ -103,7 +159,16 @@ pub fn ollama_run(backend: &Backend, req: OllamaRunRequest) -> AppResult<JobHand
     let job_id = repo_jobs::create_job(&conn, "ollama_run", None).map_err(internal_anyhow)?;
     let _ = repo_jobs::update_job_status(&conn, job_id, JobStatus::Running, None, None);
 
-    let run_res = adapters::ollama::run_prompt(&policy, &host, &req.model_id, &req.prompt);
+    // Registered models run under the name Ollama lists them with; anything
+    // else is taken as an Ollama model name.
+    let model = registry::list(&conn)
+        .map_err(internal_anyhow)?
+        .models
+        .into_iter()
+        .find(|m| m.provider == "ollama" && m.model_id == req.model_id)
+        .map(|m| m.model_name)
+        .unwrap_or_else(|| req.model_id.clone());
+    let run_res = adapters::ollama::run_prompt(&policy, &host, &model, &req.prompt);
     match run_res {
         Ok(output) => {
             let _ = backend.assets.put(&conn, output.as_bytes(), None);
@@ -111,7 +176,7 @@ pub fn ollama_run(backend: &Backend, req: OllamaRunRequest) -> AppResult<JobHand
             Ok(JobHandle { job_id })
         }
         Err(e) => {
-            let app_err = internal(&e.to_string());
+            let app_err = ollama_err(e);
             let _ = repo_jobs::update_job_status(
                 &conn,
                 job_id,
@@ -129,22 +194,73 @@ pub fn mlx_run(backend: &Backend, req: MlxRunRequest) -> AppResult<JobHandle> {
     let job_id = repo_jobs::create_job(&conn, "mlx_run", None).map_err(internal_anyhow)?;
     let _ = repo_jobs::update_job_status(&conn, job_id, JobStatus::Running, None, None);
 
-    let run_res = adapters::mlx::run_local(&req.model_id, &req.prompt);
+    // Registered models load by their model name (the weights path or repo
+    // id); anything else is handed to the runner as-is.
+    let model = registry::list(&conn)
+        .map_err(internal_anyhow)?
+        .models
+        .into_iter()
+        .find(|m| m.provider == "mlx" && m.model_id == req.model_id)
+        .map(|m| m.model_name)
+        .unwrap_or_else(|| req.model_id.clone());
+    let opts = adapters::mlx::RunOptions::from_env();
+    let mut last_progress = None;
+    let run_res = adapters::mlx::run_local(
+        &opts,
+        &model,
+        &req.prompt,
+        || backend.jobs.is_cancelled(job_id),
+        |progress| {
+            let progress = mlx_job_progress(progress);
+            let _ = repo_jobs::update_job_status(
+                &conn,
+                job_id,
+                JobStatus::Running,
+                Some(progress.clone()),
+                None,
+            );
+            last_progress = Some(progress);
+        },
+    )
+    .map_err(mlx_err)
+    .and_then(|output| {
+        registry::register(&conn, "mlx", &model, &model, &output.digest)
+            .map_err(internal_anyhow)?;
+        backend
+            .assets
+            .put(&conn, output.text.as_bytes(), None)
+            .map_err(internal_anyhow)?;
+        Ok(output)
+    });
     match run_res {
         Ok(output) => {
-            let digest = blake3::hash(req.model_id.as_bytes()).to_hex().to_string();
-            let _ = registry::register(&conn, "mlx", &req.model_id, &digest);
-            let _ = backend.assets.put(&conn, output.as_bytes(), None);
-            let _ = repo_jobs::update_job_status(&conn, job_id, JobStatus::Succeeded, None, None);
+            let _ = repo_jobs::update_job_status(
+                &conn,
+                job_id,
+                JobStatus::Succeeded,
+                Some(JobProgress {
+                    stage: "completed".to_string(),
+                    pct: 100,
+                    counters: JobCounters {
+                        done: u64::from(output.tokens),
+                        total: u64::from(output.tokens),
+                    },
+                }),
+                None,
+            );
             Ok(JobHandle { job_id })
         }
-        Err(e) => {
-            let app_err = internal(&e.to_string());
+        Err(app_err) => {
+            let status = if app_err.code == AppErrorCode::JobCancelled {
+                JobStatus::Cancelled
+            } else {
+                JobStatus::Failed
+            };
             let _ = repo_jobs::update_job_status(
                 &conn,
                 job_id,
-                JobStatus::Failed,
-                None,
+                status,
+                last_progress,
                 Some(app_err.clone()),
             );
             Err(app_err)
@@ -153,20 +269,56 @@ pub fn mlx_run(backend: &Backend, req: MlxRunRequest) -> AppResult<JobHandle> {
 }
 
 pub fn bench_run(backend: &Backend, req: BenchRunRequest) -> AppResult<JobHandle> {
+    let role = bench::BenchRole::parse(&req.benchmark).ok_or_else(|| AppError {
+        code: AppErrorCode::ValidationFailed,
+        message: format!("unknown benchmark {}", req.benchmark),
+        details: Some(format!(
+            "expected one of: {}",
+            bench::BenchRole::ALL.map(|r| r.as_str()).join(", ")
+        )),
+        recoverable: true,
+        action_hint: Some("Benchmark a model for one of its roles".to_string()),
+    })?;
     let conn = backend.storage.conn().map_err(db_err)?;
     let job_id = repo_jobs::create_job(&conn, "bench_run", None).map_err(internal_anyhow)?;
     let _ = repo_jobs::update_job_status(&conn, job_id, JobStatus::Running, None, None);
-    let score = (blake3::hash(format!("{}:{}", req.model_id, req.benchmark).as_bytes()).as_bytes()
-        [0] as i32)
-        .max(1);
-    let run_res = bench::record(&conn, &req.model_id, score);
+    let run_res = (|| -> AppResult<BenchRecord> {
+        let session = bench::golden_session().map_err(internal_anyhow)?;
+        let report = bench::run(
+            &session,
+            role,
+            &req.model_id,
+            |prompt| generate_with_model(backend, &req.model_id, prompt).map_err(Into::into),
+            |bytes| backend.assets.put(&conn, bytes, None),
+        )
+        .map_err(|e| e.downcast::<AppError>().unwrap_or_else(internal_anyhow))?;
+        let report_json = to_canonical_json(&report).map_err(|e| internal(&e.to_string()))?;
+        let report_asset_id = backend
+            .assets
+            .put(&conn, report_json.as_bytes(), None)
+            .map_err(internal_anyhow)?;
+        bench::record(&conn, &report, &report_asset_id).map_err(internal_anyhow)
+    })();
     match run_res {
-        Ok(_) => {
-            let _ = repo_jobs::update_job_status(&conn, job_id, JobStatus::Succeeded, None, None);
+        Ok(record) => {
+            let cases = record.metrics.map_or(0, |m| u64::from(m.cases));
+            let _ = repo_jobs::update_job_status(
+                &conn,
+                job_id,
+                JobStatus::Succeeded,
+                Some(JobProgress {
+                    stage: "completed".to_string(),
+                    pct: 100,
+                    counters: JobCounters {
+                        done: cases,
+                        total: cases,
+                    },
+                }),
+                None,
+            );
             Ok(JobHandle { job_id })
         }
-        Err(e) => {
-            let app_err = internal(&e.to_string());
+        Err(app_err) => {
             let _ = repo_jobs::update_job_status(
                 &conn,
                 job_id,
@@ -216,6 +368,180 @@ pub fn collect_model_pins(backend: &Backend) -> AppResult<Vec<ModelPin>> {
     Ok(pins)
 }
 
+/// Runs a model-backed output through schema validation with bounded repair
+/// retries. Every rejected reply is kept: its raw text and its violations are
+/// stored as assets and a `ProviderOutputRejected` event is appended to the
+/// session.
+pub(crate) fn validated_provider_output<T: ProviderOutput>(
+    backend: &Backend,
+    conn: &mut DbConn,
+    session_id: Uuid,
+    generate: impl FnMut(Option<&str>) -> anyhow::Result<String>,
+) -> AppResult<T> {
+    provider_schema::generate_validated::<T>(provider_schema::MAX_ATTEMPTS, generate, |rejection| {
+        let raw_asset_id = backend.assets.put(conn, rejection.raw.as_bytes(), None)?;
+        let errors_json = to_canonical_json(&serde_json::json!({
+            "output_kind": T::KIND,
+            "attempt": rejection.attempt,
+            "violations": rejection.violations,
+        }))?;
+        let errors_asset_id = backend.assets.put(conn, errors_json.as_bytes(), None)?;
+        crate::storage::event_store::append_event(
+            conn,
+            session_id,
+            "ProviderOutputRejected",
+            &ProviderOutputRejectedPayload {
+                output_kind: T::KIND.to_string(),
+                attempt: rejection.attempt,
+                max_attempts: provider_schema::MAX_ATTEMPTS,
+                raw_asset_id,
+                errors_asset_id,
+                violation_count: rejection.violations.len(),
+            },
+            None,
+        )?;
+        Ok(())
+    })
+    .map_err(|e| match e {
+        ProviderOutputError::Generate(err) => {
+            err.downcast::<AppError>().unwrap_or_else(internal_anyhow)
+        }
+        ProviderOutputError::Invalid { ref rejections, .. } => AppError {
+            code: AppErrorCode::ProviderSchemaInvalid,
+            message: e.to_string(),
+            details: rejections.last().map(|r| {
+                r.violations
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            }),
+            recoverable: true,
+            action_hint: Some(
+                "Retry, or assign a model that follows the output schema".to_string(),
+            ),
+        },
+    })
+}
+
+/// Generates text with a registered model, dispatching on its provider. Used
+/// by pipelines that run the model assigned to a role rather than a job the
+/// user started, so nothing is recorded beyond what the caller appends.
+pub(crate) fn generate_with_model(
+    backend: &Backend,
+    model_id: &str,
+    prompt: &str,
+) -> AppResult<String> {
+    let conn = backend.storage.conn().map_err(db_err)?;
+    let model = registry::list(&conn)
+        .map_err(internal_anyhow)?
+        .models
+        .into_iter()
+        .find(|m| m.model_id == model_id)
+        .ok_or_else(|| AppError {
+            code: AppErrorCode::NotFound,
+            message: format!("model {model_id} is not registered"),
+            details: None,
+            recoverable: true,
+            action_hint: Some("Register the model or reassign the role".to_string()),
+        })?;
+    match model.provider.as_str() {
+        "ollama" => {
+            let host = ollama_default_host();
+            let policy = backend
+                .network_policy
+                .lock()
+                .map_err(|_| internal("lock poisoned"))?
+                .clone();
+            policy.check_host(&host)?;
+            adapters::ollama::run_prompt(&policy, &host, &model.model_name, prompt)
+                .map_err(ollama_err)
+        }
+        "mlx" => adapters::mlx::run_local(
+            &adapters::mlx::RunOptions::from_env(),
+            &model.model_name,
+            prompt,
+            || false,
+            |_| {},
+        )
+        .map(|output| output.text)
+        .map_err(mlx_err),
+        other => Err(AppError {
+            code: AppErrorCode::ValidationFailed,
+            message: format!("provider {other} cannot generate text"),
+            details: None,
+            recoverable: true,
+            action_hint: Some("Assign an Ollama or MLX model to the role".to_string()),
+        }),
+    }
+}
+
+/// Checks each pinned model against its provider and returns a
+/// `MODEL_DIGEST_MISMATCH` warning for every pin the installed model no longer
+/// matches (`MODEL_DIGEST_UNVERIFIED` when the provider cannot be asked). A
+/// model pinned for several roles is probed once.
+pub fn model_pin_warnings(backend: &Backend, pins: &[ModelPin]) -> AppResult<Vec<ExportWarning>> {
+    if pins.is_empty() {
+        return Ok(Vec::new());
+    }
+    let conn = backend.storage.conn().map_err(db_err)?;
+    let by_id = registry::list(&conn)
+        .map_err(internal_anyhow)?
+        .models
+        .into_iter()
+        .map(|m| (m.model_id.clone(), m))
+        .collect::<BTreeMap<_, _>>();
+    let targets = provider_targets(backend)?;
+    let mut checks = BTreeMap::new();
+    let mut warnings = Vec::new();
+    for pin in pins {
+        let Some(model) = by_id.get(&pin.model_id) else {
+            continue;
+        };
+        let check = checks
+            .entry(pin.model_id.clone())
+            .or_insert_with(|| verify::check(model, &targets));
+        warnings.extend(verify::pin_warning(pin, &model.model_name, check));
+    }
+    Ok(warnings)
+}
+
+/// Digest `model_id` is installed at now, for recording which weights
+/// generated a block.
+pub(crate) fn installed_model_digest(backend: &Backend, model_id: &str) -> AppResult<String> {
+    let conn = backend.storage.conn().map_err(db_err)?;
+    let model = registry::list(&conn)
+        .map_err(internal_anyhow)?
+        .models
+        .into_iter()
+        .find(|m| m.model_id == model_id)
+        .ok_or_else(|| AppError {
+            code: AppErrorCode::NotFound,
+            message: format!("model {model_id} is not registered"),
+            details: None,
+            recoverable: true,
+            action_hint: Some("Register the model or reassign the role".to_string()),
+        })?;
+    verify::installed_digest(
+        &provider_targets(backend)?,
+        &model.provider,
+        &model.model_name,
+    )
+    .map_err(probe_err)
+}
+
+fn provider_targets(backend: &Backend) -> AppResult<verify::ProviderTargets> {
+    Ok(verify::ProviderTargets {
+        policy: backend
+            .network_policy
+            .lock()
+            .map_err(|_| internal("lock poisoned"))?
+            .clone(),
+        ollama_host: ollama_default_host(),
+        mlx: adapters::mlx::RunOptions::from_env(),
+    })
+}
+
 fn push_role_pin(
     out: &mut Vec<ModelPin>,
     by_id: &BTreeMap<String, ModelProfile>,
@@ -233,6 +559,119 @@ fn push_role_pin(
     }
 }
 
+/// Maps one `/api/pull` status line to job progress: layer downloads report
+/// bytes, the other phases report their stage only.
+fn pull_job_progress(progress: &adapters::ollama::PullProgress) -> JobProgress {
+    let (done, total) = (progress.completed.unwrap_or(0), progress.total.unwrap_or(0));
+    let (stage, pct) = match progress.status.as_str() {
+        "success" => ("success", 100),
+        "pulling manifest" => ("manifest", 0),
+        s if s.starts_with("verifying") => ("verify", 99),
+        s if s.starts_with("writing") || s.starts_with("removing") => ("finalize", 99),
+        _ if total > 0 => ("download", (done.min(total) * 99 / total) as u8),
+        _ => ("download", 0),
+    };
+    JobProgress {
+        stage: stage.to_string(),
+        pct,
+        counters: JobCounters { done, total },
+    }
+}
+
+fn ollama_err(e: adapters::ollama::OllamaError) -> AppError {
+    use adapters::ollama::OllamaError;
+    let (code, action_hint) = match &e {
+        OllamaError::Blocked(err) => return err.clone(),
+        OllamaError::InvalidHost(_) => (AppErrorCode::ValidationFailed, None),
+        OllamaError::Unreachable { .. } => (
+            AppErrorCode::Io,
+            Some("Start Ollama (`ollama serve`) or check the host"),
+        ),
+        OllamaError::Timeout { .. } => (
+            AppErrorCode::Io,
+            Some("Retry, or raise OPSCINEMA_OLLAMA_TIMEOUT_MS for slow models"),
+        ),
+        OllamaError::ModelNotFound(_) => (
+            AppErrorCode::NotFound,
+            Some("Pull the model before running it"),
+        ),
+        OllamaError::Http { status, .. } if (400..500).contains(status) => {
+            (AppErrorCode::ValidationFailed, None)
+        }
+        OllamaError::Http { .. } | OllamaError::PullFailed(_) => (AppErrorCode::Internal, None),
+        OllamaError::InvalidResponse(_) => (AppErrorCode::ProviderSchemaInvalid, None),
+    };
+    AppError {
+        recoverable: code != AppErrorCode::Internal,
+        code,
+        message: e.to_string(),
+        details: None,
+        action_hint: action_hint.map(ToString::to_string),
+    }
+}
+
+fn mlx_job_progress(progress: adapters::mlx::MlxProgress) -> JobProgress {
+    use adapters::mlx::MlxProgress;
+    match progress {
+        MlxProgress::Loading => JobProgress {
+            stage: "load".to_string(),
+            pct: 0,
+            counters: JobCounters { done: 0, total: 0 },
+        },
+        MlxProgress::Generating { tokens, max_tokens } => JobProgress {
+            stage: "generate".to_string(),
+            pct: (tokens.min(max_tokens) * 99 / max_tokens.max(1)) as u8,
+            counters: JobCounters {
+                done: u64::from(tokens),
+                total: u64::from(max_tokens),
+            },
+        },
+    }
+}
+
+fn mlx_err(e: adapters::mlx::MlxError) -> AppError {
+    use adapters::mlx::MlxError;
+    let (code, action_hint) = match &e {
+        MlxError::Spawn { .. } => (
+            AppErrorCode::Io,
+            Some("Install the MLX runner or set OPSCINEMA_MLX_RUNNER"),
+        ),
+        MlxError::ModelNotFound(_) => (
+            AppErrorCode::NotFound,
+            Some("Download the model weights before running it"),
+        ),
+        MlxError::Timeout(_) => (
+            AppErrorCode::Io,
+            Some("Retry, or raise OPSCINEMA_MLX_TIMEOUT_MS for slow models"),
+        ),
+        MlxError::Exited(_) => (AppErrorCode::Io, None),
+        MlxError::Cancelled => (AppErrorCode::JobCancelled, None),
+        MlxError::Protocol(_) => (AppErrorCode::ProviderSchemaInvalid, None),
+        MlxError::Runner(_) => (AppErrorCode::Internal, None),
+    };
+    AppError {
+        recoverable: code != AppErrorCode::Internal,
+        code,
+        message: e.to_string(),
+        details: None,
+        action_hint: action_hint.map(ToString::to_string),
+    }
+}
+
+fn probe_err(e: verify::ProbeError) -> AppError {
+    match e {
+        verify::ProbeError::Ollama(e) => ollama_err(e),
+        verify::ProbeError::Mlx(e) => mlx_err(e),
+        verify::ProbeError::Unsupported(provider) => AppError {
+            code: AppErrorCode::Unsupported,
+            message: format!("cannot verify digests of {provider} models"),
+            details: None,
+            recoverable: true,
+            action_hint: Some("Register an Ollama or MLX model".to_string()),
+        },
+    }
+}
+
 fn ollama_default_host() -> String {
     std::env::var("OPSCINEMA_OLLAMA_HOST").unwrap_or_else(|_| "127.0.0.1".to_string())
 }
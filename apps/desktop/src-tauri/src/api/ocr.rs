@@ -1,7 +1,7 @@
 use crate::api::Backend;
 use crate::ocr::pipeline::persist_ocr_for_frame;
+use crate::ocr::vision::VisionProvider;
 use crate::platform::macos::screencapturekit::capture;
-use crate::platform::macos::vision_ocr;
 use crate::storage::{repo_jobs, repo_ocr, repo_timeline};
 use opscinema_types::{
     AppError, AppErrorCode, AppResult, JobCounters, JobHandle, JobProgress,
@@ -61,20 +61,25 @@ pub fn ocr_schedule(backend: &Backend, req: OcrScheduleRequest) -> AppResult<Job
         let (frame_event_id, frame) = if let Some(existing) = keyframes.into_iter().next() {
             let bytes = std::fs::read(backend.assets.path_for(&existing.asset.asset_id))
                 .map_err(|e| internal_anyhow(anyhow::anyhow!(e)))?;
+            let dimensions = crate::image::png_dimensions(&bytes);
             (
                 existing.frame_event_id,
                 crate::capture::screen::ScreenKeyframe {
                     frame_ms: existing.frame_ms,
                     display_id: std::env::var("OPSCINEMA_CAPTURE_DISPLAY_ID")
                         .unwrap_or_else(|_| "display.main".to_string()),
-                    pixel_w: std::env::var("OPSCINEMA_CAPTURE_PIXEL_W")
-                        .ok()
-                        .and_then(|v| v.parse::<u32>().ok())
-                        .unwrap_or(1920),
-                    pixel_h: std::env::var("OPSCINEMA_CAPTURE_PIXEL_H")
-                        .ok()
-                        .and_then(|v| v.parse::<u32>().ok())
-                        .unwrap_or(1080),
+                    pixel_w: dimensions.map(|(w, _)| w).unwrap_or_else(|| {
+                        std::env::var("OPSCINEMA_CAPTURE_PIXEL_W")
+                            .ok()
+                            .and_then(|v| v.parse::<u32>().ok())
+                            .unwrap_or(1920)
+                    }),
+                    pixel_h: dimensions.map(|(_, h)| h).unwrap_or_else(|| {
+                        std::env::var("OPSCINEMA_CAPTURE_PIXEL_H")
+                            .ok()
+                            .and_then(|v| v.parse::<u32>().ok())
+                            .unwrap_or(1080)
+                    }),
                     scale_factor: std::env::var("OPSCINEMA_CAPTURE_SCALE")
                         .unwrap_or_else(|_| "2.0".to_string()),
                     png_bytes: bytes,
@@ -89,7 +94,7 @@ pub fn ocr_schedule(backend: &Backend, req: OcrScheduleRequest) -> AppResult<Job
             return Err(cancelled());
         }
 
-        let provider = vision_ocr::provider();
+        let provider = configured_provider(backend)?;
         let _ = repo_jobs::update_job_status(
             &conn,
             job_id,
@@ -198,6 +203,16 @@ fn internal_anyhow(e: anyhow::Error) -> AppError {
     }
 }
 
+/// The OCR engine selected in settings.
+pub(crate) fn configured_provider(backend: &Backend) -> AppResult<Box<dyn VisionProvider>> {
+    let kind = backend
+        .settings
+        .lock()
+        .map(|s| s.ocr_provider)
+        .map_err(|_| internal_anyhow(anyhow::anyhow!("settings lock poisoned")))?;
+    crate::ocr::registry::provider_for(kind).map_err(internal_anyhow)
+}
+
 fn provider_or_internal(e: anyhow::Error) -> AppError {
     let message = e.to_string();
     if message.to_lowercase().contains("provider schema invalid") {
@@ -1,8 +1,10 @@
+use crate::api::exports::{seq_range_through, sign_export};
 use crate::api::steps::steps_list;
 use crate::api::Backend;
 use crate::evidence::coverage;
 use crate::exports::{proof_bundle, runbook};
-use crate::storage::{repo_exports, repo_sessions};
+use crate::storage::event_store::{stream_events, EventFilter};
+use crate::storage::{repo_exports, repo_sessions, repo_verifiers};
 use opscinema_types::{
     AppError, AppErrorCode, AppResult, ExportResult, ExportWarning, ProofExportRequest,
     ProofGetViewRequest, ProofViewResponse, RunbookCreateRequest, RunbookDetail,
@@ -108,29 +110,44 @@ pub fn runbook_update(backend: &Backend, req: RunbookUpdateRequest) -> AppResult
 pub fn runbook_export(backend: &Backend, req: RunbookExportRequest) -> AppResult<ExportResult> {
     let mut conn = backend.storage.conn().map_err(db_err)?;
     let (session_id, detail) = find_runbook(&conn, req.runbook_id).map_err(internal_anyhow)?;
-    let coverage = coverage::evaluate(&detail.steps);
-    let warnings = collect_verifier_warnings(&conn, session_id).map_err(internal_anyhow)?;
+    let refs = coverage::SessionEvidenceRefs::load(&conn, session_id, &detail.steps)
+        .map_err(internal_anyhow)?;
+    let coverage = coverage::evaluate(&detail.steps, &refs);
+    let mut warnings = collect_verifier_warnings(&conn, session_id).map_err(internal_anyhow)?;
     let offline_policy_enforced = backend
         .settings
         .lock()
         .map_err(|_| internal("settings lock poisoned"))?
         .offline_mode;
     let model_pins = crate::api::model_dock::collect_model_pins(backend)?;
+    warnings.extend(crate::api::model_dock::model_pin_warnings(
+        backend,
+        &model_pins,
+    )?);
+    let head_seq = repo_sessions::get_head_seq(&conn, session_id).map_err(internal_anyhow)?;
+    let verifier_runs =
+        repo_verifiers::list_runs_for_session(&conn, session_id).map_err(internal_anyhow)?;
     let export = runbook::export_runbook(
         session_id,
         &detail,
         &warnings,
-        coverage
-            .missing_generated_block_ids
-            .iter()
-            .map(ToString::to_string)
-            .collect(),
-        model_pins,
-        offline_policy_enforced,
+        runbook::RunbookBuildOptions {
+            missing_evidence: coverage
+                .missing_generated_block_ids
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            invalid_evidence_refs: coverage.invalid_refs,
+            model_pins,
+            offline_policy_enforced,
+            source_seq_range: seq_range_through(head_seq),
+            verifier_runs,
+            redactor: Some(crate::api::redaction::session_redactor(&conn, session_id)?),
+        },
         std::path::Path::new(&req.output_dir),
     )
     .map_err(internal_anyhow)?;
-
+    sign_export(backend, &export.output_path)?;
     let manifest_path = std::path::Path::new(&export.output_path).join("manifest.json");
     let manifest_bytes = std::fs::read(&manifest_path).map_err(|e| internal(&e.to_string()))?;
     let manifest_asset_id = backend
@@ -167,36 +184,51 @@ pub fn runbook_export(backend: &Backend, req: RunbookExportRequest) -> AppResult
 }
 
 pub fn proof_export_bundle(backend: &Backend, req: ProofExportRequest) -> AppResult<ExportResult> {
-    let steps = steps_list(
+    let listed = steps_list(
         backend,
         StepsListRequest {
             session_id: req.session_id,
         },
-    )?
-    .steps;
+    )?;
+    let steps = listed.steps;
     let mut conn = backend.storage.conn().map_err(db_err)?;
-    let coverage = coverage::evaluate(&steps);
-    let warnings = collect_verifier_warnings(&conn, req.session_id).map_err(internal_anyhow)?;
+    let refs = coverage::SessionEvidenceRefs::load(&conn, req.session_id, &steps)
+        .map_err(internal_anyhow)?;
+    let coverage = coverage::evaluate(&steps, &refs);
+    let mut warnings = collect_verifier_warnings(&conn, req.session_id).map_err(internal_anyhow)?;
     let offline_policy_enforced = backend
         .settings
         .lock()
         .map_err(|_| internal("settings lock poisoned"))?
         .offline_mode;
     let model_pins = crate::api::model_dock::collect_model_pins(backend)?;
+    warnings.extend(crate::api::model_dock::model_pin_warnings(
+        backend,
+        &model_pins,
+    )?);
     let export = proof_bundle::export_proof_bundle(
         req.session_id,
         &steps,
         &warnings,
-        coverage
-            .missing_generated_block_ids
-            .iter()
-            .map(ToString::to_string)
-            .collect(),
-        model_pins,
-        offline_policy_enforced,
+        proof_bundle::ProofBundleBuildOptions {
+            missing_evidence: coverage
+                .missing_generated_block_ids
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            invalid_evidence_refs: coverage.invalid_refs,
+            model_pins,
+            offline_policy_enforced,
+            source_seq_range: seq_range_through(listed.head_seq),
+            redactor: Some(crate::api::redaction::session_redactor(
+                &conn,
+                req.session_id,
+            )?),
+        },
         std::path::Path::new(&req.output_dir),
     )
     .map_err(internal_anyhow)?;
+    sign_export(backend, &export.output_path)?;
     let manifest_path = std::path::Path::new(&export.output_path).join("manifest.json");
     let manifest_bytes = std::fs::read(&manifest_path).map_err(|e| internal(&e.to_string()))?;
     let manifest_asset_id = backend
@@ -242,12 +274,13 @@ fn collect_verifier_warnings(
     conn: &crate::storage::DbConn,
     session_id: Uuid,
 ) -> anyhow::Result<Vec<ExportWarning>> {
-    let events = crate::storage::event_store::query_events(conn, session_id, None, 100_000)?;
     let mut warnings = Vec::new();
-    for event in events {
-        if event.event_type != "VerifierRunCompleted" {
-            continue;
-        }
+    for event in stream_events(
+        conn,
+        session_id,
+        EventFilter::types(&["VerifierRunCompleted"]),
+    ) {
+        let event = event?;
         let payload: VerifierRunCompletedPayload = serde_json::from_str(&event.payload_canon_json)?;
         if payload.status.eq_ignore_ascii_case("SUCCEEDED") {
             continue;
@@ -267,10 +300,11 @@ fn replay_runbooks_for_session(
     conn: &crate::storage::DbConn,
     session_id: Uuid,
 ) -> anyhow::Result<BTreeMap<Uuid, RunbookDetail>> {
-    let events = crate::storage::event_store::query_events(conn, session_id, None, 100_000)?;
     let mut runbooks = BTreeMap::<Uuid, RunbookDetail>::new();
+    let filter = EventFilter::types(&["RunbookCreated", "RunbookUpdated"]);
 
-    for event in events {
+    for event in stream_events(conn, session_id, filter) {
+        let event = event?;
         match event.event_type.as_str() {
             "RunbookCreated" => {
                 let payload: RunbookCreatedPayload =
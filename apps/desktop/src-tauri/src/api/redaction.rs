@@ -0,0 +1,133 @@
+use crate::api::Backend;
+use crate::policy::redaction::{
+    default_rules, RedactionRulesSetPayload, Redactor, RULES_SET_EVENT,
+};
+use crate::storage::event_store::{append_event, stream_events, EventFilter};
+use crate::storage::repo_sessions;
+use opscinema_types::{
+    AppError, AppErrorCode, AppResult, RedactionRule, RedactionRules, RedactionRulesGetRequest,
+    RedactionRulesSetRequest,
+};
+use uuid::Uuid;
+
+pub fn redaction_rules_get(
+    backend: &Backend,
+    req: RedactionRulesGetRequest,
+) -> AppResult<RedactionRules> {
+    let conn = backend.storage.conn().map_err(db_err)?;
+    ensure_session(&conn, req.session_id)?;
+    let (rules, is_default) = session_rules(&conn, req.session_id).map_err(internal_anyhow)?;
+    Ok(RedactionRules {
+        session_id: req.session_id,
+        rules,
+        is_default,
+    })
+}
+
+/// Replaces the session's rules. An empty list turns redaction off for the
+/// session; it does not restore the defaults.
+pub fn redaction_rules_set(
+    backend: &Backend,
+    req: RedactionRulesSetRequest,
+) -> AppResult<RedactionRules> {
+    for rule in &req.rules {
+        if let RedactionRule::Region { bbox_norm, .. } = rule {
+            if bbox_norm.w == 0
+                || bbox_norm.h == 0
+                || bbox_norm.x.saturating_add(bbox_norm.w) > 10_000
+                || bbox_norm.y.saturating_add(bbox_norm.h) > 10_000
+            {
+                return Err(validation("region must be a non-empty box inside 0..10000"));
+            }
+        }
+    }
+    Redactor::compile(&req.rules).map_err(|e| validation(&e.to_string()))?;
+
+    let mut conn = backend.storage.conn().map_err(db_err)?;
+    ensure_session(&conn, req.session_id)?;
+    append_event(
+        &mut conn,
+        req.session_id,
+        RULES_SET_EVENT,
+        &RedactionRulesSetPayload {
+            rules: req.rules.clone(),
+        },
+        None,
+    )
+    .map_err(internal_anyhow)?;
+    Ok(RedactionRules {
+        session_id: req.session_id,
+        rules: req.rules,
+        is_default: false,
+    })
+}
+
+/// Rules in force for a session: the latest `RedactionRulesSet`, else the
+/// defaults (flagged by the returned bool).
+pub(crate) fn session_rules(
+    conn: &crate::storage::DbConn,
+    session_id: Uuid,
+) -> anyhow::Result<(Vec<RedactionRule>, bool)> {
+    let mut latest = None;
+    for event in stream_events(conn, session_id, EventFilter::types(&[RULES_SET_EVENT])) {
+        let event = event?;
+        let payload: RedactionRulesSetPayload = serde_json::from_str(&event.payload_canon_json)?;
+        latest = Some(payload.rules);
+    }
+    Ok(match latest {
+        Some(rules) => (rules, false),
+        None => (default_rules(), true),
+    })
+}
+
+/// Compiled rules every exporter scrubs the session's bundle with.
+pub(crate) fn session_redactor(
+    conn: &crate::storage::DbConn,
+    session_id: Uuid,
+) -> AppResult<Redactor> {
+    let (rules, _) = session_rules(conn, session_id).map_err(internal_anyhow)?;
+    Redactor::compile(&rules).map_err(internal_anyhow)
+}
+
+fn ensure_session(conn: &crate::storage::DbConn, session_id: Uuid) -> AppResult<()> {
+    repo_sessions::get_session(conn, session_id)
+        .map_err(internal_anyhow)?
+        .map(|_| ())
+        .ok_or_else(|| AppError {
+            code: AppErrorCode::NotFound,
+            message: "session not found".to_string(),
+            details: Some(session_id.to_string()),
+            recoverable: true,
+            action_hint: None,
+        })
+}
+
+fn validation(message: &str) -> AppError {
+    AppError {
+        code: AppErrorCode::ValidationFailed,
+        message: message.to_string(),
+        details: None,
+        recoverable: true,
+        action_hint: Some("Fix the redaction rule and save again".to_string()),
+    }
+}
+
+fn db_err<E: std::fmt::Display>(e: E) -> AppError {
+    AppError {
+        code: AppErrorCode::Db,
+        message: "database error".to_string(),
+        details: Some(e.to_string()),
+        recoverable: false,
+        action_hint: None,
+    }
+}
+
+fn internal_anyhow(e: anyhow::Error) -> AppError {
+    AppError {
+        code: AppErrorCode::Internal,
+        message: e.to_string(),
+        details: None,
+        recoverable: false,
+        action_hint: None,
+    }
+}
@@ -0,0 +1,306 @@
+use crate::api::Backend;
+use crate::ocr::index::{parse_query, OcrQuery};
+use crate::storage::event_store::{stream_events, EventFilter};
+use crate::storage::{index_fts, repo_sessions};
+use opscinema_types::{
+    AppError, AppErrorCode, AppResult, GlobalSearchHit, GlobalSearchHitKind,
+    GlobalSearchSessionGroup, OcrSearchHit, SearchGlobalRequest, SearchGlobalResponse,
+    SessionDetail, TextOffset,
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+const DEFAULT_PAGE_SIZE: u32 = 20;
+const MAX_PAGE_SIZE: u32 = 100;
+/// Hits returned per session group; `hit_count` still reports the full total.
+const HITS_PER_SESSION: usize = 20;
+/// OCR hits considered across all sessions before grouping.
+const OCR_HIT_LIMIT: u32 = 5_000;
+const SNIPPET_CHARS: usize = 160;
+const SNIPPET_LEAD_CHARS: usize = 40;
+
+#[derive(Debug, Deserialize)]
+struct WindowMetaCaptured {
+    frame_ms: i64,
+    frontmost_bundle_id: Option<String>,
+    frontmost_title: Option<String>,
+}
+
+struct WindowChange {
+    event_id: String,
+    frame_ms: i64,
+    bundle_id: Option<String>,
+    title: Option<String>,
+}
+
+/// Searches OCR text, step titles and bodies, window titles and session
+/// labels/metadata across every session. Groups are ordered like
+/// `session_list` (newest first) and paginated by session.
+pub fn search_global(
+    backend: &Backend,
+    req: SearchGlobalRequest,
+) -> AppResult<SearchGlobalResponse> {
+    if let (Some(after), Some(before)) = (req.created_after, req.created_before) {
+        if after > before {
+            return Err(AppError {
+                code: AppErrorCode::ValidationFailed,
+                message: "created_after must not be later than created_before".to_string(),
+                details: None,
+                recoverable: true,
+                action_hint: Some("Swap or clear the date range".to_string()),
+            });
+        }
+    }
+    let Some(query) = parse_query(&req.query) else {
+        return Ok(SearchGlobalResponse {
+            groups: vec![],
+            total_sessions: 0,
+            next_offset: None,
+        });
+    };
+    let bundle_filter = req
+        .bundle_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|b| !b.is_empty());
+
+    let conn = backend.storage.conn().map_err(db_err)?;
+    let mut ocr_hits: BTreeMap<Uuid, Vec<OcrSearchHit>> = BTreeMap::new();
+    for (session_id, hit) in
+        index_fts::search_all_sessions(&conn, &query, OCR_HIT_LIMIT).map_err(internal_anyhow)?
+    {
+        ocr_hits.entry(session_id).or_default().push(hit);
+    }
+
+    let mut groups = Vec::new();
+    for detail in repo_sessions::list_session_details(&conn).map_err(internal_anyhow)? {
+        let created_at = detail.summary.created_at;
+        if req.created_after.is_some_and(|t| created_at < t)
+            || req.created_before.is_some_and(|t| created_at > t)
+        {
+            continue;
+        }
+        let session_id = detail.summary.session_id;
+        let windows = window_changes(&conn, session_id).map_err(internal_anyhow)?;
+        if let Some(bundle) = bundle_filter {
+            if !windows
+                .iter()
+                .any(|w| bundle_matches(w.bundle_id.as_deref(), bundle))
+            {
+                continue;
+            }
+        }
+
+        let steps = crate::steps::replay::replay_session_steps(&conn, session_id)
+            .map_err(internal_anyhow)?;
+        let mut hits = text_hits(&query, &detail, &steps, &windows);
+        hits.extend(
+            ocr_hits
+                .remove(&session_id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|hit| GlobalSearchHit {
+                    kind: GlobalSearchHitKind::OcrText,
+                    ref_id: Some(hit.block_id),
+                    frame_ms: Some(hit.frame_ms),
+                    bundle_id: frontmost_at(&windows, hit.frame_ms),
+                    snippet: hit.snippet,
+                    highlights: hit.highlights,
+                }),
+        );
+        // Timed hits must fall while the filtered app was frontmost.
+        hits.retain(|hit| {
+            bundle_filter.is_none_or(|bundle| {
+                hit.frame_ms.is_none() || bundle_matches(hit.bundle_id.as_deref(), bundle)
+            })
+        });
+        if hits.is_empty() {
+            continue;
+        }
+        hits.sort_by_key(|hit| hit.kind);
+        let hit_count = hits.len() as u32;
+        hits.truncate(HITS_PER_SESSION);
+        groups.push(GlobalSearchSessionGroup {
+            session: detail.summary,
+            hit_count,
+            hits,
+        });
+    }
+
+    let total_sessions = groups.len() as u32;
+    let offset = req.offset.unwrap_or(0);
+    let limit = req
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let groups = groups
+        .into_iter()
+        .skip(offset as usize)
+        .take(limit as usize)
+        .collect::<Vec<_>>();
+    let next_offset = offset
+        .checked_add(limit)
+        .filter(|next| *next < total_sessions);
+    Ok(SearchGlobalResponse {
+        groups,
+        total_sessions,
+        next_offset,
+    })
+}
+
+fn text_hits(
+    query: &OcrQuery,
+    detail: &SessionDetail,
+    steps: &[opscinema_types::Step],
+    windows: &[WindowChange],
+) -> Vec<GlobalSearchHit> {
+    let untimed = |kind, ref_id: Option<String>, text: &str| {
+        excerpt(query, text).map(|(snippet, highlights)| GlobalSearchHit {
+            kind,
+            ref_id,
+            frame_ms: None,
+            bundle_id: None,
+            snippet,
+            highlights,
+        })
+    };
+    let mut hits = Vec::new();
+    hits.extend(untimed(
+        GlobalSearchHitKind::SessionLabel,
+        None,
+        &detail.summary.label,
+    ));
+    for (key, value) in &detail.metadata {
+        hits.extend(untimed(
+            GlobalSearchHitKind::SessionMetadata,
+            Some(key.clone()),
+            &format!("{key}: {value}"),
+        ));
+    }
+    for step in steps {
+        let step_id = Some(step.step_id.to_string());
+        hits.extend(untimed(
+            GlobalSearchHitKind::StepTitle,
+            step_id.clone(),
+            &step.title,
+        ));
+        for block in &step.body.blocks {
+            hits.extend(untimed(
+                GlobalSearchHitKind::StepBody,
+                step_id.clone(),
+                &block.text,
+            ));
+        }
+    }
+    // A title stays on screen across many captures; report it once per switch.
+    let mut previous: Option<(&Option<String>, &Option<String>)> = None;
+    for window in windows {
+        let current = (&window.bundle_id, &window.title);
+        if previous == Some(current) {
+            continue;
+        }
+        previous = Some(current);
+        let Some(title) = &window.title else {
+            continue;
+        };
+        if let Some((snippet, highlights)) = excerpt(query, title) {
+            hits.push(GlobalSearchHit {
+                kind: GlobalSearchHitKind::WindowTitle,
+                ref_id: Some(window.event_id.clone()),
+                frame_ms: Some(window.frame_ms),
+                bundle_id: window.bundle_id.clone(),
+                snippet,
+                highlights,
+            });
+        }
+    }
+    hits
+}
+
+/// Window metadata in capture order.
+fn window_changes(
+    conn: &crate::storage::DbConn,
+    session_id: Uuid,
+) -> anyhow::Result<Vec<WindowChange>> {
+    let mut out = Vec::new();
+    for event in stream_events(
+        conn,
+        session_id,
+        EventFilter::types(&["WindowMetaCaptured"]),
+    ) {
+        let event = event?;
+        let payload: WindowMetaCaptured = serde_json::from_str(&event.payload_canon_json)?;
+        out.push(WindowChange {
+            event_id: event.event_id,
+            frame_ms: payload.frame_ms,
+            bundle_id: payload.frontmost_bundle_id,
+            title: payload.frontmost_title,
+        });
+    }
+    out.sort_by_key(|w| w.frame_ms);
+    Ok(out)
+}
+
+fn frontmost_at(windows: &[WindowChange], frame_ms: i64) -> Option<String> {
+    windows
+        .iter()
+        .take_while(|w| w.frame_ms <= frame_ms)
+        .last()
+        .and_then(|w| w.bundle_id.clone())
+}
+
+fn bundle_matches(bundle_id: Option<&str>, filter: &str) -> bool {
+    bundle_id.is_some_and(|b| b.eq_ignore_ascii_case(filter))
+}
+
+/// The matching text, cut to a window that opens shortly before the first
+/// match when it is too long to show whole.
+fn excerpt(query: &OcrQuery, text: &str) -> Option<(String, Vec<TextOffset>)> {
+    let highlights = query.highlights(text);
+    let first = highlights.first()?.start as usize;
+    let chars = text.chars().collect::<Vec<_>>();
+    if chars.len() <= SNIPPET_CHARS {
+        return Some((text.to_string(), highlights));
+    }
+    let start = first.saturating_sub(SNIPPET_LEAD_CHARS);
+    let end = (start + SNIPPET_CHARS).min(chars.len());
+    let lead = usize::from(start > 0);
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+    snippet.extend(&chars[start..end]);
+    if end < chars.len() {
+        snippet.push('…');
+    }
+    let highlights = highlights
+        .into_iter()
+        .filter(|h| (h.start as usize) < end)
+        .map(|h| TextOffset {
+            start: (h.start as usize - start + lead) as u32,
+            end: ((h.end as usize).min(end) - start + lead) as u32,
+        })
+        .collect();
+    Some((snippet, highlights))
+}
+
+fn db_err<E: std::fmt::Display>(e: E) -> AppError {
+    AppError {
+        code: AppErrorCode::Db,
+        message: "database error".to_string(),
+        details: Some(e.to_string()),
+        recoverable: false,
+        action_hint: None,
+    }
+}
+
+fn internal_anyhow(e: anyhow::Error) -> AppError {
+    AppError {
+        code: AppErrorCode::Internal,
+        message: e.to_string(),
+        details: None,
+        recoverable: false,
+        action_hint: None,
+    }
+}
@@ -1,13 +1,17 @@
 use crate::api::Backend;
+use crate::exports::session_archive;
 use crate::storage::repo_sessions;
+use opscinema_export_manifest::SessionArchiveManifestV1;
 use opscinema_types::{
-    AppError, AppErrorCode, AppResult, SessionCloseRequest, SessionCreateRequest, SessionDetail,
-    SessionGetRequest, SessionListRequest, SessionSummary,
+    AppError, AppErrorCode, AppResult, SessionArchiveResult, SessionCloseRequest,
+    SessionCreateRequest, SessionDetail, SessionExportArchiveRequest, SessionGetRequest,
+    SessionImportArchiveRequest, SessionListRequest, SessionSummary,
 };
+use std::path::Path;
 
 pub fn session_create(backend: &Backend, req: SessionCreateRequest) -> AppResult<SessionSummary> {
     let conn = backend.storage.conn().map_err(db_err)?;
-    repo_sessions::create_session(&conn, &req.label).map_err(internal_anyhow)
+    repo_sessions::create_session(&conn, &req.label, &req.metadata).map_err(internal_anyhow)
 }
 
 pub fn session_list(backend: &Backend, req: SessionListRequest) -> AppResult<Vec<SessionSummary>> {
@@ -38,6 +42,81 @@ pub fn session_close(backend: &Backend, req: SessionCloseRequest) -> AppResult<S
     .map(|d| d.summary)
 }
 
+pub fn session_export_archive(
+    backend: &Backend,
+    req: SessionExportArchiveRequest,
+) -> AppResult<SessionArchiveResult> {
+    let conn = backend.storage.conn().map_err(db_err)?;
+    if repo_sessions::get_session(&conn, req.session_id)
+        .map_err(internal_anyhow)?
+        .is_none()
+    {
+        return Err(not_found("session not found"));
+    }
+    let output = Path::new(&req.output_dir);
+    let manifest =
+        session_archive::export_session_archive(&conn, &backend.assets, req.session_id, output)
+            .map_err(internal_anyhow)?;
+    archive_result(&req.output_dir, &manifest)
+}
+
+pub fn session_import_archive(
+    backend: &Backend,
+    req: SessionImportArchiveRequest,
+) -> AppResult<SessionArchiveResult> {
+    let archive_dir = Path::new(&req.archive_path);
+    let manifest = session_archive::read_archive_manifest(archive_dir)
+        .map_err(|e| validation_failed("session archive manifest is invalid", e))?;
+    let mut conn = backend.storage.conn().map_err(db_err)?;
+    let session_id = uuid::Uuid::parse_str(&manifest.session_id)
+        .map_err(|e| validation_failed("session archive manifest is invalid", e.into()))?;
+    if repo_sessions::get_session(&conn, session_id)
+        .map_err(internal_anyhow)?
+        .is_some()
+    {
+        return Err(AppError {
+            code: AppErrorCode::Conflict,
+            message: "session already exists".to_string(),
+            details: Some(session_id.to_string()),
+            recoverable: true,
+            action_hint: None,
+        });
+    }
+    let manifest = session_archive::import_session_archive(&mut conn, &backend.assets, archive_dir)
+        .map_err(|e| validation_failed("session archive failed verification", e))?;
+    archive_result(&req.archive_path, &manifest)
+}
+
+fn archive_result(
+    output_path: &str,
+    manifest: &SessionArchiveManifestV1,
+) -> AppResult<SessionArchiveResult> {
+    Ok(SessionArchiveResult {
+        session_id: uuid::Uuid::parse_str(&manifest.session_id)
+            .map_err(|e| internal(&e.to_string()))?,
+        output_path: output_path.to_string(),
+        event_count: manifest.event_count,
+        asset_count: manifest
+            .files
+            .iter()
+            .filter(|f| f.path.starts_with(session_archive::ARCHIVE_ASSETS_DIR))
+            .count() as u64,
+        head_seq: manifest.head_seq,
+        head_hash: manifest.head_hash.clone(),
+        archive_hash: manifest.archive_hash.clone(),
+    })
+}
+
+fn validation_failed(msg: &str, e: anyhow::Error) -> AppError {
+    AppError {
+        code: AppErrorCode::ValidationFailed,
+        message: msg.to_string(),
+        details: Some(e.to_string()),
+        recoverable: false,
+        action_hint: None,
+    }
+}
+
 fn db_err<E: std::fmt::Display>(e: E) -> AppError {
     AppError {
         code: AppErrorCode::Db,
@@ -1,12 +1,15 @@
 use crate::api::exports::tutorial_export_pack as export_tutorial_pack;
-use crate::api::steps::steps_list;
+use crate::api::steps::{steps_apply_edit, steps_list};
 use crate::api::Backend;
-use crate::policy::export_gate::{tutorial_pack_gate, ExportGateInput};
-use crate::storage::{repo_jobs, repo_timeline};
+use crate::policy::export_gate::{blocks_strict_gate, tutorial_pack_gate, ExportGateInput};
+use crate::policy::provider_schema::{ScreenExplanationOutput, StepNarrativeOutput};
+use crate::steps::narrate::{self, RejectedBlock};
+use crate::storage::event_store::{stream_events, EventFilter};
+use crate::storage::{repo_jobs, repo_models, repo_timeline, DbConn};
 use opscinema_types::{
     AppError, AppErrorCode, AppResult, ExplainThisScreenRequest, ExportResult, JobHandle,
-    StepsListRequest, TutorialExportRequest, TutorialGenerateRequest,
-    TutorialValidateExportRequest, TutorialValidateExportResponse,
+    StepEditOp, StepsApplyEditRequest, StepsListRequest, TutorialExportRequest,
+    TutorialGenerateRequest, TutorialValidateExportRequest, TutorialValidateExportResponse,
 };
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -16,6 +19,10 @@ struct TutorialGeneratedPayload {
     step_count: usize,
     missing_generated_block_ids: Vec<String>,
     narrative_preview: Option<String>,
+    /// Model that narrated the steps; `None` when no tutorial model is assigned.
+    narration_model_id: Option<String>,
+    narrated_step_ids: Vec<Uuid>,
+    rejected_blocks: Vec<RejectedBlock>,
 }
 
 #[derive(Debug, Serialize)]
@@ -51,13 +58,26 @@ pub fn tutorial_generate(backend: &Backend, req: TutorialGenerateRequest) -> App
                 },
             )?;
         }
+        let narration_model_id = crate::model_dock::roles::get(&conn)
+            .map_err(internal_anyhow)?
+            .tutorial_generation;
+        let (narrated_step_ids, rejected_blocks) = match &narration_model_id {
+            Some(model_id) => narrate_steps(backend, &mut conn, req.session_id, model_id)?,
+            None => (vec![], vec![]),
+        };
         let after = steps_list(
             backend,
             StepsListRequest {
                 session_id: req.session_id,
             },
         )?;
-        let coverage = crate::evidence::coverage::evaluate(&after.steps);
+        let refs = crate::evidence::coverage::SessionEvidenceRefs::load(
+            &conn,
+            req.session_id,
+            &after.steps,
+        )
+        .map_err(internal_anyhow)?;
+        let coverage = crate::evidence::coverage::evaluate(&after.steps, &refs);
         crate::storage::event_store::append_event(
             &mut conn,
             req.session_id,
@@ -71,6 +91,9 @@ pub fn tutorial_generate(backend: &Backend, req: TutorialGenerateRequest) -> App
                         .first()
                         .map(|b| b.text.chars().take(96).collect::<String>())
                 }),
+                narration_model_id,
+                narrated_step_ids,
+                rejected_blocks,
             },
             None,
         )
@@ -102,6 +125,87 @@ pub fn tutorial_generate(backend: &Backend, req: TutorialGenerateRequest) -> App
     }
 }
 
+/// Rewrites each step's body with `model_id`, prompting from the evidence the
+/// step cites. Accepted narratives are applied as `ReplaceBody` edits; a step
+/// whose blocks are all rejected keeps its body. Each accepted block is
+/// recorded against the digest the model is installed at, probed once
+/// before the first prompt.
+fn narrate_steps(
+    backend: &Backend,
+    conn: &mut DbConn,
+    session_id: Uuid,
+    model_id: &str,
+) -> AppResult<(Vec<Uuid>, Vec<RejectedBlock>)> {
+    let listed = steps_list(backend, StepsListRequest { session_id })?;
+    let evidence =
+        crate::evidence::graph::derive_from_event_log(conn, session_id).map_err(internal_anyhow)?;
+    let refs =
+        crate::evidence::coverage::SessionEvidenceRefs::load(conn, session_id, &listed.steps)
+            .map_err(internal_anyhow)?;
+    let digest = crate::api::model_dock::installed_model_digest(backend, model_id)?;
+    let mut narrated = Vec::new();
+    let mut rejected = Vec::new();
+    for step in &listed.steps {
+        let offered = narrate::prompt_evidence(step, &evidence, &refs);
+        if offered.is_empty() {
+            continue;
+        }
+        let prompt = narrate::build_prompt(step, &offered);
+        let output = crate::api::model_dock::validated_provider_output::<StepNarrativeOutput>(
+            backend,
+            conn,
+            session_id,
+            |hint| {
+                let prompt = match hint {
+                    Some(hint) => format!("{prompt}\n\n{hint}"),
+                    None => prompt.clone(),
+                };
+                crate::api::model_dock::generate_with_model(backend, model_id, &prompt)
+                    .map_err(anyhow::Error::new)
+            },
+        )?;
+        let offered_ids = offered.iter().map(|e| e.evidence_id).collect();
+        let (body, step_rejected) = narrate::narrated_body(step.step_id, &output, &offered_ids);
+        rejected.extend(step_rejected);
+        if body.blocks.is_empty() {
+            continue;
+        }
+        // Rejected replies append events, so the head is read per edit.
+        let base_seq = crate::storage::repo_sessions::get_head_seq(conn, session_id)
+            .map_err(internal_anyhow)?;
+        let block_ids = body
+            .blocks
+            .iter()
+            .map(|b| b.block_id.clone())
+            .collect::<Vec<_>>();
+        let applied = steps_apply_edit(
+            backend,
+            StepsApplyEditRequest {
+                session_id,
+                base_seq,
+                op: StepEditOp::ReplaceBody {
+                    step_id: step.step_id,
+                    body,
+                },
+            },
+        )?;
+        let history = block_ids
+            .into_iter()
+            .map(|block_id| repo_models::GeneratedBlockModel {
+                step_id: step.step_id,
+                block_id,
+                edit_seq: applied.head_seq,
+                role: "tutorial_generation".to_string(),
+                model_id: model_id.to_string(),
+                digest: digest.clone(),
+            })
+            .collect::<Vec<_>>();
+        repo_models::record_block_models(conn, session_id, &history).map_err(internal_anyhow)?;
+        narrated.push(step.step_id);
+    }
+    Ok((narrated, rejected))
+}
+
 pub fn tutorial_export_pack(
     backend: &Backend,
     req: TutorialExportRequest,
@@ -136,9 +240,19 @@ pub fn tutorial_validate_export(
     )?
     .steps;
 
-    let coverage = crate::evidence::coverage::evaluate(&steps);
     let conn = backend.storage.conn().map_err(db_err)?;
-    let warnings = collect_tutorial_warnings(&conn, req.session_id).map_err(internal_anyhow)?;
+    let refs = crate::evidence::coverage::SessionEvidenceRefs::load(&conn, req.session_id, &steps)
+        .map_err(internal_anyhow)?;
+    let coverage = crate::evidence::coverage::evaluate(&steps, &refs);
+    let grounding =
+        crate::evidence::grounding::load(&conn, req.session_id, &steps).map_err(internal_anyhow)?;
+    let mut warnings = collect_tutorial_warnings(&conn, req.session_id).map_err(internal_anyhow)?;
+    warnings.extend(crate::evidence::grounding::warnings(&grounding));
+    let model_pins = crate::api::model_dock::collect_model_pins(backend)?;
+    warnings.extend(crate::api::model_dock::model_pin_warnings(
+        backend,
+        &model_pins,
+    )?);
     let degraded_anchor_ids = crate::anchors::cache::replay_session(&conn, req.session_id)
         .map_err(internal_anyhow)?
         .into_iter()
@@ -153,6 +267,7 @@ pub fn tutorial_validate_export(
             .iter()
             .map(ToString::to_string)
             .collect(),
+        invalid_evidence_refs: coverage.invalid_refs.clone(),
         degraded_anchor_ids: degraded_anchor_ids.clone(),
         warnings: warnings.clone(),
     });
@@ -179,10 +294,14 @@ pub fn tutorial_validate_export(
                     degraded_anchor_ids.join(",")
                 ));
             }
-            if !warnings.is_empty() {
+            let blocking = warnings
+                .iter()
+                .filter(|w| blocks_strict_gate(w))
+                .collect::<Vec<_>>();
+            if !blocking.is_empty() {
                 reasons.push(format!(
                     "warnings present: {}",
-                    warnings
+                    blocking
                         .iter()
                         .map(|w| format!("{}={}", w.code, w.message))
                         .collect::<Vec<_>>()
@@ -202,7 +321,7 @@ pub fn explain_this_screen(
     req: ExplainThisScreenRequest,
 ) -> AppResult<JobHandle> {
     let mut conn = backend.storage.conn().map_err(db_err)?;
-    let asset_id = repo_timeline::get_thumbnail_asset(&conn, req.session_id, req.frame_event_id)
+    let asset_id = repo_timeline::get_keyframe_assets(&conn, req.session_id, req.frame_event_id)
         .map_err(internal_anyhow)?
         .ok_or_else(|| AppError {
             code: AppErrorCode::NotFound,
@@ -210,7 +329,8 @@ pub fn explain_this_screen(
             details: None,
             recoverable: true,
             action_hint: Some("Select a captured frame".to_string()),
-        })?;
+        })?
+        .asset_id;
     let job_id = repo_jobs::create_job(&conn, "explain_this_screen", Some(req.session_id))
         .map_err(internal_anyhow)?;
     let _ = repo_jobs::update_job_status(
@@ -223,17 +343,19 @@ pub fn explain_this_screen(
     let run = (|| -> AppResult<()> {
         let bytes = std::fs::read(backend.assets.path_for(&asset_id))
             .map_err(|e| internal(&e.to_string()))?;
-        let provider = crate::platform::macos::vision_ocr::provider();
+        let provider = crate::api::ocr::configured_provider(backend)?;
         let blocks = provider.recognize(&bytes).map_err(internal_anyhow)?;
         let summary = if let Some(first) = blocks.first() {
             format!("Detected screen text: {}", first.text)
         } else {
             "No OCR text detected in selected frame".to_string()
         };
-        let summary_json = crate::util::canon_json::to_canonical_json(
-            &serde_json::json!({ "frame_event_id": req.frame_event_id, "summary": summary }),
-        )
-        .map_err(|e| internal(&e.to_string()))?;
+        let explanation = ScreenExplanationOutput {
+            frame_event_id: req.frame_event_id,
+            summary,
+        };
+        let summary_json = crate::util::canon_json::to_canonical_json(&explanation)
+            .map_err(|e| internal(&e.to_string()))?;
         let summary_asset_id = backend
             .assets
             .put(&conn, summary_json.as_bytes(), None)
@@ -297,12 +419,13 @@ fn collect_tutorial_warnings(
     conn: &crate::storage::DbConn,
     session_id: Uuid,
 ) -> anyhow::Result<Vec<opscinema_types::ExportWarning>> {
-    let events = crate::storage::event_store::query_events(conn, session_id, None, 100_000)?;
     let mut warnings = Vec::new();
-    for event in events {
-        if event.event_type != "VerifierRunCompleted" {
-            continue;
-        }
+    for event in stream_events(
+        conn,
+        session_id,
+        EventFilter::types(&["VerifierRunCompleted"]),
+    ) {
+        let event = event?;
         let payload: VerifierRunCompletedPayload = serde_json::from_str(&event.payload_canon_json)?;
         if payload.status.eq_ignore_ascii_case("SUCCEEDED") {
             continue;
@@ -1,7 +1,7 @@
 use crate::api::Backend;
 use crate::steps::derive::StepsCandidatesGeneratedPayload;
-use crate::steps::{edit_ops, validate};
-use crate::storage::{repo_jobs, repo_ocr, repo_sessions};
+use crate::steps::{edit_ops, segment, validate};
+use crate::storage::{repo_jobs, repo_sessions, repo_timeline};
 use opscinema_types::{
     AppError, AppErrorCode, AppResult, JobHandle, Step, StepDetail, StepId, StepModel,
     StepsApplyEditRequest, StepsApplyEditResponse, StepsGenerateCandidatesRequest, StepsGetRequest,
@@ -24,61 +24,61 @@ pub fn steps_generate_candidates(
     let deterministic = std::env::var("OPSCINEMA_DETERMINISTIC_IDS")
         .map(|v| v == "1")
         .unwrap_or(false);
-    let step_id = if deterministic {
-        Uuid::new_v5(
-            &Uuid::NAMESPACE_URL,
-            format!("{}:step:0", req.session_id).as_bytes(),
-        )
-    } else {
-        Uuid::new_v4()
+    let step_id_for = |idx: usize| {
+        if deterministic {
+            Uuid::new_v5(
+                &Uuid::NAMESPACE_URL,
+                format!("{}:step:{idx}", req.session_id).as_bytes(),
+            )
+        } else {
+            Uuid::new_v4()
+        }
     };
     let mut conn = backend.storage.conn().map_err(db_err)?;
-    let mut narrative_text = "Navigate to the target screen".to_string();
-    let mut title = "Open target screen".to_string();
-    let mut evidence_id = crate::util::ids::deterministic_evidence_id(
-        req.session_id,
-        "GeneratedStepBlock",
-        &format!("{step_id}:b1"),
-    );
-    if let Some((block, _frame_ms)) = repo_ocr::list_blocks_by_session(&conn, req.session_id)
-        .map_err(internal_anyhow)?
-        .into_iter()
-        .next()
+    let evidence = crate::evidence::graph::derive_from_event_log(&conn, req.session_id)
+        .map_err(internal_anyhow)?;
+    let mut frame_hashes =
+        repo_timeline::keyframe_dhashes(&conn, req.session_id).map_err(internal_anyhow)?;
+    for asset_id in evidence
+        .evidence
+        .iter()
+        .filter(|e| e.kind == "FrameKeyframe")
+        .flat_map(|e| e.locators.iter().filter_map(|l| l.asset_id.clone()))
     {
-        let snippet = block.text.chars().take(72).collect::<String>();
-        if !snippet.trim().is_empty() {
-            narrative_text = format!(
-                "Open the target screen and verify the visible text contains: \"{}\"",
-                snippet
-            );
-            title = "Open screen and verify key text".to_string();
-            evidence_id = crate::util::ids::deterministic_evidence_id(
-                req.session_id,
-                "OcrSpan",
-                &block.ocr_block_id,
-            );
+        if frame_hashes.contains_key(&asset_id) {
+            continue;
+        }
+        // Frames captured before hashes were recorded are hashed from the asset.
+        let hash = std::fs::read(backend.assets.path_for(&asset_id))
+            .ok()
+            .and_then(|bytes| crate::image::dhash::png_dhash(&bytes));
+        if let Some(hash) = hash {
+            frame_hashes.insert(asset_id, hash);
         }
     }
-    let step = Step {
-        step_id,
-        order_index: 0,
-        title,
-        body: opscinema_types::StructuredText {
-            blocks: vec![opscinema_types::TextBlock {
-                block_id: "b1".to_string(),
-                text: narrative_text,
-                provenance: opscinema_types::TextBlockProvenance::Generated,
-                evidence_refs: vec![evidence_id],
-            }],
-        },
-        risk_tags: vec![],
-        branch_label: None,
-    };
+    let segments = segment::segment(&evidence, &frame_hashes);
+    let mut steps = segment::candidate_steps(&segments, step_id_for);
+    if steps.is_empty() {
+        // Nothing captured yet: keep a single placeholder step to edit. It
+        // cites nothing, so it is marked human-authored rather than generated.
+        let step_id = step_id_for(0);
+        steps.push(Step {
+            step_id,
+            order_index: 0,
+            title: "Open target screen".to_string(),
+            body: opscinema_types::StructuredText {
+                blocks: vec![opscinema_types::TextBlock {
+                    block_id: "b1".to_string(),
+                    text: "Navigate to the target screen".to_string(),
+                    provenance: opscinema_types::TextBlockProvenance::Human,
+                    evidence_refs: vec![],
+                }],
+            },
+            risk_tags: vec![],
+            branch_label: None,
+        });
+    }
 
-    let payload = StepsCandidatesGeneratedPayload {
-        schema_version: 1,
-        steps: vec![step],
-    };
     let job_id = repo_jobs::create_job(&conn, "steps_generate_candidates", Some(req.session_id))
         .map_err(internal_anyhow)?;
     let _ = repo_jobs::update_job_status(
@@ -88,6 +88,10 @@ pub fn steps_generate_candidates(
         None,
         None,
     );
+    let payload = StepsCandidatesGeneratedPayload {
+        schema_version: 1,
+        steps,
+    };
     let run = crate::storage::event_store::append_event(
         &mut conn,
         req.session_id,
@@ -192,11 +196,13 @@ pub fn steps_validate(
     let conn = backend.storage.conn().map_err(db_err)?;
     let steps = crate::steps::replay::replay_session_steps(&conn, req.session_id)
         .map_err(internal_anyhow)?;
+    let refs = crate::evidence::coverage::SessionEvidenceRefs::load(&conn, req.session_id, &steps)
+        .map_err(internal_anyhow)?;
     let model = StepModel {
         schema_version: 1,
         steps,
     };
-    Ok(validate::validate_for_export(&model))
+    Ok(validate::validate_for_export(&model, &refs))
 }
 
 #[allow(dead_code)]
@@ -3,8 +3,8 @@ use std::sync::Arc;
 use tauri::State;
 
 use crate::api::{
-    agent_plant, anchors, app, capture, evidence, exports, jobs, model_dock, ocr, proof,
-    runtime_events::RuntimeEventBus, sessions, slicer, steps, timeline, verifiers, Backend,
+    agent_plant, anchors, app, capture, evidence, exports, jobs, model_dock, ocr, proof, redaction,
+    runtime_events::RuntimeEventBus, search, sessions, slicer, steps, timeline, verifiers, Backend,
 };
 use opscinema_types::*;
 
@@ -120,6 +120,22 @@ pub fn session_close(
     sessions::session_close(backend(&state), req)
 }
 
+#[tauri::command]
+pub fn session_export_archive(
+    state: State<'_, Arc<Backend>>,
+    req: SessionExportArchiveRequest,
+) -> AppResult<SessionArchiveResult> {
+    sessions::session_export_archive(backend(&state), req)
+}
+
+#[tauri::command]
+pub fn session_import_archive(
+    state: State<'_, Arc<Backend>>,
+    req: SessionImportArchiveRequest,
+) -> AppResult<SessionArchiveResult> {
+    sessions::session_import_archive(backend(&state), req)
+}
+
 #[tauri::command]
 pub fn timeline_get_keyframes(
     state: State<'_, Arc<Backend>>,
@@ -255,6 +271,46 @@ pub fn evidence_get_coverage(
     evidence::evidence_get_coverage(backend(&state), req)
 }
 
+#[tauri::command]
+pub fn evidence_get_grounding(
+    state: State<'_, Arc<Backend>>,
+    req: EvidenceGroundingRequest,
+) -> AppResult<EvidenceGroundingResponse> {
+    evidence::evidence_get_grounding(backend(&state), req)
+}
+
+#[tauri::command]
+pub fn evidence_graph_neighbors(
+    state: State<'_, Arc<Backend>>,
+    req: EvidenceGraphNeighborsRequest,
+) -> AppResult<EvidenceSubgraph> {
+    evidence::evidence_graph_neighbors(backend(&state), req)
+}
+
+#[tauri::command]
+pub fn evidence_graph_path(
+    state: State<'_, Arc<Backend>>,
+    req: EvidenceGraphPathRequest,
+) -> AppResult<EvidenceSubgraph> {
+    evidence::evidence_graph_path(backend(&state), req)
+}
+
+#[tauri::command]
+pub fn evidence_graph_supports(
+    state: State<'_, Arc<Backend>>,
+    req: EvidenceGraphSupportsRequest,
+) -> AppResult<EvidenceSubgraph> {
+    evidence::evidence_graph_supports(backend(&state), req)
+}
+
+#[tauri::command]
+pub fn search_global(
+    state: State<'_, Arc<Backend>>,
+    req: SearchGlobalRequest,
+) -> AppResult<SearchGlobalResponse> {
+    search::search_global(backend(&state), req)
+}
+
 #[tauri::command]
 pub fn steps_generate_candidates(
     events: State<'_, RuntimeEventBus>,
@@ -572,6 +628,35 @@ pub fn export_verify_bundle(
     exports::export_verify_bundle(backend(&state), req)
 }
 
+#[tauri::command]
+pub fn export_trusted_keys_get(state: State<'_, Arc<Backend>>) -> AppResult<ExportTrustedKeys> {
+    exports::export_trusted_keys_get(backend(&state))
+}
+
+#[tauri::command]
+pub fn export_trusted_keys_set(
+    state: State<'_, Arc<Backend>>,
+    req: ExportTrustedKeysUpdate,
+) -> AppResult<ExportTrustedKeys> {
+    exports::export_trusted_keys_set(backend(&state), req)
+}
+
+#[tauri::command]
+pub fn redaction_rules_get(
+    state: State<'_, Arc<Backend>>,
+    req: RedactionRulesGetRequest,
+) -> AppResult<RedactionRules> {
+    redaction::redaction_rules_get(backend(&state), req)
+}
+
+#[tauri::command]
+pub fn redaction_rules_set(
+    state: State<'_, Arc<Backend>>,
+    req: RedactionRulesSetRequest,
+) -> AppResult<RedactionRules> {
+    redaction::redaction_rules_set(backend(&state), req)
+}
+
 #[tauri::command]
 pub fn jobs_list(
     state: State<'_, Arc<Backend>>,
@@ -612,6 +697,8 @@ pub fn invoke_handler<R: tauri::Runtime>(
         session_list,
         session_get,
         session_close,
+        session_export_archive,
+        session_import_archive,
         timeline_get_keyframes,
         timeline_get_events,
         timeline_get_thumbnail,
@@ -628,6 +715,11 @@ pub fn invoke_handler<R: tauri::Runtime>(
         evidence_for_step,
         evidence_find_text,
         evidence_get_coverage,
+        evidence_get_grounding,
+        evidence_graph_neighbors,
+        evidence_graph_path,
+        evidence_graph_supports,
+        search_global,
         steps_generate_candidates,
         steps_list,
         steps_get,
@@ -665,6 +757,10 @@ pub fn invoke_handler<R: tauri::Runtime>(
         agent_pipeline_report,
         exports_list,
         export_verify_bundle,
+        export_trusted_keys_get,
+        export_trusted_keys_set,
+        redaction_rules_get,
+        redaction_rules_set,
         jobs_list,
         jobs_get,
         jobs_cancel
@@ -1,4 +1,5 @@
 use crate::api::Backend;
+use crate::image::thumbnail::THUMBNAIL_MAX_EDGE;
 use crate::storage::repo_timeline;
 use opscinema_types::{
     AppError, AppErrorCode, AppResult, AssetRef, TimelineEventsRequest, TimelineEventsResponse,
@@ -30,12 +31,15 @@ pub fn timeline_get_events(
     Ok(TimelineEventsResponse { events })
 }
 
+/// The keyframe's downscaled thumbnail. Frames captured before thumbnails
+/// were recorded get one generated on demand; frames that are not PNGs fall
+/// back to the full-size asset.
 pub fn timeline_get_thumbnail(
     backend: &Backend,
     req: TimelineThumbnailRequest,
 ) -> AppResult<AssetRef> {
     let conn = backend.storage.conn().map_err(db_err)?;
-    let asset_id = repo_timeline::get_thumbnail_asset(&conn, req.session_id, req.frame_event_id)
+    let assets = repo_timeline::get_keyframe_assets(&conn, req.session_id, req.frame_event_id)
         .map_err(internal_anyhow)?
         .ok_or_else(|| AppError {
             code: AppErrorCode::NotFound,
@@ -44,6 +48,25 @@ pub fn timeline_get_thumbnail(
             recoverable: true,
             action_hint: None,
         })?;
+    if let Some(asset_id) = assets.thumbnail_asset_id {
+        return Ok(AssetRef { asset_id });
+    }
+    let bytes = std::fs::read(backend.assets.path_for(&assets.asset_id))
+        .map_err(|e| internal_anyhow(anyhow::anyhow!("keyframe asset unreadable: {e}")))?;
+    let Ok(image) = crate::image::decode_png(&bytes) else {
+        return Ok(AssetRef {
+            asset_id: assets.asset_id,
+        });
+    };
+    let thumbnail = crate::image::encode_png(
+        &crate::image::thumbnail::downscale(&image, THUMBNAIL_MAX_EDGE),
+        &[],
+    )
+    .map_err(internal_anyhow)?;
+    let asset_id = backend
+        .assets
+        .put(&conn, &thumbnail, None)
+        .map_err(internal_anyhow)?;
     Ok(AssetRef { asset_id })
 }
 
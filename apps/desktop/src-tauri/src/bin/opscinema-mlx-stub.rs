@@ -0,0 +1,19 @@
+//! Deterministic MLX runner for tests; see `model_dock::adapters::mlx_stub`.
+//! `OPSCINEMA_MLX_STUB_TOKEN_DELAY_MS` slows token streaming.
+
+use opscinema_desktop_backend::model_dock::adapters::mlx_stub;
+use std::io::BufReader;
+use std::time::Duration;
+
+fn main() -> std::io::Result<()> {
+    let token_delay = std::env::var("OPSCINEMA_MLX_STUB_TOKEN_DELAY_MS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(Duration::from_millis)
+        .unwrap_or_default();
+    mlx_stub::serve(
+        BufReader::new(std::io::stdin()),
+        std::io::stdout().lock(),
+        token_delay,
+    )
+}
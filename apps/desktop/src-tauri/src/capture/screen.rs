@@ -1,5 +1,11 @@
+use crate::image::{encode_png, RgbImage};
 use serde::{Deserialize, Serialize};
 
+/// `tEXt` keyword carrying the text the stub OCR provider reads back.
+pub const STUB_TEXT_KEYWORD: &str = "opscinema:stub-text";
+const STUB_WIDTH: u32 = 320;
+const STUB_HEIGHT: u32 = 180;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ScreenKeyframe {
     pub frame_ms: i64,
@@ -22,10 +28,36 @@ impl ScreenCaptureKitProvider for StubScreenCaptureKit {
         Ok(ScreenKeyframe {
             frame_ms,
             display_id: "display.main".to_string(),
-            pixel_w: 1920,
-            pixel_h: 1080,
+            pixel_w: STUB_WIDTH,
+            pixel_h: STUB_HEIGHT,
             scale_factor: "2.0".to_string(),
-            png_bytes: format!("fake-keyframe-{frame_ms}").into_bytes(),
+            png_bytes: stub_png(&format!("fake-keyframe-{frame_ms}"))?,
         })
     }
 }
+
+/// The same window mock-up for every frame, so stub frames never read as a
+/// screen change; only the embedded text differs.
+fn stub_png(text: &str) -> anyhow::Result<Vec<u8>> {
+    let mut pixels = Vec::with_capacity((STUB_WIDTH * STUB_HEIGHT * 3) as usize);
+    for y in 0..STUB_HEIGHT {
+        for x in 0..STUB_WIDTH {
+            let px = if y < 16 {
+                [52, 58, 64]
+            } else if x < 64 {
+                [233, 236, 239]
+            } else {
+                [255, 255, 255]
+            };
+            pixels.extend_from_slice(&px);
+        }
+    }
+    encode_png(
+        &RgbImage {
+            width: STUB_WIDTH,
+            height: STUB_HEIGHT,
+            pixels,
+        },
+        &[(STUB_TEXT_KEYWORD, text)],
+    )
+}
@@ -1,8 +1,95 @@
-use opscinema_types::{EvidenceCoverageResponse, Step, TextBlockProvenance};
+use crate::storage::repo_evidence;
+use opscinema_types::{
+    EvidenceCoverageResponse, EvidenceRefIssue, EvidenceRefIssueKind, SessionId, Step,
+    TextBlockProvenance,
+};
+use std::collections::{BTreeMap, BTreeSet};
+use uuid::Uuid;
 
-pub fn evaluate(steps: &[Step]) -> EvidenceCoverageResponse {
+/// Evidence ids a session's steps may cite, resolved against its derived
+/// evidence. Refs the session does not derive are looked up in the
+/// `evidence_owners` index so a copied ref is told apart from a fabricated one.
+#[derive(Debug, Default)]
+pub struct SessionEvidenceRefs {
+    valid: BTreeSet<Uuid>,
+    invalidated: BTreeSet<Uuid>,
+    other_sessions: BTreeMap<Uuid, SessionId>,
+}
+
+impl SessionEvidenceRefs {
+    pub fn load(
+        conn: &crate::storage::DbConn,
+        session_id: SessionId,
+        steps: &[Step],
+    ) -> anyhow::Result<Self> {
+        let evidence = super::graph::derive_from_event_log(conn, session_id)?;
+        let degraded = crate::anchors::cache::replay_session(conn, session_id)?
+            .into_iter()
+            .filter(|a| a.degraded)
+            .map(|a| a.anchor_id.to_string())
+            .collect::<BTreeSet<_>>();
+        let mut refs = Self::default();
+        for item in evidence.evidence {
+            let from_degraded_anchor =
+                matches!(item.kind.as_str(), "AnchorObservation" | "AnchorDegraded")
+                    && degraded.contains(&item.source_id);
+            if from_degraded_anchor {
+                refs.invalidated.insert(item.evidence_id);
+            } else {
+                refs.valid.insert(item.evidence_id);
+            }
+        }
+
+        let unknown = steps
+            .iter()
+            .flat_map(|s| &s.body.blocks)
+            .flat_map(|b| b.evidence_refs.iter().copied())
+            .filter(|id| !refs.valid.contains(id) && !refs.invalidated.contains(id))
+            .collect::<BTreeSet<_>>();
+        if unknown.is_empty() {
+            return Ok(refs);
+        }
+        for (other_id, indexed_seq, head_seq) in repo_evidence::stale_sessions(conn)? {
+            let derived =
+                super::graph::derive_from_event_range(conn, other_id, indexed_seq, head_seq)?;
+            repo_evidence::record_owners(
+                conn,
+                other_id,
+                derived.evidence.iter().map(|item| item.evidence_id),
+                head_seq,
+            )?;
+        }
+        refs.other_sessions = repo_evidence::owners_of(conn, &unknown)?
+            .into_iter()
+            .filter(|(_, owner)| *owner != session_id)
+            .collect();
+        Ok(refs)
+    }
+
+    /// True when the session derives `evidence_id` and it is not invalidated.
+    pub fn resolves(&self, evidence_id: &Uuid) -> bool {
+        self.valid.contains(evidence_id)
+    }
+
+    fn issue(&self, evidence_id: &Uuid) -> Option<(EvidenceRefIssueKind, Option<SessionId>)> {
+        if self.valid.contains(evidence_id) {
+            None
+        } else if self.invalidated.contains(evidence_id) {
+            Some((EvidenceRefIssueKind::Invalidated, None))
+        } else if let Some(owner) = self.other_sessions.get(evidence_id) {
+            Some((EvidenceRefIssueKind::OtherSession, Some(*owner)))
+        } else {
+            Some((EvidenceRefIssueKind::Dangling, None))
+        }
+    }
+}
+
+/// Generated blocks need at least one ref, and every ref on any block must
+/// resolve to valid evidence of the session.
+pub fn evaluate(steps: &[Step], refs: &SessionEvidenceRefs) -> EvidenceCoverageResponse {
     let mut missing_step_ids = Vec::new();
     let mut missing_generated_block_ids = Vec::new();
+    let mut invalid_refs = Vec::new();
 
     for step in steps {
         let mut step_missing = false;
@@ -12,6 +99,17 @@ pub fn evaluate(steps: &[Step]) -> EvidenceCoverageResponse {
                 missing_generated_block_ids.push(block.block_id.clone());
                 step_missing = true;
             }
+            for evidence_id in &block.evidence_refs {
+                if let Some((kind, owner_session_id)) = refs.issue(evidence_id) {
+                    invalid_refs.push(EvidenceRefIssue {
+                        step_id: step.step_id,
+                        block_id: block.block_id.clone(),
+                        evidence_id: *evidence_id,
+                        kind,
+                        owner_session_id,
+                    });
+                }
+            }
         }
         if step_missing {
             missing_step_ids.push(step.step_id);
@@ -19,8 +117,9 @@ pub fn evaluate(steps: &[Step]) -> EvidenceCoverageResponse {
     }
 
     EvidenceCoverageResponse {
-        pass: missing_generated_block_ids.is_empty(),
+        pass: missing_generated_block_ids.is_empty() && invalid_refs.is_empty(),
         missing_step_ids,
         missing_generated_block_ids,
+        invalid_refs,
     }
 }
@@ -1,6 +1,12 @@
+use crate::storage::event_store::{stream_events, EventFilter};
 use crate::util::ids::deterministic_evidence_id;
-use opscinema_types::{EvidenceItem, EvidenceLocator, EvidenceLocatorType, EvidenceSet, OcrBlock};
+use opscinema_types::{
+    AnchorCandidate, BBoxNorm, EvidenceEdgeDirection, EvidenceEdgeType, EvidenceGraphEdge,
+    EvidenceGraphNode, EvidenceItem, EvidenceLocator, EvidenceLocatorType, EvidenceNodeType,
+    EvidenceSet, EvidenceSubgraph, OcrBlock, Step, StepId,
+};
 use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
 use uuid::Uuid;
 
 #[derive(Debug, Deserialize)]
@@ -39,8 +45,8 @@ struct OcrBlocksPersisted {
 
 #[derive(Debug, Deserialize)]
 struct VerifierRunCompleted {
-    run_id: Uuid,
-    result_asset_id: String,
+    run_id: Option<Uuid>,
+    result_asset_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -68,10 +74,46 @@ pub fn derive_from_event_log(
     conn: &crate::storage::DbConn,
     session_id: Uuid,
 ) -> anyhow::Result<EvidenceSet> {
-    let events = crate::storage::event_store::query_events(conn, session_id, None, 100_000)?;
+    derive_from_events(conn, session_id, EventFilter::default())
+}
+
+/// Evidence derived from events with `after_seq < seq <= through_seq` only.
+pub fn derive_from_event_range(
+    conn: &crate::storage::DbConn,
+    session_id: Uuid,
+    after_seq: i64,
+    through_seq: i64,
+) -> anyhow::Result<EvidenceSet> {
+    derive_from_events(
+        conn,
+        session_id,
+        EventFilter::default().after(after_seq).through(through_seq),
+    )
+}
+
+fn derive_from_events(
+    conn: &crate::storage::DbConn,
+    session_id: Uuid,
+    window: EventFilter,
+) -> anyhow::Result<EvidenceSet> {
+    let filter = EventFilter {
+        after_seq: window.after_seq,
+        through_seq: window.through_seq,
+        ..EventFilter::types(&[
+            "KeyframeCaptured",
+            "ClickCaptured",
+            "WindowMetaCaptured",
+            "OcrBlocksPersisted",
+            "VerifierRunCompleted",
+            "AnchorResolved",
+            "AnchorDegraded",
+            "ExportCreated",
+        ])
+    };
     let mut evidence = Vec::new();
 
-    for event in events {
+    for event in stream_events(conn, session_id, filter) {
+        let event = event?;
         match event.event_type.as_str() {
             "KeyframeCaptured" => {
                 let payload: KeyframeCaptured = serde_json::from_str(&event.payload_canon_json)?;
@@ -182,17 +224,24 @@ pub fn derive_from_event_log(
             "VerifierRunCompleted" => {
                 let payload: VerifierRunCompleted =
                     serde_json::from_str(&event.payload_canon_json)?;
+                // Completions recorded only as export warnings carry no run
+                // result to cite.
+                let (Some(run_id), Some(result_asset_id)) =
+                    (payload.run_id, payload.result_asset_id)
+                else {
+                    continue;
+                };
                 evidence.push(EvidenceItem {
                     evidence_id: deterministic_evidence_id(
                         session_id,
                         "VerifierResult",
-                        &payload.run_id.to_string(),
+                        &run_id.to_string(),
                     ),
                     kind: "VerifierResult".to_string(),
-                    source_id: payload.run_id.to_string(),
+                    source_id: run_id.to_string(),
                     locators: vec![EvidenceLocator {
                         locator_type: EvidenceLocatorType::VerifierLog,
-                        asset_id: Some(payload.result_asset_id),
+                        asset_id: Some(result_asset_id),
                         frame_ms: None,
                         bbox_norm: None,
                         text_offset: None,
@@ -270,3 +319,333 @@ pub fn derive_from_event_log(
 
     Ok(EvidenceSet { evidence })
 }
+
+/// Hops a path query may take unless the caller asks for more.
+pub const DEFAULT_PATH_MAX_DEPTH: u32 = 8;
+
+pub fn evidence_node_id(evidence_id: Uuid) -> String {
+    format!("evidence:{evidence_id}")
+}
+
+pub fn block_node_id(step_id: StepId, block_id: &str) -> String {
+    format!("block:{step_id}:{block_id}")
+}
+
+/// Evidence items and step blocks joined by typed edges, derived from the same
+/// inputs as the flat [`EvidenceSet`] so it can be rebuilt on every query.
+#[derive(Debug, Default)]
+pub struct EvidenceGraph {
+    nodes: BTreeMap<String, EvidenceGraphNode>,
+    edges: Vec<EvidenceGraphEdge>,
+    /// Edge indexes touching each node, in edge order.
+    adjacency: BTreeMap<String, Vec<usize>>,
+}
+
+impl EvidenceGraph {
+    pub fn build(set: &EvidenceSet, steps: &[Step], anchors: &[AnchorCandidate]) -> Self {
+        let mut nodes = BTreeMap::new();
+        let mut edges = BTreeSet::new();
+        let mut keyframes = Vec::new();
+        let mut ocr_spans = Vec::new();
+        let mut by_source = BTreeMap::new();
+        for item in &set.evidence {
+            let node_id = evidence_node_id(item.evidence_id);
+            let first = item.locators.first();
+            match (item.kind.as_str(), first.and_then(|l| l.frame_ms)) {
+                ("FrameKeyframe", Some(ms)) => {
+                    keyframes.push((ms, first.and_then(|l| l.asset_id.clone()), node_id.clone()))
+                }
+                ("OcrSpan", Some(ms)) => {
+                    if let Some(bbox) = first.and_then(|l| l.bbox_norm.clone()) {
+                        ocr_spans.push((ms, bbox, node_id.clone()));
+                    }
+                }
+                _ => {}
+            }
+            by_source.insert(
+                (item.kind.as_str(), item.source_id.as_str()),
+                node_id.clone(),
+            );
+            nodes.insert(
+                node_id.clone(),
+                EvidenceGraphNode {
+                    node_id,
+                    node_type: EvidenceNodeType::Evidence,
+                    evidence: Some(item.clone()),
+                    step_id: None,
+                    block_id: None,
+                },
+            );
+        }
+        keyframes.sort();
+        // The keyframe on screen at `ms`: the latest one captured at or before it.
+        let on_screen = |ms: i64| {
+            let idx = keyframes.partition_point(|(frame_ms, _, _)| *frame_ms <= ms);
+            idx.checked_sub(1).map(|i| &keyframes[i])
+        };
+        let captured_at = |ms: i64| keyframes.iter().find(|(frame_ms, _, _)| *frame_ms == ms);
+        let mut edge = |from: &str, to: &str, edge_type| {
+            edges.insert(EvidenceGraphEdge {
+                from: from.to_string(),
+                to: to.to_string(),
+                edge_type,
+            });
+        };
+
+        for item in &set.evidence {
+            let node_id = evidence_node_id(item.evidence_id);
+            let frame_ms = item.locators.first().and_then(|l| l.frame_ms);
+            match item.kind.as_str() {
+                "OcrSpan" | "OcrProviderOutput" => {
+                    if let Some((_, _, frame)) = frame_ms.and_then(captured_at) {
+                        edge(&node_id, frame, EvidenceEdgeType::DerivedFrom);
+                    }
+                }
+                "AnchorObservation" | "AnchorDegraded" => {
+                    for locator in &item.locators {
+                        let frame = keyframes
+                            .iter()
+                            .find(|(_, asset_id, _)| {
+                                asset_id.is_some() && *asset_id == locator.asset_id
+                            })
+                            .or_else(|| locator.frame_ms.and_then(captured_at));
+                        if let Some((_, _, frame)) = frame {
+                            edge(&node_id, frame, EvidenceEdgeType::DerivedFrom);
+                        }
+                    }
+                }
+                "Click" | "WindowMeta" => {
+                    let Some((shown_ms, _, frame)) = frame_ms.and_then(on_screen) else {
+                        continue;
+                    };
+                    edge(&node_id, frame, EvidenceEdgeType::LocatedIn);
+                    let point = item.locators.first().and_then(|l| l.bbox_norm.as_ref());
+                    if let (Some(point), "Click") = (point, item.kind.as_str()) {
+                        for (_, _, span) in ocr_spans
+                            .iter()
+                            .filter(|(ms, bbox, _)| ms == shown_ms && contains(bbox, point))
+                        {
+                            edge(&node_id, span, EvidenceEdgeType::LocatedIn);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut step_blocks = BTreeMap::<StepId, Vec<String>>::new();
+        for step in steps {
+            for block in &step.body.blocks {
+                let node_id = block_node_id(step.step_id, &block.block_id);
+                for evidence_id in &block.evidence_refs {
+                    let target = evidence_node_id(*evidence_id);
+                    let Some(cited) = nodes.get(&target) else {
+                        continue;
+                    };
+                    edge(&node_id, &target, EvidenceEdgeType::Cites);
+                    if cited
+                        .evidence
+                        .as_ref()
+                        .is_some_and(|e| e.kind == "VerifierResult")
+                    {
+                        edge(&target, &node_id, EvidenceEdgeType::Verifies);
+                    }
+                }
+                step_blocks
+                    .entry(step.step_id)
+                    .or_default()
+                    .push(node_id.clone());
+                nodes.insert(
+                    node_id.clone(),
+                    EvidenceGraphNode {
+                        node_id,
+                        node_type: EvidenceNodeType::StepBlock,
+                        evidence: None,
+                        step_id: Some(step.step_id),
+                        block_id: Some(block.block_id.clone()),
+                    },
+                );
+            }
+        }
+        for anchor in anchors.iter().filter(|a| !a.degraded) {
+            let anchor_id = anchor.anchor_id.to_string();
+            let (Some(observation), Some(blocks)) = (
+                by_source.get(&("AnchorObservation", anchor_id.as_str())),
+                step_blocks.get(&anchor.step_id),
+            ) else {
+                continue;
+            };
+            for block in blocks {
+                edge(observation, block, EvidenceEdgeType::Verifies);
+            }
+        }
+
+        let edges = edges.into_iter().collect::<Vec<_>>();
+        let mut adjacency = BTreeMap::<String, Vec<usize>>::new();
+        for (idx, e) in edges.iter().enumerate() {
+            adjacency.entry(e.from.clone()).or_default().push(idx);
+            if e.to != e.from {
+                adjacency.entry(e.to.clone()).or_default().push(idx);
+            }
+        }
+        Self {
+            nodes,
+            edges,
+            adjacency,
+        }
+    }
+
+    pub fn node(&self, node_id: &str) -> Option<&EvidenceGraphNode> {
+        self.nodes.get(node_id)
+    }
+
+    fn edges_of<'a>(&'a self, node_id: &str) -> impl Iterator<Item = &'a EvidenceGraphEdge> {
+        self.adjacency
+            .get(node_id)
+            .into_iter()
+            .flatten()
+            .map(|idx| &self.edges[*idx])
+    }
+
+    /// The node first, then every node one edge away in id order.
+    pub fn neighbors(
+        &self,
+        node_id: &str,
+        direction: EvidenceEdgeDirection,
+        edge_types: &[EvidenceEdgeType],
+    ) -> EvidenceSubgraph {
+        let edges = self
+            .edges_of(node_id)
+            .filter(|e| edge_types.is_empty() || edge_types.contains(&e.edge_type))
+            .filter(|e| match direction {
+                EvidenceEdgeDirection::Outgoing => e.from == node_id,
+                EvidenceEdgeDirection::Incoming => e.to == node_id,
+                EvidenceEdgeDirection::Both => true,
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        let others = edges
+            .iter()
+            .map(|e| if e.from == node_id { &e.to } else { &e.from })
+            .filter(|id| *id != node_id)
+            .cloned()
+            .collect::<BTreeSet<_>>();
+        self.subgraph(
+            std::iter::once(node_id).chain(others.iter().map(String::as_str)),
+            edges,
+        )
+    }
+
+    /// Shortest path between two nodes, walking edges in either direction.
+    /// Nodes come in path order and edges keep their own direction; both are
+    /// empty when no path of at most `max_depth` edges exists.
+    pub fn shortest_path(&self, from: &str, to: &str, max_depth: u32) -> EvidenceSubgraph {
+        let mut came_from = BTreeMap::<&str, Option<(&str, usize)>>::new();
+        came_from.insert(from, None);
+        let mut frontier = vec![from];
+        for _ in 0..max_depth {
+            if came_from.contains_key(to) {
+                break;
+            }
+            let mut next = Vec::new();
+            for node in frontier {
+                for idx in self.adjacency.get(node).into_iter().flatten() {
+                    let e = &self.edges[*idx];
+                    let other = if e.from == node { &e.to } else { &e.from };
+                    if !came_from.contains_key(other.as_str()) {
+                        came_from.insert(other, Some((node, *idx)));
+                        next.push(other.as_str());
+                    }
+                }
+            }
+            frontier = next;
+        }
+        if !came_from.contains_key(to) || !self.nodes.contains_key(to) {
+            return EvidenceSubgraph {
+                nodes: vec![],
+                edges: vec![],
+            };
+        }
+        let mut path = vec![to];
+        let mut edges = Vec::new();
+        while let Some(Some((prev, idx))) = came_from.get(path[path.len() - 1]) {
+            edges.push(self.edges[*idx].clone());
+            path.push(prev);
+        }
+        path.reverse();
+        edges.reverse();
+        self.subgraph(path.into_iter(), edges)
+    }
+
+    /// Everything a step block rests on: the evidence it cites, what that
+    /// evidence was derived from or located in, transitively, and the anchors
+    /// and verifier results that verify it. Nodes come in discovery order.
+    pub fn supports(&self, block_node_id: &str) -> EvidenceSubgraph {
+        let mut order = vec![block_node_id];
+        let mut seen = BTreeSet::from([block_node_id]);
+        let mut edges = BTreeSet::new();
+        let mut idx = 0;
+        while idx < order.len() {
+            let node = order[idx];
+            idx += 1;
+            for e in self.edges_of(node) {
+                let next = if e.from == node {
+                    &e.to
+                } else if e.edge_type == EvidenceEdgeType::Verifies {
+                    &e.from
+                } else {
+                    continue;
+                };
+                edges.insert(e.clone());
+                if seen.insert(next.as_str()) {
+                    order.push(next);
+                }
+            }
+        }
+        self.subgraph(order.into_iter(), edges.into_iter().collect())
+    }
+
+    /// Evidence a block cites, followed along `derived_from`/`located_in` to
+    /// the keyframes (and OCR spans under clicks) it was captured on.
+    pub fn cited_evidence(&self, block_node_id: &str) -> Vec<&EvidenceItem> {
+        let mut order = vec![block_node_id];
+        let mut seen = BTreeSet::from([block_node_id]);
+        let mut idx = 0;
+        while idx < order.len() {
+            let node = order[idx];
+            idx += 1;
+            for e in self.edges_of(node) {
+                if e.from == node
+                    && e.edge_type != EvidenceEdgeType::Verifies
+                    && seen.insert(e.to.as_str())
+                {
+                    order.push(&e.to);
+                }
+            }
+        }
+        order
+            .into_iter()
+            .filter_map(|id| self.nodes.get(id)?.evidence.as_ref())
+            .collect()
+    }
+
+    fn subgraph<'a>(
+        &self,
+        node_ids: impl Iterator<Item = &'a str>,
+        edges: Vec<EvidenceGraphEdge>,
+    ) -> EvidenceSubgraph {
+        EvidenceSubgraph {
+            nodes: node_ids
+                .filter_map(|id| self.nodes.get(id).cloned())
+                .collect(),
+            edges,
+        }
+    }
+}
+
+fn contains(bbox: &BBoxNorm, point: &BBoxNorm) -> bool {
+    point.x >= bbox.x
+        && point.x < bbox.x.saturating_add(bbox.w)
+        && point.y >= bbox.y
+        && point.y < bbox.y.saturating_add(bbox.h)
+}
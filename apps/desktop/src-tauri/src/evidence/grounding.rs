@@ -0,0 +1,233 @@
+use super::graph::{block_node_id, EvidenceGraph};
+use crate::storage::repo_ocr;
+use opscinema_types::{
+    BlockGrounding, EvidenceGroundingResponse, EvidenceItem, ExportWarning, SessionId, Step,
+    TextBlockProvenance,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+pub const UNGROUNDED_CLAIM_WARNING_CODE: &str = "UNGROUNDED_CLAIM";
+
+/// Control words that name a UI element; the capitalized words right before
+/// one are the element's label ("the Network Settings pane").
+const UI_CONTROL_WORDS: &[&str] = &[
+    "button", "checkbox", "dialog", "dropdown", "field", "icon", "item", "link", "list", "menu",
+    "option", "page", "pane", "panel", "section", "switch", "tab", "toggle", "window",
+];
+
+const QUOTE_PAIRS: &[(char, char)] = &[
+    ('"', '"'),
+    ('\u{201c}', '\u{201d}'),
+    ('\u{2018}', '\u{2019}'),
+];
+
+/// OCR text recorded in `ocr_blocks`, per frame and per span.
+#[derive(Debug, Default)]
+pub struct FrameText {
+    by_frame: BTreeMap<String, Vec<String>>,
+    span_frames: BTreeMap<String, String>,
+}
+
+impl FrameText {
+    pub fn load(conn: &crate::storage::DbConn, session_id: SessionId) -> anyhow::Result<Self> {
+        let mut text = Self::default();
+        for (frame_event_id, ocr_block_id, block_text) in
+            repo_ocr::list_block_texts(conn, session_id)?
+        {
+            text.by_frame
+                .entry(frame_event_id.clone())
+                .or_default()
+                .push(normalize(&block_text));
+            text.span_frames.insert(ocr_block_id, frame_event_id);
+        }
+        Ok(text)
+    }
+
+    /// Text of the frames behind `evidence`: a keyframe's own, and for an
+    /// OCR span the frame it was recognized on.
+    fn visible<'a>(&self, evidence: impl IntoIterator<Item = &'a EvidenceItem>) -> String {
+        let frames = evidence
+            .into_iter()
+            .filter_map(|item| match item.kind.as_str() {
+                "FrameKeyframe" => Some(&item.source_id),
+                "OcrSpan" => self.span_frames.get(&item.source_id),
+                _ => None,
+            })
+            .collect::<BTreeSet<_>>();
+        frames
+            .into_iter()
+            .filter_map(|frame| self.by_frame.get(frame))
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Scores every generated block against the OCR text of the frames its
+/// evidence was captured on.
+pub fn load(
+    conn: &crate::storage::DbConn,
+    session_id: SessionId,
+    steps: &[Step],
+) -> anyhow::Result<EvidenceGroundingResponse> {
+    let evidence = super::graph::derive_from_event_log(conn, session_id)?;
+    let anchors = crate::anchors::cache::replay_session(conn, session_id)?;
+    let graph = EvidenceGraph::build(&evidence, steps, &anchors);
+    Ok(evaluate(steps, &graph, &FrameText::load(conn, session_id)?))
+}
+
+pub fn evaluate(
+    steps: &[Step],
+    graph: &EvidenceGraph,
+    frame_text: &FrameText,
+) -> EvidenceGroundingResponse {
+    let mut blocks = Vec::new();
+    for step in steps {
+        for block in &step.body.blocks {
+            if block.provenance != TextBlockProvenance::Generated {
+                continue;
+            }
+            let visible = frame_text
+                .visible(graph.cited_evidence(&block_node_id(step.step_id, &block.block_id)));
+            let claims = claims(&block.text);
+            let ungrounded_claims = ungrounded_claims(&claims, &visible);
+            let score = if claims.is_empty() {
+                100
+            } else {
+                ((claims.len() - ungrounded_claims.len()) * 100 / claims.len()) as u8
+            };
+            blocks.push(BlockGrounding {
+                step_id: step.step_id,
+                block_id: block.block_id.clone(),
+                score,
+                claims,
+                ungrounded_claims,
+            });
+        }
+    }
+    EvidenceGroundingResponse {
+        pass: blocks.iter().all(|b| b.ungrounded_claims.is_empty()),
+        blocks,
+    }
+}
+
+/// Claims whose tokens `visible` does not show as whole words, in order;
+/// `visible` must already be [`normalize`]d. "OK" is not grounded by "token".
+pub fn ungrounded_claims(claims: &[String], visible: &str) -> Vec<String> {
+    let visible = visible.split(' ').collect::<Vec<_>>();
+    claims
+        .iter()
+        .filter(|claim| {
+            let claim = normalize(claim);
+            let tokens = claim.split(' ').collect::<Vec<_>>();
+            !claim.is_empty() && !visible.windows(tokens.len()).any(|w| w == tokens)
+        })
+        .cloned()
+        .collect()
+}
+
+/// One `UNGROUNDED_CLAIM` warning per block with a claim its frames do not show.
+pub fn warnings(grounding: &EvidenceGroundingResponse) -> Vec<ExportWarning> {
+    grounding
+        .blocks
+        .iter()
+        .filter(|b| !b.ungrounded_claims.is_empty())
+        .map(|b| ExportWarning {
+            code: UNGROUNDED_CLAIM_WARNING_CODE.to_string(),
+            message: format!(
+                "step={} block={} score={} not on cited frames: {}",
+                b.step_id,
+                b.block_id,
+                b.score,
+                b.ungrounded_claims
+                    .iter()
+                    .map(|c| format!("\"{c}\""))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        })
+        .collect()
+}
+
+/// Checkable claims of a text: quoted strings, then capitalized UI nouns
+/// outside quotes, each once in order of appearance.
+pub fn claims(text: &str) -> Vec<String> {
+    let mut quoted = Vec::new();
+    // Words outside quotes; `None` marks a quote or sentence break.
+    let mut words = Vec::<Option<String>>::new();
+    let mut word = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if let Some((_, close)) = QUOTE_PAIRS.iter().find(|(open, _)| *open == c) {
+            push_word(&mut words, &mut word);
+            let inner = chars
+                .by_ref()
+                .take_while(|c| c != close)
+                .collect::<String>();
+            let inner = inner.trim();
+            if !inner.is_empty() {
+                quoted.push(inner.to_string());
+            }
+            words.push(None);
+        } else if c.is_whitespace() {
+            push_word(&mut words, &mut word);
+        } else if matches!(c, '.' | '!' | '?' | ':' | ';' | ',' | '(' | ')') {
+            push_word(&mut words, &mut word);
+            words.push(None);
+        } else {
+            word.push(c);
+        }
+    }
+    push_word(&mut words, &mut word);
+
+    let mut nouns = Vec::new();
+    for (idx, w) in words.iter().enumerate() {
+        let Some(w) = w else { continue };
+        if !UI_CONTROL_WORDS.contains(&w.to_lowercase().as_str()) {
+            continue;
+        }
+        let mut start = idx;
+        // The first word of a sentence is capitalized anyway, so it never
+        // starts a label.
+        while start > 1
+            && words[start - 1]
+                .as_deref()
+                .is_some_and(|p| p.starts_with(char::is_uppercase))
+            && words[start - 2].is_some()
+        {
+            start -= 1;
+        }
+        if start < idx {
+            let label = words[start..idx]
+                .iter()
+                .flatten()
+                .cloned()
+                .collect::<Vec<_>>();
+            nouns.push(label.join(" "));
+        }
+    }
+
+    let mut seen = Vec::new();
+    for claim in quoted.into_iter().chain(nouns) {
+        if !normalize(&claim).is_empty() && !seen.contains(&claim) {
+            seen.push(claim);
+        }
+    }
+    seen
+}
+
+fn push_word(words: &mut Vec<Option<String>>, word: &mut String) {
+    if !word.is_empty() {
+        words.push(Some(std::mem::take(word)));
+    }
+}
+
+/// Lowercased alphanumeric tokens joined by single spaces.
+pub fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -1,3 +1,4 @@
 pub mod coverage;
 pub mod graph;
+pub mod grounding;
 pub mod query;
@@ -1,13 +1,17 @@
 use opscinema_types::{EvidenceItem, EvidenceSet, Step};
+use std::collections::{BTreeMap, BTreeSet};
 use uuid::Uuid;
 
-pub fn for_step(step: &Step, all: &EvidenceSet) -> EvidenceSet {
-    let ids: std::collections::BTreeSet<Uuid> = step
-        .body
-        .blocks
+pub fn referenced_ids(steps: &[Step]) -> BTreeSet<Uuid> {
+    steps
         .iter()
+        .flat_map(|s| s.body.blocks.iter())
         .flat_map(|b| b.evidence_refs.iter().copied())
-        .collect();
+        .collect()
+}
+
+pub fn for_step(step: &Step, all: &EvidenceSet) -> EvidenceSet {
+    let ids = referenced_ids(std::slice::from_ref(step));
     let evidence = all
         .evidence
         .iter()
@@ -16,3 +20,19 @@ pub fn for_step(step: &Step, all: &EvidenceSet) -> EvidenceSet {
         .collect::<Vec<EvidenceItem>>();
     EvidenceSet { evidence }
 }
+
+/// Assets located by evidence that `steps` reference, each with the evidence
+/// ids pointing at it.
+pub fn assets_for_steps(steps: &[Step], all: &EvidenceSet) -> BTreeMap<String, BTreeSet<Uuid>> {
+    let ids = referenced_ids(steps);
+    let mut assets: BTreeMap<String, BTreeSet<Uuid>> = BTreeMap::new();
+    for item in all.evidence.iter().filter(|e| ids.contains(&e.evidence_id)) {
+        for asset_id in item.locators.iter().filter_map(|l| l.asset_id.as_ref()) {
+            assets
+                .entry(asset_id.clone())
+                .or_default()
+                .insert(item.evidence_id);
+        }
+    }
+    assets
+}
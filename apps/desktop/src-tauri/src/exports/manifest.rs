@@ -2,19 +2,42 @@ use crate::exports::fs::list_files_sorted;
 use crate::util::canon_json::to_canonical_json;
 use crate::util::hash::blake3_hex;
 use opscinema_export_manifest::{
-    compute_bundle_hash, BundleType, ExportManifestV1, ManifestFileEntry, ManifestWarning,
-    ModelPin, PolicyAttestations,
+    compute_bundle_hash, BundleType, EventSeqRange, ExportManifestV1, ExportManifestV2,
+    ManifestFileEntry, ManifestFileEntryV2, ManifestWarning, ModelPin, PolicyAttestations,
 };
+use std::collections::BTreeMap;
 use std::path::Path;
 
-pub fn build_manifest(
-    root: &Path,
-    bundle_type: BundleType,
-    session_id: &str,
-    warnings: Vec<ManifestWarning>,
-    policy: PolicyAttestations,
-    model_pins: Vec<ModelPin>,
-) -> anyhow::Result<ExportManifestV1> {
+/// Where a bundle file came from, keyed by relative path in
+/// [`BundleProvenance::files`]. Files without an entry get no asset or
+/// evidence links.
+#[derive(Debug, Clone, Default)]
+pub struct FileProvenance {
+    pub asset_id: Option<String>,
+    pub evidence_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BundleProvenance {
+    pub source_seq_range: EventSeqRange,
+    pub files: BTreeMap<String, FileProvenance>,
+}
+
+pub fn media_type_for(path: &str) -> &'static str {
+    match Path::new(path).extension().and_then(|e| e.to_str()) {
+        Some("json") => "application/json",
+        Some("jsonl") => "application/x-ndjson",
+        Some("html") => "text/html",
+        Some("md") => "text/markdown",
+        Some("png") => "image/png",
+        Some("txt") => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Hashes every file under `root` except the manifest itself, sorted by
+/// relative path.
+pub fn file_entries(root: &Path, manifest_name: &str) -> anyhow::Result<Vec<ManifestFileEntry>> {
     let files = list_files_sorted(root)?;
     let mut entries = Vec::new();
     for path in files {
@@ -23,7 +46,7 @@ pub fn build_manifest(
             .map_err(|e| anyhow::anyhow!("manifest path escaping root: {e}"))?
             .to_string_lossy()
             .to_string();
-        if rel == "manifest.json" {
+        if rel == manifest_name {
             continue;
         }
         let bytes = std::fs::read(&path)?;
@@ -33,8 +56,19 @@ pub fn build_manifest(
             size_bytes: bytes.len() as u64,
         });
     }
-
     entries.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(entries)
+}
+
+pub fn build_manifest(
+    root: &Path,
+    bundle_type: BundleType,
+    session_id: &str,
+    warnings: Vec<ManifestWarning>,
+    policy: PolicyAttestations,
+    model_pins: Vec<ModelPin>,
+) -> anyhow::Result<ExportManifestV1> {
+    let entries = file_entries(root, "manifest.json")?;
     let tuples = entries
         .iter()
         .map(|e| (e.path.clone(), e.hash_blake3.clone()))
@@ -52,6 +86,61 @@ pub fn build_manifest(
         model_pins,
         manifest_hash: String::new(),
         bundle_hash,
+        signature: None,
+    };
+
+    let json = to_canonical_json(&manifest)?;
+    manifest.manifest_hash = blake3_hex(json.as_bytes());
+    Ok(manifest)
+}
+
+pub fn build_manifest_v2(
+    root: &Path,
+    bundle_type: BundleType,
+    session_id: &str,
+    warnings: Vec<ManifestWarning>,
+    policy: PolicyAttestations,
+    model_pins: Vec<ModelPin>,
+    provenance: BundleProvenance,
+) -> anyhow::Result<ExportManifestV2> {
+    let BundleProvenance {
+        source_seq_range,
+        files: mut origins,
+    } = provenance;
+    let entries = file_entries(root, "manifest.json")?;
+    let tuples = entries
+        .iter()
+        .map(|e| (e.path.clone(), e.hash_blake3.clone()))
+        .collect::<Vec<_>>();
+    let bundle_hash = compute_bundle_hash(&tuples);
+    let files = entries
+        .into_iter()
+        .map(|e| {
+            let origin = origins.remove(&e.path).unwrap_or_default();
+            ManifestFileEntryV2 {
+                media_type: media_type_for(&e.path).to_string(),
+                path: e.path,
+                hash_blake3: e.hash_blake3,
+                size_bytes: e.size_bytes,
+                asset_id: origin.asset_id,
+                evidence_ids: origin.evidence_ids,
+            }
+        })
+        .collect();
+
+    let mut manifest = ExportManifestV2 {
+        manifest_version: 2,
+        bundle_type,
+        session_id: session_id.to_string(),
+        created_at_utc: crate::util::time::now_utc_iso(),
+        source_seq_range,
+        files,
+        warnings,
+        policy,
+        model_pins,
+        manifest_hash: String::new(),
+        bundle_hash,
+        signature: None,
     };
 
     let json = to_canonical_json(&manifest)?;
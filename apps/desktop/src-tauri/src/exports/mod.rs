@@ -1,6 +1,10 @@
 pub mod fs;
 pub mod manifest;
+pub mod player;
 pub mod proof_bundle;
 pub mod runbook;
+pub mod runbook_render;
+pub mod session_archive;
+pub mod signing;
 pub mod tutorial_pack;
 pub mod verify;
@@ -0,0 +1,71 @@
+(function () {
+  var steps = Array.prototype.slice.call(document.querySelectorAll('.step'));
+  var nav = document.getElementById('player-nav');
+  var search = document.getElementById('step-search');
+  var position = document.getElementById('step-position');
+  var bar = document.getElementById('progress-bar');
+  var empty = document.getElementById('no-matches');
+  var matches = steps;
+  var current = 0;
+
+  function render() {
+    steps.forEach(function (step) {
+      step.hidden = true;
+    });
+    empty.hidden = matches.length > 0;
+    if (matches.length === 0) {
+      position.textContent = 'No matching steps';
+      bar.style.width = '0%';
+      return;
+    }
+    matches[current].hidden = false;
+    position.textContent = 'Step ' + (current + 1) + ' of ' + matches.length;
+    bar.style.width = ((current + 1) / matches.length) * 100 + '%';
+  }
+
+  function go(index) {
+    if (matches.length === 0) {
+      return;
+    }
+    current = Math.max(0, Math.min(matches.length - 1, index));
+    render();
+  }
+
+  search.addEventListener('input', function () {
+    var query = search.value.trim().toLowerCase();
+    matches = steps.filter(function (step) {
+      return query === '' || step.getAttribute('data-search').indexOf(query) !== -1;
+    });
+    current = 0;
+    render();
+  });
+  document.getElementById('prev-step').addEventListener('click', function () {
+    go(current - 1);
+  });
+  document.getElementById('next-step').addEventListener('click', function () {
+    go(current + 1);
+  });
+  document.addEventListener('keydown', function (event) {
+    if (event.target === search) {
+      if (event.key === 'Escape') {
+        search.blur();
+      }
+      return;
+    }
+    if (event.key === 'ArrowRight' || event.key === 'j') {
+      go(current + 1);
+    } else if (event.key === 'ArrowLeft' || event.key === 'k') {
+      go(current - 1);
+    } else if (event.key === 'Home') {
+      go(0);
+    } else if (event.key === 'End') {
+      go(matches.length - 1);
+    } else if (event.key === '/') {
+      event.preventDefault();
+      search.focus();
+    }
+  });
+
+  nav.hidden = false;
+  render();
+})();
@@ -0,0 +1,227 @@
+use crate::exports::tutorial_pack::StepFrame;
+use chrono::{DateTime, SecondsFormat};
+use opscinema_types::{EvidenceItem, EvidenceLocatorType, Step};
+use std::collections::BTreeMap;
+
+/// Inlined into `player/index.html` so the pack runs from `file://` with no
+/// network or module loading.
+const PLAYER_SCRIPT: &str = include_str!("player.js");
+const OCR_SNIPPET_CHARS: usize = 160;
+
+pub struct PlayerInput<'a> {
+    pub steps: &'a [Step],
+    pub step_frames: &'a [StepFrame],
+    pub asset_paths: &'a BTreeMap<String, String>,
+    pub evidence: &'a [EvidenceItem],
+    pub strict_passed: bool,
+    pub missing_evidence_count: usize,
+}
+
+/// Renders the self-contained player. Every step is in the markup so the pack
+/// still reads top to bottom without scripting; the inline script adds
+/// one-step-at-a-time navigation, a progress bar and search.
+pub fn build_player_html(input: &PlayerInput<'_>) -> String {
+    let evidence_by_id = input
+        .evidence
+        .iter()
+        .map(|e| (e.evidence_id, e))
+        .collect::<BTreeMap<_, _>>();
+    let mut step_rows = String::new();
+    for step in input.steps {
+        let mut frame_rows = String::new();
+        for frame in input
+            .step_frames
+            .iter()
+            .filter(|f| f.step_id == step.step_id)
+        {
+            let mut overlays = String::new();
+            for bbox in &frame.anchor_boxes {
+                overlays.push_str(&format!(
+                    "<span class=\"anchor-box\" style=\"left:{};top:{};width:{};height:{}\"></span>",
+                    norm_percent(bbox.x),
+                    norm_percent(bbox.y),
+                    norm_percent(bbox.w),
+                    norm_percent(bbox.h)
+                ));
+            }
+            for click in &frame.clicks {
+                overlays.push_str(&format!(
+                    "<span class=\"click-dot\" style=\"left:{};top:{}\"></span>",
+                    norm_percent(click.x),
+                    norm_percent(click.y)
+                ));
+            }
+            frame_rows.push_str(&format!(
+                "<figure class=\"frame\"><div class=\"frame-canvas\"><img src=\"../{}\" alt=\"Step {} at {} ms\" />{}</div><figcaption>Frame at {} ms</figcaption></figure>",
+                escape_html(&input.asset_paths[&frame.asset_id]),
+                step.order_index + 1,
+                frame.frame_ms,
+                overlays,
+                frame.frame_ms
+            ));
+        }
+        let mut search_text = step.title.to_lowercase();
+        let mut block_rows = String::new();
+        for block in &step.body.blocks {
+            search_text.push('\n');
+            search_text.push_str(&block.text.to_lowercase());
+            let evidence = if block.evidence_refs.is_empty() {
+                "<p class=\"evidence\">Evidence: none</p>".to_string()
+            } else {
+                let items = block
+                    .evidence_refs
+                    .iter()
+                    .map(|id| evidence_row(&id.to_string(), evidence_by_id.get(id).copied()))
+                    .collect::<String>();
+                format!(
+                    "<details class=\"evidence\"><summary>Evidence: {} ref{}</summary><ul>{}</ul></details>",
+                    block.evidence_refs.len(),
+                    if block.evidence_refs.len() == 1 { "" } else { "s" },
+                    items
+                )
+            };
+            block_rows.push_str(&format!(
+                "<li><p class=\"block-text\">{}</p>{}</li>",
+                escape_html(&block.text),
+                evidence
+            ));
+        }
+        step_rows.push_str(&format!(
+            "<article class=\"step\" id=\"step-{}\" data-search=\"{}\"><h2>{}. {}</h2>{}<ul>{}</ul></article>",
+            step.order_index + 1,
+            escape_html(&search_text),
+            step.order_index + 1,
+            escape_html(&step.title),
+            frame_rows,
+            block_rows
+        ));
+    }
+
+    let strict_label = if input.strict_passed {
+        "PASS"
+    } else {
+        "BLOCKED"
+    };
+    let strict_class = if input.strict_passed {
+        "badge badge-pass"
+    } else {
+        "badge badge-block"
+    };
+    let missing_evidence_count = input.missing_evidence_count;
+
+    format!(
+        "<!doctype html>\
+<html lang=\"en\">\
+<head>\
+  <meta charset=\"utf-8\" />\
+  <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\" />\
+  <title>OpsCinema Tutorial Pack</title>\
+  <style>\
+    :root {{ --bg:#f7f7f7; --ink:#111; --muted:#666; --card:#fff; --line:#ddd; --ok:#1f7a1f; --bad:#9c1a1a; }}\
+    body {{ font-family: 'Avenir Next', 'Segoe UI', sans-serif; margin:0; background:var(--bg); color:var(--ink); }}\
+    main {{ max-width: 980px; margin: 0 auto; padding: 24px; }}\
+    .hero {{ background: linear-gradient(135deg, #ffffff, #eef5ff); border:1px solid var(--line); border-radius:14px; padding:18px 20px; margin-bottom:18px; }}\
+    h1 {{ margin:0 0 8px; font-size: 28px; }}\
+    .meta {{ color:var(--muted); margin: 0; font-size: 14px; }}\
+    .badge {{ display:inline-block; padding:5px 10px; border-radius:999px; font-weight:700; font-size:12px; margin-right:8px; }}\
+    .badge-pass {{ background:#e8f7e8; color:var(--ok); border:1px solid #b8e0b8; }}\
+    .badge-block {{ background:#fdecec; color:var(--bad); border:1px solid #f4bcbc; }}\
+    .step {{ background:var(--card); border:1px solid var(--line); border-radius:12px; padding:14px 16px; margin-bottom:10px; }}\
+    .step h2 {{ margin:0 0 8px; font-size:20px; }}\
+    .step ul {{ margin:0; padding-left:20px; }}\
+    .block-text {{ margin:0 0 4px; }}\
+    .evidence {{ margin:0 0 8px; color:var(--muted); font-size:13px; word-break:break-word; }}\
+    .evidence summary {{ cursor:pointer; }}\
+    .evidence ul {{ margin:6px 0 0; padding-left:18px; }}\
+    .evidence-kind {{ font-weight:700; color:var(--ink); }}\
+    .evidence q {{ display:block; margin-top:2px; font-style:italic; }}\
+    .player-nav {{ position:sticky; top:0; z-index:1; display:flex; flex-wrap:wrap; align-items:center; gap:8px; background:var(--bg); padding:10px 0; margin-bottom:10px; }}\
+    .player-nav input {{ flex:1; min-width:180px; padding:6px 10px; border:1px solid var(--line); border-radius:8px; }}\
+    .progress {{ flex-basis:100%; height:6px; background:var(--line); border-radius:999px; overflow:hidden; }}\
+    .progress-bar {{ height:100%; width:0; background:var(--ok); }}\
+    .frame {{ margin:0 0 12px; }}\
+    .frame-canvas {{ position:relative; display:inline-block; max-width:100%; }}\
+    .frame-canvas img {{ display:block; max-width:100%; border:1px solid var(--line); border-radius:8px; }}\
+    .anchor-box {{ position:absolute; border:2px solid #e8590c; border-radius:4px; box-sizing:border-box; }}\
+    .click-dot {{ position:absolute; width:14px; height:14px; margin:-7px 0 0 -7px; border-radius:50%; background:rgba(28,126,214,0.55); border:2px solid #1c7ed6; box-sizing:border-box; }}\
+    figcaption {{ color:var(--muted); font-size:12px; margin-top:4px; }}\
+  </style>\
+</head>\
+<body>\
+  <main>\
+    <section class=\"hero\">\
+      <h1>OpsCinema Tutorial Pack</h1>\
+      <p class=\"meta\">Follow these steps to complete the handoff flow. Each generated block includes evidence references.</p>\
+      <p><span class=\"{strict_class}\">Tutorial strict: {strict_label}</span><span class=\"badge\">Missing evidence refs: {missing_evidence_count}</span></p>\
+    </section>\
+    <nav class=\"player-nav\" id=\"player-nav\" hidden>\
+      <button type=\"button\" id=\"prev-step\">Previous</button>\
+      <span id=\"step-position\"></span>\
+      <button type=\"button\" id=\"next-step\">Next</button>\
+      <input type=\"search\" id=\"step-search\" placeholder=\"Search steps (/)\" aria-label=\"Search steps\" />\
+      <div class=\"progress\"><div class=\"progress-bar\" id=\"progress-bar\"></div></div>\
+    </nav>\
+    <p class=\"meta\" id=\"no-matches\" hidden>No steps match your search.</p>\
+    {step_rows}\
+  </main>\
+  <script>{PLAYER_SCRIPT}</script>\
+</body>\
+</html>"
+    )
+}
+
+/// One evidence reference: kind, capture time and OCR text when the id
+/// resolves, otherwise just the id.
+fn evidence_row(id: &str, item: Option<&EvidenceItem>) -> String {
+    let Some(item) = item else {
+        return format!(
+            "<li><code>{}</code> <span class=\"evidence-kind\">unresolved</span></li>",
+            escape_html(id)
+        );
+    };
+    let time = item
+        .locators
+        .iter()
+        .find_map(|l| l.frame_ms)
+        .and_then(DateTime::from_timestamp_millis)
+        .map(|t| {
+            let iso = t.to_rfc3339_opts(SecondsFormat::Millis, true);
+            format!(" <time datetime=\"{iso}\">{iso}</time>")
+        })
+        .unwrap_or_default();
+    let snippet = item
+        .locators
+        .iter()
+        .filter(|l| l.locator_type == EvidenceLocatorType::OcrBbox)
+        .find_map(|l| l.note.as_deref())
+        .map(|text| {
+            let mut snippet = text.chars().take(OCR_SNIPPET_CHARS).collect::<String>();
+            if text.chars().count() > OCR_SNIPPET_CHARS {
+                snippet.push('…');
+            }
+            format!("<q>{}</q>", escape_html(&snippet))
+        })
+        .unwrap_or_default();
+    format!(
+        "<li><code>{}</code> <span class=\"evidence-kind\">{}</span>{}{}</li>",
+        escape_html(id),
+        escape_html(&item.kind),
+        time,
+        snippet
+    )
+}
+
+/// Renders a 0–10000 normalized coordinate as a CSS percentage with integer
+/// math so the player bytes stay deterministic.
+fn norm_percent(value: u32) -> String {
+    format!("{}.{:02}%", value / 100, value % 100)
+}
+
+pub(crate) fn escape_html(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\"', "&quot;")
+        .replace('\'', "&#39;")
+}
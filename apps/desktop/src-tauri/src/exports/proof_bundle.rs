@@ -1,34 +1,66 @@
 use crate::exports::fs::write_file;
-use crate::exports::manifest::build_manifest;
+use crate::exports::manifest::{build_manifest_v2, BundleProvenance, FileProvenance};
 use crate::policy::export_gate::{proof_bundle_gate, ExportGateInput};
+use crate::policy::redaction::{RedactionReport, Redactor};
 use crate::util::canon_json::to_canonical_json;
-use opscinema_export_manifest::{BundleType, ManifestWarning, ModelPin, PolicyAttestations};
-use opscinema_types::{ExportResult, ExportWarning, Step};
+use opscinema_export_manifest::{
+    BundleType, EventSeqRange, ManifestWarning, ModelPin, PolicyAttestations,
+};
+use opscinema_types::{EvidenceRefIssue, ExportResult, ExportWarning, Step};
+use std::collections::BTreeMap;
 use std::path::Path;
 use uuid::Uuid;
 
+pub struct ProofBundleBuildOptions {
+    pub missing_evidence: Vec<String>,
+    pub invalid_evidence_refs: Vec<EvidenceRefIssue>,
+    pub model_pins: Vec<ModelPin>,
+    pub offline_policy_enforced: bool,
+    pub source_seq_range: EventSeqRange,
+    /// Session rules scrubbing step text; `None` exports unredacted.
+    pub redactor: Option<Redactor>,
+}
+
 pub fn export_proof_bundle(
     session_id: Uuid,
     steps: &[Step],
     warnings: &[ExportWarning],
-    missing_evidence: Vec<String>,
-    model_pins: Vec<ModelPin>,
-    offline_policy_enforced: bool,
+    options: ProofBundleBuildOptions,
     output_dir: &Path,
 ) -> anyhow::Result<ExportResult> {
+    let ProofBundleBuildOptions {
+        missing_evidence,
+        invalid_evidence_refs,
+        model_pins,
+        offline_policy_enforced,
+        source_seq_range,
+        redactor,
+    } = options;
     proof_bundle_gate(&ExportGateInput {
         steps: steps.to_vec(),
         missing_evidence: missing_evidence.clone(),
+        invalid_evidence_refs,
         degraded_anchor_ids: vec![],
         warnings: warnings.to_vec(),
     })?;
 
+    let mut report = RedactionReport::default();
+    let mut steps = steps.to_vec();
+    if let Some(redactor) = &redactor {
+        redactor.redact_steps(&mut steps, &mut report);
+    }
+    let steps = steps.as_slice();
+
     write_file(
         &output_dir.join("proof.json"),
         to_canonical_json(&serde_json::json!({"steps":steps,"warnings":warnings}))?.as_bytes(),
     )?;
 
-    let manifest = build_manifest(
+    let evidence_ids = crate::evidence::query::referenced_ids(steps)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    let manifest = build_manifest_v2(
         output_dir,
         BundleType::ProofBundle,
         &session_id.to_string(),
@@ -38,13 +70,25 @@ pub fn export_proof_bundle(
                 code: w.code.clone(),
                 message: w.message.clone(),
             })
+            .chain(report.manifest_warnings())
             .collect(),
         PolicyAttestations {
             evidence_coverage_passed: missing_evidence.is_empty(),
             tutorial_strict_passed: true,
             offline_policy_enforced,
+            redaction_rules_hash: redactor.as_ref().map(|r| r.rules_hash().to_string()),
         },
         model_pins,
+        BundleProvenance {
+            source_seq_range,
+            files: BTreeMap::from([(
+                "proof.json".to_string(),
+                FileProvenance {
+                    asset_id: None,
+                    evidence_ids,
+                },
+            )]),
+        },
     )?;
     write_file(
         &output_dir.join("manifest.json"),
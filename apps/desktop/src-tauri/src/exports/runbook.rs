@@ -1,34 +1,85 @@
 use crate::exports::fs::write_file;
-use crate::exports::manifest::build_manifest;
+use crate::exports::manifest::{build_manifest_v2, BundleProvenance, FileProvenance};
+use crate::exports::runbook_render::{render_html, render_markdown};
 use crate::policy::export_gate::{proof_bundle_gate, ExportGateInput};
+use crate::policy::redaction::{RedactionReport, RedactionScope, Redactor};
 use crate::util::canon_json::to_canonical_json;
-use opscinema_export_manifest::{BundleType, ManifestWarning, ModelPin, PolicyAttestations};
-use opscinema_types::{ExportResult, ExportWarning, RunbookDetail};
+use opscinema_export_manifest::{
+    BundleType, EventSeqRange, ManifestWarning, ModelPin, PolicyAttestations,
+};
+use opscinema_types::{
+    EvidenceRefIssue, ExportResult, ExportWarning, RunbookDetail, VerifierResultDetail,
+};
+use std::collections::BTreeMap;
 use std::path::Path;
 use uuid::Uuid;
 
+pub struct RunbookBuildOptions {
+    pub missing_evidence: Vec<String>,
+    pub invalid_evidence_refs: Vec<EvidenceRefIssue>,
+    pub model_pins: Vec<ModelPin>,
+    pub offline_policy_enforced: bool,
+    pub source_seq_range: EventSeqRange,
+    /// Session verifier runs, oldest first, inlined into the readable renders.
+    pub verifier_runs: Vec<VerifierResultDetail>,
+    /// Session rules scrubbing the title and step text; `None` exports
+    /// unredacted.
+    pub redactor: Option<Redactor>,
+}
+
 pub fn export_runbook(
     session_id: Uuid,
     runbook: &RunbookDetail,
     warnings: &[ExportWarning],
-    missing_evidence: Vec<String>,
-    model_pins: Vec<ModelPin>,
-    offline_policy_enforced: bool,
+    options: RunbookBuildOptions,
     output_dir: &Path,
 ) -> anyhow::Result<ExportResult> {
+    let RunbookBuildOptions {
+        missing_evidence,
+        invalid_evidence_refs,
+        model_pins,
+        offline_policy_enforced,
+        source_seq_range,
+        verifier_runs,
+        redactor,
+    } = options;
     proof_bundle_gate(&ExportGateInput {
         steps: runbook.steps.clone(),
         missing_evidence: missing_evidence.clone(),
+        invalid_evidence_refs,
         degraded_anchor_ids: vec![],
         warnings: warnings.to_vec(),
     })?;
 
+    let mut report = RedactionReport::default();
+    let mut runbook = runbook.clone();
+    if let Some(redactor) = &redactor {
+        runbook.title = redactor.redact_text(&runbook.title, RedactionScope::StepText, &mut report);
+        redactor.redact_steps(&mut runbook.steps, &mut report);
+    }
+    let runbook = &runbook;
+
     write_file(
         &output_dir.join("runbook.json"),
         to_canonical_json(runbook)?.as_bytes(),
     )?;
+    write_file(
+        &output_dir.join("runbook.md"),
+        render_markdown(runbook, &verifier_runs).as_bytes(),
+    )?;
+    write_file(
+        &output_dir.join("runbook.html"),
+        render_html(runbook, &verifier_runs).as_bytes(),
+    )?;
 
-    let manifest = build_manifest(
+    let step_evidence = FileProvenance {
+        asset_id: None,
+        evidence_ids: crate::evidence::query::referenced_ids(&runbook.steps)
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
+    };
+    let manifest = build_manifest_v2(
         output_dir,
         BundleType::Runbook,
         &session_id.to_string(),
@@ -38,13 +89,22 @@ pub fn export_runbook(
                 code: w.code.clone(),
                 message: w.message.clone(),
             })
+            .chain(report.manifest_warnings())
             .collect(),
         PolicyAttestations {
             evidence_coverage_passed: missing_evidence.is_empty(),
             tutorial_strict_passed: true,
             offline_policy_enforced,
+            redaction_rules_hash: redactor.as_ref().map(|r| r.rules_hash().to_string()),
         },
         model_pins,
+        BundleProvenance {
+            source_seq_range,
+            files: ["runbook.json", "runbook.md", "runbook.html"]
+                .into_iter()
+                .map(|path| (path.to_string(), step_evidence.clone()))
+                .collect::<BTreeMap<_, _>>(),
+        },
     )?;
     write_file(
         &output_dir.join("manifest.json"),
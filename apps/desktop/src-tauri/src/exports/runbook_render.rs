@@ -0,0 +1,160 @@
+use crate::exports::player::escape_html;
+use opscinema_types::{RunbookDetail, Step, VerifierResultDetail};
+
+/// Branch label (`None` for the main path) and its steps with their numbers.
+type Section<'a> = (Option<&'a str>, Vec<(usize, &'a Step)>);
+
+/// Groups consecutive steps sharing a `branch_label`.
+fn sections(steps: &[Step]) -> Vec<Section<'_>> {
+    let mut out: Vec<Section<'_>> = Vec::new();
+    for (idx, step) in steps.iter().enumerate() {
+        let label = step.branch_label.as_deref();
+        match out.last_mut() {
+            Some((current, members)) if *current == label => members.push((idx + 1, step)),
+            _ => out.push((label, vec![(idx + 1, step)])),
+        }
+    }
+    out
+}
+
+fn section_title(label: Option<&str>) -> String {
+    match label {
+        Some(label) => format!("Branch: {label}"),
+        None => "Steps".to_string(),
+    }
+}
+
+/// Markdown for wikis and change tickets. Steps are numbered in runbook order
+/// across branch sections so references stay stable when pasted piecemeal.
+pub fn render_markdown(runbook: &RunbookDetail, verifier_runs: &[VerifierResultDetail]) -> String {
+    let mut out = format!("# {}\n", runbook.title.trim());
+    for (label, members) in sections(&runbook.steps) {
+        out.push_str(&format!("\n## {}\n", section_title(label)));
+        for (number, step) in members {
+            out.push_str(&format!("\n### {number}. {}\n", step.title.trim()));
+            if !step.risk_tags.is_empty() {
+                let tags = step
+                    .risk_tags
+                    .iter()
+                    .map(|t| format!("`{t}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                out.push_str(&format!("\n> **Risk:** {tags}\n"));
+            }
+            for block in &step.body.blocks {
+                out.push_str(&format!("\n{}\n", block.text.trim()));
+                if !block.evidence_refs.is_empty() {
+                    let refs = block
+                        .evidence_refs
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    out.push_str(&format!("\n_Evidence: {refs}_\n"));
+                }
+            }
+        }
+    }
+    out.push_str("\n## Verifier results\n\n");
+    if verifier_runs.is_empty() {
+        out.push_str("No verifier runs recorded.\n");
+    } else {
+        out.push_str("| Verifier | Status | Run |\n| --- | --- | --- |\n");
+        for run in verifier_runs {
+            out.push_str(&format!(
+                "| {} | {} | `{}` |\n",
+                run.verifier_id.replace('|', "\\|"),
+                run.status.replace('|', "\\|"),
+                run.run_id
+            ));
+        }
+    }
+    out
+}
+
+/// Standalone HTML laid out for printing to PDF: no scripts, no external
+/// assets, and steps never split across pages.
+pub fn render_html(runbook: &RunbookDetail, verifier_runs: &[VerifierResultDetail]) -> String {
+    let mut body = String::new();
+    for (label, members) in sections(&runbook.steps) {
+        body.push_str(&format!(
+            "<section><h2>{}</h2>",
+            escape_html(&section_title(label))
+        ));
+        for (number, step) in members {
+            body.push_str(&format!(
+                "<article class=\"step\"><h3>{number}. {}</h3>",
+                escape_html(step.title.trim())
+            ));
+            if !step.risk_tags.is_empty() {
+                let tags = step
+                    .risk_tags
+                    .iter()
+                    .map(|t| format!("<code>{}</code>", escape_html(t)))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                body.push_str(&format!(
+                    "<aside class=\"risk\"><strong>Risk:</strong> {tags}</aside>"
+                ));
+            }
+            for block in &step.body.blocks {
+                body.push_str(&format!("<p>{}</p>", escape_html(block.text.trim())));
+                if !block.evidence_refs.is_empty() {
+                    let refs = block
+                        .evidence_refs
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    body.push_str(&format!("<p class=\"evidence\">Evidence: {refs}</p>"));
+                }
+            }
+            body.push_str("</article>");
+        }
+        body.push_str("</section>");
+    }
+    body.push_str("<section><h2>Verifier results</h2>");
+    if verifier_runs.is_empty() {
+        body.push_str("<p>No verifier runs recorded.</p>");
+    } else {
+        body.push_str(
+            "<table><thead><tr><th>Verifier</th><th>Status</th><th>Run</th></tr></thead><tbody>",
+        );
+        for run in verifier_runs {
+            body.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td><code>{}</code></td></tr>",
+                escape_html(&run.verifier_id),
+                escape_html(&run.status),
+                run.run_id
+            ));
+        }
+        body.push_str("</tbody></table>");
+    }
+    body.push_str("</section>");
+
+    let title = escape_html(runbook.title.trim());
+    format!(
+        "<!doctype html>\
+<html lang=\"en\">\
+<head>\
+  <meta charset=\"utf-8\" />\
+  <title>{title}</title>\
+  <style>\
+    body {{ font-family: Georgia, \"Times New Roman\", serif; color:#111; max-width:760px; margin:32px auto; padding:0 16px; line-height:1.45; }}\
+    h1 {{ font-size:26px; margin:0 0 16px; }}\
+    h2 {{ font-size:19px; border-bottom:1px solid #999; padding-bottom:4px; margin-top:28px; }}\
+    h3 {{ font-size:16px; margin:18px 0 6px; }}\
+    .risk {{ border-left:4px solid #b45309; background:#fff7ed; padding:6px 10px; margin:6px 0; }}\
+    .evidence {{ color:#555; font-size:12px; word-break:break-all; }}\
+    table {{ border-collapse:collapse; width:100%; }}\
+    th, td {{ border:1px solid #999; padding:4px 8px; text-align:left; font-size:13px; }}\
+    @media print {{ body {{ margin:0; max-width:none; }} .step, tr {{ break-inside:avoid; }} h2, h3 {{ break-after:avoid; }} }}\
+  </style>\
+</head>\
+<body>\
+  <h1>{title}</h1>\
+  {body}\
+</body>\
+</html>"
+    )
+}
@@ -0,0 +1,216 @@
+use crate::exports::fs::{ensure_dir, write_file};
+use crate::exports::manifest::file_entries;
+use crate::ocr::pipeline::OcrBlocksPersistedPayload;
+use crate::storage::asset_store::AssetStore;
+use crate::storage::event_store::{self, StoredEvent};
+use crate::storage::{gc, repo_ocr, repo_sessions, DbConn};
+use crate::util::canon_json::to_canonical_json;
+use opscinema_export_manifest::{compute_bundle_hash, SessionArchiveManifestV1};
+use opscinema_types::SessionDetail;
+use std::collections::BTreeSet;
+use std::io::{BufRead, Write};
+use std::path::Path;
+use uuid::Uuid;
+
+pub const ARCHIVE_MANIFEST_FILE: &str = "archive.json";
+pub const ARCHIVE_EVENTS_FILE: &str = "events.jsonl";
+pub const ARCHIVE_ASSETS_DIR: &str = "assets";
+
+/// Writes the session's canonical event log and every asset it references into
+/// `output_dir`, which must be empty or absent.
+pub fn export_session_archive(
+    conn: &DbConn,
+    store: &AssetStore,
+    session_id: Uuid,
+    output_dir: &Path,
+) -> anyhow::Result<SessionArchiveManifestV1> {
+    let SessionDetail {
+        summary: session,
+        metadata,
+    } = repo_sessions::get_session(conn, session_id)?
+        .ok_or_else(|| anyhow::anyhow!("session {session_id} not found"))?;
+    event_store::validate_hash_chain(conn, session_id)?;
+
+    ensure_dir(output_dir)?;
+    if std::fs::read_dir(output_dir)?.next().is_some() {
+        anyhow::bail!("archive output dir {} is not empty", output_dir.display())
+    }
+
+    let mut asset_ids = BTreeSet::new();
+    let mut event_count = 0_u64;
+    let events_path = output_dir.join(ARCHIVE_EVENTS_FILE);
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(&events_path)?);
+    event_store::for_each_stored_event(conn, session_id, |event| {
+        if let Ok(payload) = serde_json::from_str::<serde_json::Value>(&event.payload_canon_json) {
+            gc::collect_asset_ids_from_json(&payload, &mut asset_ids);
+        }
+        writer.write_all(to_canonical_json(&event)?.as_bytes())?;
+        writer.write_all(b"\n")?;
+        event_count += 1;
+        Ok(())
+    })?;
+    writer.flush()?;
+    writer.get_ref().sync_all()?;
+
+    for asset_id in &asset_ids {
+        let bytes = std::fs::read(store.path_for(asset_id))
+            .map_err(|e| anyhow::anyhow!("referenced asset {asset_id} unreadable: {e}"))?;
+        write_file(&output_dir.join(ARCHIVE_ASSETS_DIR).join(asset_id), &bytes)?;
+    }
+
+    let files = file_entries(output_dir, ARCHIVE_MANIFEST_FILE)?;
+    let manifest = SessionArchiveManifestV1 {
+        archive_version: 1,
+        session_id: session_id.to_string(),
+        label: session.label,
+        session_created_at_utc: session.created_at.to_rfc3339(),
+        session_closed_at_utc: session.closed_at.map(|t| t.to_rfc3339()),
+        session_metadata: metadata,
+        created_at_utc: crate::util::time::now_utc_iso(),
+        event_count,
+        head_seq: session.head_seq,
+        head_hash: session.head_hash,
+        archive_hash: archive_hash(&files),
+        files,
+    };
+    write_file(
+        &output_dir.join(ARCHIVE_MANIFEST_FILE),
+        to_canonical_json(&manifest)?.as_bytes(),
+    )?;
+    Ok(manifest)
+}
+
+pub fn read_archive_manifest(archive_dir: &Path) -> anyhow::Result<SessionArchiveManifestV1> {
+    let raw = std::fs::read_to_string(archive_dir.join(ARCHIVE_MANIFEST_FILE))?;
+    let manifest: SessionArchiveManifestV1 = serde_json::from_str(&raw)?;
+    if manifest.archive_version != 1 {
+        anyhow::bail!("unsupported archive_version {}", manifest.archive_version)
+    }
+    Ok(manifest)
+}
+
+/// Verifies file hashes and the event hash chain against the manifest head,
+/// then inserts the session, its events and its assets in one transaction.
+pub fn import_session_archive(
+    conn: &mut DbConn,
+    store: &AssetStore,
+    archive_dir: &Path,
+) -> anyhow::Result<SessionArchiveManifestV1> {
+    let manifest = read_archive_manifest(archive_dir)?;
+    let session_id = Uuid::parse_str(&manifest.session_id)?;
+    verify_archive_files(archive_dir, &manifest)?;
+
+    let head = event_store::verify_chain(session_id, &manifest.label, read_events(archive_dir)?)?;
+    if head.seq != manifest.head_seq || (head.seq > 0 && head.hash != manifest.head_hash) {
+        anyhow::bail!(
+            "archive head_hash mismatch: chain ends at seq {} ({}), manifest claims seq {} ({})",
+            head.seq,
+            head.hash,
+            manifest.head_seq,
+            manifest.head_hash
+        )
+    }
+
+    let event_count = read_events(archive_dir)?.count() as u64;
+    if event_count != manifest.event_count {
+        anyhow::bail!(
+            "archive event_count mismatch: {event_count} events, manifest claims {}",
+            manifest.event_count
+        )
+    }
+
+    let tx = conn.transaction()?;
+    if repo_sessions::get_session(&tx, session_id)?.is_some() {
+        anyhow::bail!("session {session_id} already exists")
+    }
+    repo_sessions::insert_imported_session(
+        &tx,
+        session_id,
+        &manifest.label,
+        &manifest.session_created_at_utc,
+        manifest.session_closed_at_utc.as_deref(),
+        manifest.head_seq,
+        &manifest.head_hash,
+    )?;
+    repo_sessions::set_metadata(&tx, session_id, &manifest.session_metadata)?;
+    for event in read_events(archive_dir)? {
+        let event = event?;
+        event_store::insert_stored_event(&tx, session_id, &event)?;
+        if event.event_type == "OcrBlocksPersisted" {
+            let payload: OcrBlocksPersistedPayload =
+                serde_json::from_str(&event.payload_canon_json)?;
+            repo_ocr::upsert_blocks(
+                &tx,
+                session_id,
+                payload.frame_event_id,
+                payload.frame_ms,
+                &payload.blocks,
+            )?;
+        }
+    }
+    event_store::validate_hash_chain(&tx, session_id)?;
+
+    let assets_root = archive_dir.join(ARCHIVE_ASSETS_DIR);
+    for file in &manifest.files {
+        let Some(asset_id) = file
+            .path
+            .strip_prefix(ARCHIVE_ASSETS_DIR)
+            .and_then(|rest| rest.strip_prefix('/'))
+        else {
+            continue;
+        };
+        let bytes = std::fs::read(assets_root.join(asset_id))?;
+        let stored = store.put(&tx, &bytes, None)?;
+        if stored != asset_id {
+            anyhow::bail!("asset {asset_id} content does not match its id")
+        }
+    }
+    tx.commit()?;
+    Ok(manifest)
+}
+
+fn verify_archive_files(
+    archive_dir: &Path,
+    manifest: &SessionArchiveManifestV1,
+) -> anyhow::Result<()> {
+    let actual = file_entries(archive_dir, ARCHIVE_MANIFEST_FILE)?;
+    for entry in &actual {
+        match manifest.files.iter().find(|f| f.path == entry.path) {
+            None => anyhow::bail!("undeclared file {}", entry.path),
+            Some(f) if f != entry => anyhow::bail!("hash mismatch {}", entry.path),
+            Some(_) => {}
+        }
+    }
+    if let Some(missing) = manifest
+        .files
+        .iter()
+        .find(|f| !actual.iter().any(|a| a.path == f.path))
+    {
+        anyhow::bail!("missing file {}", missing.path)
+    }
+    if !actual.iter().any(|f| f.path == ARCHIVE_EVENTS_FILE) {
+        anyhow::bail!("archive is missing {ARCHIVE_EVENTS_FILE}")
+    }
+    if archive_hash(&manifest.files) != manifest.archive_hash {
+        anyhow::bail!("archive_hash mismatch")
+    }
+    Ok(())
+}
+
+fn read_events(
+    archive_dir: &Path,
+) -> anyhow::Result<impl Iterator<Item = anyhow::Result<StoredEvent>>> {
+    let file = std::fs::File::open(archive_dir.join(ARCHIVE_EVENTS_FILE))?;
+    Ok(std::io::BufReader::new(file)
+        .lines()
+        .filter(|line| !matches!(line, Ok(l) if l.is_empty()))
+        .map(|line| Ok(serde_json::from_str::<StoredEvent>(&line?)?)))
+}
+
+fn archive_hash(files: &[opscinema_export_manifest::ManifestFileEntry]) -> String {
+    let tuples = files
+        .iter()
+        .map(|f| (f.path.clone(), f.hash_blake3.clone()))
+        .collect::<Vec<_>>();
+    compute_bundle_hash(&tuples)
+}
@@ -0,0 +1,70 @@
+use crate::exports::fs::write_file;
+use crate::util::canon_json::to_canonical_json;
+use crate::util::hash::{hex_decode, hex_encode};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use opscinema_export_manifest::{ExportManifest, ManifestSignature, SIGNATURE_ALGORITHM_ED25519};
+use opscinema_types::ExportSignatureStatus;
+use std::path::Path;
+
+/// Bytes covered by the signature: the canonical manifest without its
+/// signature block.
+fn signing_payload(manifest: &ExportManifest) -> anyhow::Result<Vec<u8>> {
+    let mut unsigned = manifest.clone();
+    unsigned.set_signature(None);
+    Ok(to_canonical_json(&unsigned)?.into_bytes())
+}
+
+pub fn sign_manifest(manifest: &mut ExportManifest, key: &SigningKey) -> anyhow::Result<()> {
+    let signature = key.sign(&signing_payload(manifest)?);
+    manifest.set_signature(Some(ManifestSignature {
+        algorithm: SIGNATURE_ALGORITHM_ED25519.to_string(),
+        public_key: hex_encode(key.verifying_key().as_bytes()),
+        signature: hex_encode(&signature.to_bytes()),
+    }));
+    Ok(())
+}
+
+/// Signs `manifest.json` of an already written bundle in place. File hashes and
+/// `bundle_hash` are unaffected because the manifest is not part of them.
+pub fn sign_bundle(root: &Path, key: &SigningKey) -> anyhow::Result<ExportManifest> {
+    let path = root.join("manifest.json");
+    let mut manifest = ExportManifest::from_json(&std::fs::read_to_string(&path)?)?;
+    sign_manifest(&mut manifest, key)?;
+    write_file(&path, to_canonical_json(&manifest)?.as_bytes())?;
+    Ok(manifest)
+}
+
+pub fn check_signature(
+    manifest: &ExportManifest,
+    trusted_public_keys: &[String],
+) -> anyhow::Result<ExportSignatureStatus> {
+    let Some(sig) = manifest.signature() else {
+        return Ok(ExportSignatureStatus::Unsigned);
+    };
+    if sig.algorithm != SIGNATURE_ALGORITHM_ED25519 {
+        return Ok(ExportSignatureStatus::Invalid);
+    }
+    let public_key = hex_decode(&sig.public_key)
+        .and_then(|b| <[u8; 32]>::try_from(b).ok())
+        .and_then(|b| VerifyingKey::from_bytes(&b).ok());
+    let signature = hex_decode(&sig.signature)
+        .and_then(|b| <[u8; 64]>::try_from(b).ok())
+        .map(|b| Signature::from_bytes(&b));
+    let (Some(public_key), Some(signature)) = (public_key, signature) else {
+        return Ok(ExportSignatureStatus::Invalid);
+    };
+    if public_key
+        .verify(&signing_payload(manifest)?, &signature)
+        .is_err()
+    {
+        return Ok(ExportSignatureStatus::Invalid);
+    }
+    if trusted_public_keys
+        .iter()
+        .any(|k| k.eq_ignore_ascii_case(&sig.public_key))
+    {
+        Ok(ExportSignatureStatus::Trusted)
+    } else {
+        Ok(ExportSignatureStatus::Untrusted)
+    }
+}
@@ -1,18 +1,184 @@
 use crate::exports::fs::write_file;
-use crate::exports::manifest::build_manifest;
-use crate::policy::export_gate::{tutorial_pack_gate, ExportGateInput};
+use crate::exports::manifest::{build_manifest_v2, BundleProvenance, FileProvenance};
+use crate::exports::player::{build_player_html, PlayerInput};
+use crate::policy::export_gate::{blocks_strict_gate, tutorial_pack_gate, ExportGateInput};
+use crate::policy::redaction::{RedactionReport, Redactor};
 use crate::util::canon_json::to_canonical_json;
-use opscinema_export_manifest::{BundleType, ManifestWarning, ModelPin, PolicyAttestations};
-use opscinema_types::{ExportResult, ExportWarning, Step};
-use std::path::Path;
+use opscinema_export_manifest::{
+    BundleType, EventSeqRange, ManifestWarning, ModelPin, PolicyAttestations,
+};
+use opscinema_types::{
+    AnchorCandidate, BBoxNorm, EvidenceItem, EvidenceLocator, EvidenceLocatorType,
+    EvidenceRefIssue, EvidenceSet, ExportResult, ExportWarning, Step, StepId, TimelineKeyframe,
+};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
+pub const TUTORIAL_ASSETS_DIR: &str = "assets";
+
 pub struct TutorialPackBuildOptions {
     pub missing_evidence: Vec<String>,
+    pub invalid_evidence_refs: Vec<EvidenceRefIssue>,
     pub degraded_anchor_ids: Vec<String>,
     pub warnings: Vec<ExportWarning>,
     pub model_pins: Vec<ModelPin>,
     pub offline_policy_enforced: bool,
+    pub source_seq_range: EventSeqRange,
+    pub evidence_assets: Vec<EvidenceAsset>,
+    pub step_frames: Vec<StepFrame>,
+    /// Evidence the steps reference, resolved in the player's evidence panels.
+    pub evidence: Vec<EvidenceItem>,
+    /// Session rules scrubbing step text, evidence and assets; `None` exports
+    /// unredacted and leaves the manifest without a redaction attestation.
+    pub redactor: Option<Redactor>,
+}
+
+/// A stored asset backing step evidence, copied into the pack under
+/// `assets/<asset_id>.<ext>`.
+pub struct EvidenceAsset {
+    pub asset_id: String,
+    pub source_path: PathBuf,
+    pub evidence_ids: Vec<Uuid>,
+    /// Boxes painted over a keyframe asset, labelled by the rule behind them
+    /// (see [`Redactor::frame_boxes`]).
+    pub redact_boxes: Vec<(BBoxNorm, String)>,
+}
+
+/// A keyframe shown with a step in the player. Overlays use the normalized
+/// 0–10000 `BBoxNorm` space of the frame; clicks are 1x1 boxes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepFrame {
+    pub step_id: StepId,
+    pub frame_ms: i64,
+    pub asset_id: String,
+    pub evidence_ids: Vec<Uuid>,
+    pub anchor_boxes: Vec<BBoxNorm>,
+    pub clicks: Vec<BBoxNorm>,
+}
+
+/// Picks the keyframes each step's evidence and anchors point at. A locator
+/// maps to the keyframe with its `asset_id`, else to the keyframe nearest its
+/// `frame_ms` (the earlier one on ties). Anchor locators without either land on the step's
+/// first frame, or the session's first keyframe, which is what anchors are
+/// resolved against. Clicks are drawn on every shown frame they map to.
+pub fn resolve_step_frames(
+    steps: &[Step],
+    evidence: &EvidenceSet,
+    anchors: &[AnchorCandidate],
+    keyframes: &[TimelineKeyframe],
+) -> Vec<StepFrame> {
+    let mut keyframes = keyframes.iter().collect::<Vec<_>>();
+    keyframes.sort_by_key(|k| k.frame_ms);
+    let locate = |locator: &EvidenceLocator| -> Option<&TimelineKeyframe> {
+        let by_asset = locator
+            .asset_id
+            .as_deref()
+            .and_then(|id| keyframes.iter().find(|k| k.asset.asset_id == id));
+        by_asset
+            .or_else(|| {
+                let frame_ms = locator.frame_ms?;
+                keyframes
+                    .iter()
+                    .min_by_key(|k| k.frame_ms.abs_diff(frame_ms))
+            })
+            .copied()
+    };
+    let clicks = evidence
+        .evidence
+        .iter()
+        .filter(|e| e.kind == "Click")
+        .flat_map(|e| e.locators.iter())
+        .filter_map(|l| Some((locate(l)?.frame_ms, l.bbox_norm.clone()?)))
+        .collect::<Vec<_>>();
+
+    let mut out = Vec::new();
+    for step in steps {
+        let referenced = crate::evidence::query::referenced_ids(std::slice::from_ref(step));
+        let mut frames: BTreeMap<i64, StepFrame> = BTreeMap::new();
+        let mut anchor_boxes = Vec::new();
+        let mut frame_evidence = Vec::new();
+        for item in evidence
+            .evidence
+            .iter()
+            .filter(|e| referenced.contains(&e.evidence_id))
+        {
+            for locator in &item.locators {
+                let Some(keyframe) = locate(locator) else {
+                    continue;
+                };
+                let frame_ms = add_frame(&mut frames, step.step_id, keyframe);
+                frame_evidence.push((frame_ms, item.evidence_id));
+                if locator.locator_type == EvidenceLocatorType::AnchorBbox {
+                    if let Some(bbox) = &locator.bbox_norm {
+                        anchor_boxes.push((frame_ms, bbox.clone()));
+                    }
+                }
+            }
+        }
+        let unplaced = anchors
+            .iter()
+            .filter(|a| a.step_id == step.step_id)
+            .flat_map(|a| a.locators.iter())
+            .filter(|l| l.locator_type == EvidenceLocatorType::AnchorBbox)
+            .filter_map(|l| Some((locate(l), l.bbox_norm.clone()?)))
+            .collect::<Vec<_>>();
+        for (keyframe, bbox) in unplaced {
+            let fallback = frames
+                .keys()
+                .next()
+                .and_then(|ms| keyframes.iter().find(|k| k.frame_ms == *ms))
+                .or_else(|| keyframes.first())
+                .copied();
+            if let Some(keyframe) = keyframe.or(fallback) {
+                let frame_ms = add_frame(&mut frames, step.step_id, keyframe);
+                anchor_boxes.push((frame_ms, bbox));
+            }
+        }
+
+        for frame in frames.values_mut() {
+            frame.evidence_ids = frame_evidence
+                .iter()
+                .filter(|(ms, _)| *ms == frame.frame_ms)
+                .map(|(_, id)| *id)
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect();
+            frame.anchor_boxes = sorted_boxes(&anchor_boxes, frame.frame_ms);
+            frame.clicks = sorted_boxes(&clicks, frame.frame_ms);
+        }
+        out.extend(frames.into_values());
+    }
+    out
+}
+
+fn add_frame(
+    frames: &mut BTreeMap<i64, StepFrame>,
+    step_id: StepId,
+    keyframe: &TimelineKeyframe,
+) -> i64 {
+    frames
+        .entry(keyframe.frame_ms)
+        .or_insert_with(|| StepFrame {
+            step_id,
+            frame_ms: keyframe.frame_ms,
+            asset_id: keyframe.asset.asset_id.clone(),
+            evidence_ids: vec![],
+            anchor_boxes: vec![],
+            clicks: vec![],
+        })
+        .frame_ms
+}
+
+fn sorted_boxes(boxes: &[(i64, BBoxNorm)], frame_ms: i64) -> Vec<BBoxNorm> {
+    let mut out = boxes
+        .iter()
+        .filter(|(ms, _)| *ms == frame_ms)
+        .map(|(_, b)| b.clone())
+        .collect::<Vec<_>>();
+    out.sort_by_key(|b| (b.y, b.x, b.w, b.h));
+    out.dedup();
+    out
 }
 
 pub fn export_tutorial_pack(
@@ -23,32 +189,102 @@ pub fn export_tutorial_pack(
 ) -> anyhow::Result<ExportResult> {
     let TutorialPackBuildOptions {
         missing_evidence,
+        invalid_evidence_refs,
         degraded_anchor_ids,
         warnings,
         model_pins,
         offline_policy_enforced,
+        source_seq_range,
+        evidence_assets,
+        step_frames,
+        mut evidence,
+        redactor,
     } = options;
-    let strict_passed =
-        missing_evidence.is_empty() && degraded_anchor_ids.is_empty() && warnings.is_empty();
+    let strict_passed = missing_evidence.is_empty()
+        && degraded_anchor_ids.is_empty()
+        && !warnings.iter().any(blocks_strict_gate);
     tutorial_pack_gate(&ExportGateInput {
         steps: steps.to_vec(),
         missing_evidence: missing_evidence.clone(),
+        invalid_evidence_refs,
         degraded_anchor_ids: degraded_anchor_ids.clone(),
         warnings: warnings.clone(),
     })?;
 
+    let mut report = RedactionReport::default();
+    let mut steps = steps.to_vec();
+    if let Some(redactor) = &redactor {
+        redactor.redact_steps(&mut steps, &mut report);
+        redactor.redact_evidence(&mut evidence, &mut report);
+    }
+    let steps = steps.as_slice();
+
     let tutorial_json = to_canonical_json(&serde_json::json!({
         "schema_version": 1,
         "steps": steps,
     }))?;
     write_file(&output_dir.join("tutorial.json"), tutorial_json.as_bytes())?;
-    let player_html = build_player_html(steps, strict_passed, missing_evidence.len());
+
+    let step_evidence = FileProvenance {
+        asset_id: None,
+        evidence_ids: crate::evidence::query::referenced_ids(steps)
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
+    };
+    let mut files = BTreeMap::from([
+        ("tutorial.json".to_string(), step_evidence.clone()),
+        ("player/index.html".to_string(), step_evidence),
+    ]);
+    let mut asset_paths = BTreeMap::new();
+    for asset in evidence_assets {
+        let mut bytes = std::fs::read(&asset.source_path)
+            .map_err(|e| anyhow::anyhow!("evidence asset {} unreadable: {e}", asset.asset_id))?;
+        // Keyframes are PNG by capture contract; other assets are sniffed.
+        let extension = if step_frames.iter().any(|f| f.asset_id == asset.asset_id) {
+            "png"
+        } else {
+            asset_extension(&bytes)
+        };
+        // A redacted file is no longer the stored asset, so it keeps the
+        // asset's path and evidence links but drops the asset_id claim.
+        let mut redacted = false;
+        if let Some(redactor) = &redactor {
+            let scrubbed = redact_asset(redactor, &asset, extension, &bytes, &mut report)?;
+            redacted = scrubbed != bytes;
+            bytes = scrubbed;
+        }
+        let rel = format!("{TUTORIAL_ASSETS_DIR}/{}.{extension}", asset.asset_id);
+        write_file(&output_dir.join(&rel), &bytes)?;
+        asset_paths.insert(asset.asset_id.clone(), rel.clone());
+        files.insert(
+            rel,
+            FileProvenance {
+                asset_id: (!redacted).then_some(asset.asset_id),
+                evidence_ids: asset.evidence_ids.iter().map(ToString::to_string).collect(),
+            },
+        );
+    }
+    if let Some(frame) = step_frames
+        .iter()
+        .find(|f| !asset_paths.contains_key(&f.asset_id))
+    {
+        anyhow::bail!("keyframe asset {} was not provided", frame.asset_id)
+    }
+    let player_html = build_player_html(&PlayerInput {
+        steps,
+        step_frames: &step_frames,
+        asset_paths: &asset_paths,
+        evidence: &evidence,
+        strict_passed,
+        missing_evidence_count: missing_evidence.len(),
+    });
     write_file(
         &output_dir.join("player/index.html"),
         player_html.as_bytes(),
     )?;
 
-    let manifest = build_manifest(
+    let manifest = build_manifest_v2(
         output_dir,
         BundleType::TutorialPack,
         &session_id.to_string(),
@@ -58,13 +294,19 @@ pub fn export_tutorial_pack(
                 code: w.code.clone(),
                 message: w.message.clone(),
             })
+            .chain(report.manifest_warnings())
             .collect(),
         PolicyAttestations {
             evidence_coverage_passed: missing_evidence.is_empty(),
             tutorial_strict_passed: strict_passed,
             offline_policy_enforced,
+            redaction_rules_hash: redactor.as_ref().map(|r| r.rules_hash().to_string()),
         },
         model_pins,
+        BundleProvenance {
+            source_seq_range,
+            files,
+        },
     )?;
     let manifest_json = to_canonical_json(&manifest)?;
     write_file(&output_dir.join("manifest.json"), manifest_json.as_bytes())?;
@@ -77,85 +319,41 @@ pub fn export_tutorial_pack(
     })
 }
 
-fn build_player_html(steps: &[Step], strict_passed: bool, missing_evidence_count: usize) -> String {
-    let mut step_rows = String::new();
-    for step in steps {
-        let mut block_rows = String::new();
-        for block in &step.body.blocks {
-            let refs = if block.evidence_refs.is_empty() {
-                "none".to_string()
-            } else {
-                block
-                    .evidence_refs
-                    .iter()
-                    .map(ToString::to_string)
-                    .collect::<Vec<_>>()
-                    .join(", ")
+/// Paints redaction boxes over PNG assets and scrubs strings in JSON assets;
+/// untouched assets come back byte-identical.
+fn redact_asset(
+    redactor: &Redactor,
+    asset: &EvidenceAsset,
+    extension: &str,
+    bytes: &[u8],
+    report: &mut RedactionReport,
+) -> anyhow::Result<Vec<u8>> {
+    match extension {
+        "png" => redactor
+            .redact_png(bytes, &asset.redact_boxes, report)
+            .map_err(|e| anyhow::anyhow!("keyframe {} redaction failed: {e}", asset.asset_id)),
+        "json" => {
+            let Ok(original) = serde_json::from_slice::<serde_json::Value>(bytes) else {
+                return Ok(bytes.to_vec());
             };
-            block_rows.push_str(&format!(
-                "<li><p class=\"block-text\">{}</p><p class=\"evidence\">Evidence: {}</p></li>",
-                escape_html(&block.text),
-                escape_html(&refs)
-            ));
+            let mut scrubbed = original.clone();
+            redactor.redact_json(&mut scrubbed, report);
+            if scrubbed == original {
+                Ok(bytes.to_vec())
+            } else {
+                Ok(to_canonical_json(&scrubbed)?.into_bytes())
+            }
         }
-        step_rows.push_str(&format!(
-            "<article class=\"step\"><h2>{}. {}</h2><ul>{}</ul></article>",
-            step.order_index + 1,
-            escape_html(&step.title),
-            block_rows
-        ));
+        _ => Ok(bytes.to_vec()),
     }
-
-    let strict_label = if strict_passed { "PASS" } else { "BLOCKED" };
-    let strict_class = if strict_passed {
-        "badge badge-pass"
-    } else {
-        "badge badge-block"
-    };
-
-    format!(
-        "<!doctype html>\
-<html lang=\"en\">\
-<head>\
-  <meta charset=\"utf-8\" />\
-  <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\" />\
-  <title>OpsCinema Tutorial Pack</title>\
-  <style>\
-    :root {{ --bg:#f7f7f7; --ink:#111; --muted:#666; --card:#fff; --line:#ddd; --ok:#1f7a1f; --bad:#9c1a1a; }}\
-    body {{ font-family: 'Avenir Next', 'Segoe UI', sans-serif; margin:0; background:var(--bg); color:var(--ink); }}\
-    main {{ max-width: 980px; margin: 0 auto; padding: 24px; }}\
-    .hero {{ background: linear-gradient(135deg, #ffffff, #eef5ff); border:1px solid var(--line); border-radius:14px; padding:18px 20px; margin-bottom:18px; }}\
-    h1 {{ margin:0 0 8px; font-size: 28px; }}\
-    .meta {{ color:var(--muted); margin: 0; font-size: 14px; }}\
-    .badge {{ display:inline-block; padding:5px 10px; border-radius:999px; font-weight:700; font-size:12px; margin-right:8px; }}\
-    .badge-pass {{ background:#e8f7e8; color:var(--ok); border:1px solid #b8e0b8; }}\
-    .badge-block {{ background:#fdecec; color:var(--bad); border:1px solid #f4bcbc; }}\
-    .step {{ background:var(--card); border:1px solid var(--line); border-radius:12px; padding:14px 16px; margin-bottom:10px; }}\
-    .step h2 {{ margin:0 0 8px; font-size:20px; }}\
-    .step ul {{ margin:0; padding-left:20px; }}\
-    .block-text {{ margin:0 0 4px; }}\
-    .evidence {{ margin:0 0 8px; color:var(--muted); font-size:13px; word-break:break-word; }}\
-  </style>\
-</head>\
-<body>\
-  <main>\
-    <section class=\"hero\">\
-      <h1>OpsCinema Tutorial Pack</h1>\
-      <p class=\"meta\">Follow these steps to complete the handoff flow. Each generated block includes evidence references.</p>\
-      <p><span class=\"{strict_class}\">Tutorial strict: {strict_label}</span><span class=\"badge\">Missing evidence refs: {missing_evidence_count}</span></p>\
-    </section>\
-    {step_rows}\
-  </main>\
-</body>\
-</html>"
-    )
 }
 
-fn escape_html(input: &str) -> String {
-    input
-        .replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-        .replace('\"', "&quot;")
-        .replace('\'', "&#39;")
+fn asset_extension(bytes: &[u8]) -> &'static str {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        "png"
+    } else if bytes.first().is_some_and(|b| *b == b'{' || *b == b'[') {
+        "json"
+    } else {
+        "bin"
+    }
 }
@@ -1,59 +1,81 @@
 use crate::exports::fs::list_files_sorted;
+use crate::exports::signing::check_signature;
+use crate::model_dock::verify::MODEL_DIGEST_UNVERIFIED_WARNING_CODE;
+use crate::policy::redaction::REDACTION_WARNING_CODE;
 use crate::util::hash::blake3_hex;
-use opscinema_export_manifest::{BundleType, ExportManifestV1};
-use opscinema_types::ExportVerifyResponse;
+use opscinema_export_manifest::{BundleType, ExportManifest, ExportManifestV2};
+use opscinema_types::{ExportSignatureStatus, ExportVerifyResponse};
 use std::path::Path;
 
-pub fn verify_bundle(root: &Path) -> anyhow::Result<ExportVerifyResponse> {
+/// Checks manifest policy, file hashes and the manifest signature. Accepts v1 and
+/// v2 manifests. A bundle is only valid when it is signed by one of
+/// `trusted_public_keys`.
+pub fn verify_bundle(
+    root: &Path,
+    trusted_public_keys: &[String],
+) -> anyhow::Result<ExportVerifyResponse> {
     let manifest_path = root.join("manifest.json");
     let manifest_raw = std::fs::read_to_string(&manifest_path)?;
-    let manifest: ExportManifestV1 = serde_json::from_str(&manifest_raw)?;
+    let manifest = ExportManifest::from_json(&manifest_raw)?;
 
     let mut issues = Vec::new();
-    if manifest.manifest_version != 1 {
+    if !matches!(manifest.manifest_version(), 1 | 2) {
         issues.push(format!(
             "unsupported manifest_version {}",
-            manifest.manifest_version
+            manifest.manifest_version()
         ));
     }
-    if !manifest.policy.evidence_coverage_passed {
+    let policy = manifest.policy();
+    if !policy.evidence_coverage_passed {
         issues.push("policy attestation failed: evidence coverage".to_string());
     }
-    if !manifest.policy.offline_policy_enforced {
+    if !policy.offline_policy_enforced {
         issues.push("policy attestation failed: offline policy".to_string());
     }
-    if matches!(manifest.bundle_type, BundleType::TutorialPack) {
-        if !manifest.warnings.is_empty() {
+    if matches!(manifest.bundle_type(), BundleType::TutorialPack) {
+        // Redaction counts disclose what was scrubbed; they only count as
+        // warnings when the manifest does not attest the rules that made them.
+        // Unverified model pins are recorded without failing the strict gate.
+        let redaction_attested = policy.redaction_rules_hash.is_some();
+        if manifest.warnings().iter().any(|w| {
+            !(redaction_attested && w.code == REDACTION_WARNING_CODE)
+                && w.code != MODEL_DIGEST_UNVERIFIED_WARNING_CODE
+        }) {
             issues.push("tutorial bundle contains warnings".to_string());
         }
-        if !manifest.policy.tutorial_strict_passed {
+        if !policy.tutorial_strict_passed {
             issues.push("policy attestation failed: tutorial strictness".to_string());
         }
     }
 
-    for file in &manifest.files {
-        let p = root.join(&file.path);
+    let declared = manifest.file_hashes();
+    for (path, expected_hash) in &declared {
+        let p = root.join(path);
         if !p.exists() {
-            issues.push(format!("missing file {}", file.path));
+            issues.push(format!("missing file {}", path));
             continue;
         }
         let bytes = std::fs::read(&p)?;
         let hash = blake3_hex(&bytes);
-        if hash != file.hash_blake3 {
-            issues.push(format!("hash mismatch {}", file.path));
+        if hash != *expected_hash {
+            issues.push(format!("hash mismatch {}", path));
         }
     }
 
-    let mut tuples = Vec::new();
-    for file in &manifest.files {
-        tuples.push((file.path.clone(), file.hash_blake3.clone()));
-    }
+    let mut tuples = declared
+        .iter()
+        .map(|(path, hash)| (path.to_string(), hash.to_string()))
+        .collect::<Vec<_>>();
     tuples.sort();
     let expected_bundle_hash = opscinema_export_manifest::compute_bundle_hash(&tuples);
-    if expected_bundle_hash != manifest.bundle_hash {
+    if expected_bundle_hash != manifest.bundle_hash() {
         issues.push("bundle_hash mismatch".to_string());
     }
 
+    if let ExportManifest::V2(v2) = &manifest {
+        verify_v2_provenance(v2, &mut issues);
+    }
+
     // Ensure no undeclared files except manifest itself.
     let all_files = list_files_sorted(root)?;
     for p in all_files {
@@ -65,13 +87,49 @@ pub fn verify_bundle(root: &Path) -> anyhow::Result<ExportVerifyResponse> {
         if rel == "manifest.json" {
             continue;
         }
-        if !manifest.files.iter().any(|f| f.path == rel) {
+        if !declared.iter().any(|(path, _)| *path == rel) {
             issues.push(format!("undeclared file {}", rel));
         }
     }
 
+    let signature = check_signature(&manifest, trusted_public_keys)?;
+    let signer_public_key = manifest.signature().map(|s| s.public_key.clone());
+    match signature {
+        ExportSignatureStatus::Unsigned => issues.push("manifest unsigned".to_string()),
+        ExportSignatureStatus::Invalid => issues.push("manifest signature invalid".to_string()),
+        ExportSignatureStatus::Untrusted => issues.push(format!(
+            "manifest signed by untrusted key {}",
+            signer_public_key.as_deref().unwrap_or_default()
+        )),
+        ExportSignatureStatus::Trusted => {}
+    }
+
     Ok(ExportVerifyResponse {
         valid: issues.is_empty(),
         issues,
+        signature,
+        signer_public_key,
     })
 }
+
+/// Asset ids are content hashes, so a file copied from the asset store must
+/// hash to its `asset_id`.
+fn verify_v2_provenance(manifest: &ExportManifestV2, issues: &mut Vec<String>) {
+    let range = &manifest.source_seq_range;
+    if range.first_seq < 0 || range.first_seq > range.last_seq {
+        issues.push(format!(
+            "invalid source_seq_range {}..={}",
+            range.first_seq, range.last_seq
+        ));
+    }
+    for file in &manifest.files {
+        if file.media_type.is_empty() {
+            issues.push(format!("missing media_type {}", file.path));
+        }
+        if let Some(asset_id) = &file.asset_id {
+            if *asset_id != file.hash_blake3 {
+                issues.push(format!("asset_id mismatch {}", file.path));
+            }
+        }
+    }
+}
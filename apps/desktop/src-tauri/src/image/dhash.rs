@@ -0,0 +1,47 @@
+use crate::image::{decode_png, RgbImage};
+
+const COLS: u32 = 9;
+const ROWS: u32 = 8;
+
+/// 64-bit difference hash: luminance box-sampled to 9x8, one bit per
+/// horizontal gradient. Visually similar frames differ in few bits.
+pub fn dhash(image: &RgbImage) -> u64 {
+    let (width, height) = (image.width, image.height);
+    let mut cells = [[0u64; COLS as usize]; ROWS as usize];
+    for (row, cells_row) in (0u32..).zip(cells.iter_mut()) {
+        let y0 = row * height / ROWS;
+        let y1 = ((row + 1) * height / ROWS).max(y0 + 1).min(height);
+        for (col, cell) in (0u32..).zip(cells_row.iter_mut()) {
+            let x0 = col * width / COLS;
+            let x1 = ((col + 1) * width / COLS).max(x0 + 1).min(width);
+            let mut sum = 0;
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    sum += u64::from(image.luma(x, y));
+                }
+            }
+            *cell = sum / (u64::from(y1 - y0) * u64::from(x1 - x0));
+        }
+    }
+    let mut hash = 0u64;
+    for row in &cells {
+        for pair in row.windows(2) {
+            hash = (hash << 1) | u64::from(pair[0] > pair[1]);
+        }
+    }
+    hash
+}
+
+/// [`dhash`] of encoded bytes; `None` when they do not decode as PNG.
+pub fn png_dhash(png_bytes: &[u8]) -> Option<u64> {
+    decode_png(png_bytes).ok().map(|image| dhash(&image))
+}
+
+/// Hex form stored in `KeyframeCaptured.dhash`.
+pub fn to_hex(hash: u64) -> String {
+    format!("{hash:016x}")
+}
+
+pub fn from_hex(hex: &str) -> Option<u64> {
+    u64::from_str_radix(hex, 16).ok()
+}
@@ -0,0 +1,132 @@
+pub mod dhash;
+pub mod thumbnail;
+
+use opscinema_types::BBoxNorm;
+
+/// Decoded pixel budget; larger images are rejected before allocation.
+const MAX_DECODED_BYTES: usize = 256 * 1024 * 1024;
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// 8-bit RGB pixels, row-major without padding. Alpha is dropped: keyframes
+/// are opaque screenshots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RgbImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl RgbImage {
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 3] {
+        let idx = (y as usize * self.width as usize + x as usize) * 3;
+        [self.pixels[idx], self.pixels[idx + 1], self.pixels[idx + 2]]
+    }
+
+    /// Rec. 601 luma.
+    pub fn luma(&self, x: u32, y: u32) -> u8 {
+        let [r, g, b] = self.pixel(x, y);
+        ((u32::from(r) * 299 + u32::from(g) * 587 + u32::from(b) * 114) / 1000) as u8
+    }
+}
+
+/// Decodes any PNG color type and bit depth to RGB8, rejecting truncated
+/// data, zero-sized images and images over the decode budget.
+pub fn decode_png(bytes: &[u8]) -> anyhow::Result<RgbImage> {
+    if !bytes.starts_with(PNG_SIGNATURE) {
+        anyhow::bail!("not a PNG: missing signature");
+    }
+    let mut decoder = png::Decoder::new_with_limits(
+        bytes,
+        png::Limits {
+            bytes: MAX_DECODED_BYTES,
+        },
+    );
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder
+        .read_info()
+        .map_err(|e| anyhow::anyhow!("invalid PNG header: {e}"))?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut buf)
+        .map_err(|e| anyhow::anyhow!("invalid PNG data: {e}"))?;
+    if info.width == 0 || info.height == 0 {
+        anyhow::bail!("PNG has no pixels");
+    }
+    let channels = info.color_type.samples();
+    let row_len = info.width as usize * channels;
+    let rows = buf.chunks(info.line_size).take(info.height as usize);
+    let pixels = if channels == 3 {
+        rows.flat_map(|row| &row[..row_len]).copied().collect()
+    } else {
+        let mut pixels = Vec::with_capacity(info.width as usize * info.height as usize * 3);
+        for row in rows {
+            for px in row[..row_len].chunks_exact(channels) {
+                if channels > 3 {
+                    pixels.extend_from_slice(&px[..3]);
+                } else {
+                    pixels.extend_from_slice(&[px[0]; 3]);
+                }
+            }
+        }
+        pixels
+    };
+    Ok(RgbImage {
+        width: info.width,
+        height: info.height,
+        pixels,
+    })
+}
+
+/// Paints a normalized (0–10000) box, rounded outward to whole pixels so
+/// partially covered pixels are painted too.
+pub fn fill_box(image: &mut RgbImage, bbox: &BBoxNorm, color: [u8; 3]) {
+    let span = |start: u32, len: u32, size: u32| {
+        let size = u64::from(size);
+        let lo = u64::from(start.min(10_000)) * size / 10_000;
+        let hi = (u64::from(start.saturating_add(len).min(10_000)) * size).div_ceil(10_000);
+        (lo as usize, hi.min(size) as usize)
+    };
+    let (x0, x1) = span(bbox.x, bbox.w, image.width);
+    let (y0, y1) = span(bbox.y, bbox.h, image.height);
+    let width = image.width as usize;
+    for y in y0..y1 {
+        for px in image.pixels[(y * width + x0) * 3..(y * width + x1) * 3].chunks_exact_mut(3) {
+            px.copy_from_slice(&color);
+        }
+    }
+}
+
+/// Width and height from the PNG header, without decoding pixels.
+pub fn png_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let reader = png::Decoder::new(bytes).read_info().ok()?;
+    let info = reader.info();
+    Some((info.width, info.height))
+}
+
+/// Value of a `tEXt` chunk written before the image data.
+pub fn png_text(bytes: &[u8], keyword: &str) -> Option<String> {
+    let reader = png::Decoder::new(bytes).read_info().ok()?;
+    reader
+        .info()
+        .uncompressed_latin1_text
+        .iter()
+        .find(|chunk| chunk.keyword == keyword)
+        .map(|chunk| chunk.text.clone())
+}
+
+/// Encodes as an RGB8 PNG with optional `tEXt` chunks. Output is
+/// deterministic for the same pixels and text.
+pub fn encode_png(image: &RgbImage, text: &[(&str, &str)]) -> anyhow::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut out, image.width, image.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        for (keyword, value) in text {
+            encoder.add_text_chunk((*keyword).to_string(), (*value).to_string())?;
+        }
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&image.pixels)?;
+    }
+    Ok(out)
+}
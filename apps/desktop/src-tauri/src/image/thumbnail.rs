@@ -0,0 +1,42 @@
+use crate::image::RgbImage;
+
+/// Longest edge of a timeline thumbnail, in pixels.
+pub const THUMBNAIL_MAX_EDGE: u32 = 320;
+
+/// Box-filtered downscale that fits within `max_edge` on both axes and keeps
+/// the aspect ratio. Images already small enough are copied unchanged.
+pub fn downscale(image: &RgbImage, max_edge: u32) -> RgbImage {
+    let longest = image.width.max(image.height);
+    if longest <= max_edge {
+        return image.clone();
+    }
+    let scaled =
+        |edge: u32| ((u64::from(edge) * u64::from(max_edge) / u64::from(longest)) as u32).max(1);
+    let (out_w, out_h) = (scaled(image.width), scaled(image.height));
+    let mut pixels = Vec::with_capacity(out_w as usize * out_h as usize * 3);
+    for oy in 0..out_h {
+        let y0 = oy * image.height / out_h;
+        let y1 = ((oy + 1) * image.height / out_h).max(y0 + 1);
+        for ox in 0..out_w {
+            let x0 = ox * image.width / out_w;
+            let x1 = ((ox + 1) * image.width / out_w).max(x0 + 1);
+            let mut sum = [0u64; 3];
+            for y in y0..y1 {
+                let row = y as usize * image.width as usize;
+                let span = &image.pixels[(row + x0 as usize) * 3..(row + x1 as usize) * 3];
+                for px in span.chunks_exact(3) {
+                    sum[0] += u64::from(px[0]);
+                    sum[1] += u64::from(px[1]);
+                    sum[2] += u64::from(px[2]);
+                }
+            }
+            let count = u64::from(y1 - y0) * u64::from(x1 - x0);
+            pixels.extend(sum.map(|acc| ((acc + count / 2) / count) as u8));
+        }
+    }
+    RgbImage {
+        width: out_w,
+        height: out_h,
+        pixels,
+    }
+}
@@ -4,6 +4,7 @@ pub mod api;
 pub mod capture;
 pub mod evidence;
 pub mod exports;
+pub mod image;
 pub mod jobs;
 pub mod model_dock;
 pub mod ocr;
@@ -0,0 +1,212 @@
+//! Minimal HTTP/1.1 client for local model servers. Requests are one-shot
+//! (`Connection: close`) over plain TCP; responses may be sized or chunked.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    pub host: String,
+    pub port: u16,
+}
+
+impl Endpoint {
+    /// Accepts `host`, `host:port` or `http://host:port[/...]`; `https` is
+    /// rejected since local servers speak plain HTTP.
+    pub fn parse(raw: &str, default_port: u16) -> Result<Self, String> {
+        let trimmed = raw.trim();
+        let rest = match trimmed.split_once("://") {
+            Some((scheme, rest)) if scheme.eq_ignore_ascii_case("http") => rest,
+            Some((scheme, _)) => return Err(format!("unsupported scheme {scheme}")),
+            None => trimmed,
+        };
+        let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) if !host.ends_with(':') => (
+                host,
+                port.parse::<u16>()
+                    .map_err(|_| format!("invalid port in {raw}"))?,
+            ),
+            _ => (authority, default_port),
+        };
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        if host.is_empty() {
+            return Err(format!("missing host in {raw}"));
+        }
+        Ok(Self {
+            host: host.to_string(),
+            port,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Timeouts {
+    pub connect: Duration,
+    /// Longest wait for any single read or write.
+    pub io: Duration,
+}
+
+pub struct Response {
+    pub status: u16,
+    pub body: Box<dyn BufRead + Send>,
+}
+
+impl Response {
+    pub fn text(mut self) -> io::Result<String> {
+        let mut out = String::new();
+        self.body.read_to_string(&mut out)?;
+        Ok(out)
+    }
+}
+
+pub fn post_json(
+    endpoint: &Endpoint,
+    path: &str,
+    body: &serde_json::Value,
+    timeouts: Timeouts,
+) -> io::Result<Response> {
+    request(
+        endpoint,
+        "POST",
+        path,
+        Some(body.to_string().as_bytes()),
+        timeouts,
+    )
+}
+
+pub fn get(endpoint: &Endpoint, path: &str, timeouts: Timeouts) -> io::Result<Response> {
+    request(endpoint, "GET", path, None, timeouts)
+}
+
+fn request(
+    endpoint: &Endpoint,
+    method: &str,
+    path: &str,
+    body: Option<&[u8]>,
+    timeouts: Timeouts,
+) -> io::Result<Response> {
+    let mut last_err = io::Error::new(io::ErrorKind::NotFound, "host did not resolve");
+    let mut stream = None;
+    for addr in (endpoint.host.as_str(), endpoint.port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, timeouts.connect) {
+            Ok(s) => {
+                stream = Some(s);
+                break;
+            }
+            Err(e) => last_err = e,
+        }
+    }
+    let mut stream = stream.ok_or(last_err)?;
+    stream.set_read_timeout(Some(timeouts.io))?;
+    stream.set_write_timeout(Some(timeouts.io))?;
+
+    let mut head = format!(
+        "{method} {path} HTTP/1.1\r\nHost: {}:{}\r\nAccept: application/json\r\nConnection: close\r\n",
+        endpoint.host, endpoint.port
+    );
+    if let Some(body) = body {
+        head.push_str(&format!(
+            "Content-Type: application/json\r\nContent-Length: {}\r\n",
+            body.len()
+        ));
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes())?;
+    if let Some(body) = body {
+        stream.write_all(body)?;
+    }
+    stream.flush()?;
+
+    let mut reader = BufReader::new(stream);
+    let status_line = read_line(&mut reader)?;
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or_else(|| invalid(format!("malformed status line: {status_line}")))?;
+    let mut chunked = false;
+    let mut content_length = None;
+    loop {
+        let line = read_line(&mut reader)?;
+        if line.is_empty() {
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("transfer-encoding") {
+            chunked = value.to_ascii_lowercase().contains("chunked");
+        } else if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.parse::<u64>().ok();
+        }
+    }
+    let body: Box<dyn BufRead + Send> = if chunked {
+        Box::new(BufReader::new(ChunkedReader {
+            inner: reader,
+            remaining: 0,
+            done: false,
+        }))
+    } else if let Some(len) = content_length {
+        Box::new(reader.take(len))
+    } else {
+        Box::new(reader)
+    };
+    Ok(Response { status, body })
+}
+
+fn read_line(reader: &mut impl BufRead) -> io::Result<String> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "connection closed mid-response",
+        ));
+    }
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Decodes `Transfer-Encoding: chunked`, ignoring chunk extensions and trailers.
+struct ChunkedReader<R> {
+    inner: R,
+    remaining: usize,
+    done: bool,
+}
+
+impl<R: BufRead> Read for ChunkedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.done || buf.is_empty() {
+            return Ok(0);
+        }
+        if self.remaining == 0 {
+            let size_line = read_line(&mut self.inner)?;
+            let size = size_line.split(';').next().unwrap_or_default().trim();
+            self.remaining = usize::from_str_radix(size, 16)
+                .map_err(|_| invalid(format!("malformed chunk size: {size_line}")))?;
+            if self.remaining == 0 {
+                while !read_line(&mut self.inner)?.is_empty() {}
+                self.done = true;
+                return Ok(0);
+            }
+        }
+        let want = buf.len().min(self.remaining);
+        let n = self.inner.read(&mut buf[..want])?;
+        if n == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "connection closed mid-chunk",
+            ));
+        }
+        self.remaining -= n;
+        if self.remaining == 0 {
+            read_line(&mut self.inner)?;
+        }
+        Ok(n)
+    }
+}
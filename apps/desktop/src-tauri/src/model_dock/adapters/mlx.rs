@@ -1,3 +1,373 @@
-pub fn run_local(_model_id: &str, prompt: &str) -> anyhow::Result<String> {
-    Ok(format!("mlx:{prompt}"))
+//! Drives a local MLX inference subprocess over line-delimited JSON: one
+//! request per line on its stdin, one event per line on its stdout. A run is
+//! `load`, then `generate` (streaming `token` events until `done`), with
+//! `cancel` accepted mid-generation. `digest` hashes a model's weights
+//! without loading them.
+
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_RUNNER: &str = "opscinema-mlx-runner";
+pub const DEFAULT_MAX_TOKENS: u32 = 512;
+/// Idle limit between events; loading large weights can take minutes.
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+const POLL_INTERVAL: Duration = Duration::from_millis(25);
+/// How long a runner gets to acknowledge `cancel` or exit before it is killed.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum RunnerRequest {
+    Load {
+        model: String,
+    },
+    /// Hash the weights `model` resolves to without loading them.
+    Digest {
+        model: String,
+    },
+    Generate {
+        prompt: String,
+        max_tokens: u32,
+    },
+    Cancel,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum RunnerEvent {
+    Loaded {
+        model: String,
+        digest: String,
+    },
+    /// Answer to `digest`.
+    Digest {
+        model: String,
+        digest: String,
+    },
+    Token {
+        text: String,
+    },
+    Done {
+        tokens: u32,
+    },
+    Cancelled,
+    Error {
+        message: String,
+        /// `model_not_found` is the only code the adapter distinguishes.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        code: Option<String>,
+    },
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MlxError {
+    #[error("failed to start MLX runner {runner}: {source}")]
+    Spawn {
+        runner: String,
+        source: std::io::Error,
+    },
+    #[error("model {0} not found")]
+    ModelNotFound(String),
+    #[error("MLX runner error: {0}")]
+    Runner(String),
+    #[error("unexpected MLX runner output: {0}")]
+    Protocol(String),
+    #[error("MLX runner sent nothing for {0:?}")]
+    Timeout(Duration),
+    #[error("MLX runner exited: {0}")]
+    Exited(String),
+    #[error("MLX run cancelled")]
+    Cancelled,
+}
+
+#[derive(Debug, Clone)]
+pub struct RunOptions {
+    /// Runner executable; `OPSCINEMA_MLX_RUNNER` overrides the default.
+    pub runner: String,
+    pub max_tokens: u32,
+    /// `OPSCINEMA_MLX_TIMEOUT_MS` overrides the default.
+    pub idle_timeout: Duration,
+}
+
+impl RunOptions {
+    pub fn from_env() -> Self {
+        Self {
+            runner: std::env::var("OPSCINEMA_MLX_RUNNER")
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_RUNNER.to_string()),
+            max_tokens: DEFAULT_MAX_TOKENS,
+            idle_timeout: std::env::var("OPSCINEMA_MLX_TIMEOUT_MS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(DEFAULT_IDLE_TIMEOUT),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MlxProgress {
+    Loading,
+    Generating { tokens: u32, max_tokens: u32 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MlxOutput {
+    pub text: String,
+    /// Digest of the weights the runner loaded.
+    pub digest: String,
+    pub tokens: u32,
+}
+
+/// Loads `model` and generates from `prompt`. `is_cancelled` is polled while
+/// waiting on the runner; once it returns true the runner is sent `cancel`
+/// and the run ends with [`MlxError::Cancelled`].
+pub fn run_local(
+    opts: &RunOptions,
+    model: &str,
+    prompt: &str,
+    is_cancelled: impl Fn() -> bool,
+    mut on_progress: impl FnMut(MlxProgress),
+) -> Result<MlxOutput, MlxError> {
+    let mut runner = Runner::spawn(&opts.runner)?;
+    let result = (|| {
+        on_progress(MlxProgress::Loading);
+        let digest = runner.load(opts, model, &is_cancelled)?;
+
+        runner.send(&RunnerRequest::Generate {
+            prompt: prompt.to_string(),
+            max_tokens: opts.max_tokens,
+        })?;
+        let mut text = String::new();
+        let mut streamed = 0;
+        loop {
+            match runner.next_event(opts, &is_cancelled)? {
+                RunnerEvent::Token { text: token } => {
+                    text.push_str(&token);
+                    streamed += 1;
+                    on_progress(MlxProgress::Generating {
+                        tokens: streamed,
+                        max_tokens: opts.max_tokens,
+                    });
+                }
+                RunnerEvent::Done { tokens } => {
+                    return Ok(MlxOutput {
+                        text,
+                        digest,
+                        tokens,
+                    })
+                }
+                RunnerEvent::Cancelled => return Err(MlxError::Cancelled),
+                RunnerEvent::Error { message, code } => {
+                    return Err(runner_error(model, message, code))
+                }
+                other @ (RunnerEvent::Loaded { .. } | RunnerEvent::Digest { .. }) => {
+                    return Err(MlxError::Protocol(format!(
+                        "unexpected {other:?} while generating"
+                    )))
+                }
+            }
+        }
+    })();
+    runner.shutdown();
+    result
+}
+
+/// Digest of the weights `model` resolves to, as `load` would report it,
+/// without loading them, so a registered digest can be checked cheaply.
+pub fn probe_digest(opts: &RunOptions, model: &str) -> Result<String, MlxError> {
+    let mut runner = Runner::spawn(&opts.runner)?;
+    let result = (|| {
+        runner.send(&RunnerRequest::Digest {
+            model: model.to_string(),
+        })?;
+        match runner.next_event(opts, &|| false)? {
+            RunnerEvent::Digest { digest, .. } => Ok(digest),
+            RunnerEvent::Error { message, code } => Err(runner_error(model, message, code)),
+            other => Err(MlxError::Protocol(format!(
+                "expected digest, got {other:?}"
+            ))),
+        }
+    })();
+    runner.shutdown();
+    result
+}
+
+fn runner_error(model: &str, message: String, code: Option<String>) -> MlxError {
+    match code.as_deref() {
+        Some("model_not_found") => MlxError::ModelNotFound(model.to_string()),
+        _ => MlxError::Runner(message),
+    }
+}
+
+struct Runner {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    lines: Receiver<std::io::Result<String>>,
+    stderr: Arc<Mutex<String>>,
+}
+
+impl Runner {
+    fn spawn(program: &str) -> Result<Self, MlxError> {
+        let mut child = Command::new(program)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|source| MlxError::Spawn {
+                runner: program.to_string(),
+                source,
+            })?;
+        let stdin = child.stdin.take();
+        let (tx, lines) = mpsc::channel();
+        if let Some(stdout) = child.stdout.take() {
+            std::thread::spawn(move || {
+                for line in BufReader::new(stdout).lines() {
+                    if tx.send(line).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        let stderr = Arc::new(Mutex::new(String::new()));
+        if let Some(mut pipe) = child.stderr.take() {
+            let sink = stderr.clone();
+            std::thread::spawn(move || {
+                let mut buf = String::new();
+                let _ = pipe.read_to_string(&mut buf);
+                if let Ok(mut sink) = sink.lock() {
+                    *sink = buf;
+                }
+            });
+        }
+        Ok(Self {
+            child,
+            stdin,
+            lines,
+            stderr,
+        })
+    }
+
+    fn load(
+        &mut self,
+        opts: &RunOptions,
+        model: &str,
+        is_cancelled: &impl Fn() -> bool,
+    ) -> Result<String, MlxError> {
+        self.send(&RunnerRequest::Load {
+            model: model.to_string(),
+        })?;
+        match self.next_event(opts, is_cancelled)? {
+            RunnerEvent::Loaded { digest, .. } => Ok(digest),
+            RunnerEvent::Error { message, code } => Err(runner_error(model, message, code)),
+            other => Err(MlxError::Protocol(format!(
+                "expected loaded, got {other:?}"
+            ))),
+        }
+    }
+
+    fn send(&mut self, request: &RunnerRequest) -> Result<(), MlxError> {
+        let stdin = self
+            .stdin
+            .as_mut()
+            .ok_or_else(|| MlxError::Exited("stdin closed".to_string()))?;
+        let line = serde_json::to_string(request)
+            .map_err(|e| MlxError::Protocol(format!("encode request: {e}")))?;
+        writeln!(stdin, "{line}")
+            .and_then(|_| stdin.flush())
+            .map_err(|e| self.exited(e.to_string()))
+    }
+
+    /// Waits for the next event. Cancellation is checked before every wait;
+    /// after `cancel` is sent, events are drained until the runner
+    /// acknowledges or the grace period runs out.
+    fn next_event(
+        &mut self,
+        opts: &RunOptions,
+        is_cancelled: &impl Fn() -> bool,
+    ) -> Result<RunnerEvent, MlxError> {
+        let mut last_event = Instant::now();
+        loop {
+            if is_cancelled() {
+                let _ = self.send(&RunnerRequest::Cancel);
+                let deadline = Instant::now() + SHUTDOWN_GRACE;
+                while Instant::now() < deadline {
+                    match self.lines.recv_timeout(POLL_INTERVAL) {
+                        Ok(Ok(line)) => {
+                            if matches!(
+                                serde_json::from_str::<RunnerEvent>(&line),
+                                Ok(RunnerEvent::Cancelled
+                                    | RunnerEvent::Done { .. }
+                                    | RunnerEvent::Error { .. })
+                            ) {
+                                break;
+                            }
+                        }
+                        Ok(Err(_)) | Err(RecvTimeoutError::Disconnected) => break,
+                        Err(RecvTimeoutError::Timeout) => {}
+                    }
+                }
+                return Err(MlxError::Cancelled);
+            }
+            match self.lines.recv_timeout(POLL_INTERVAL) {
+                Ok(Ok(line)) if line.trim().is_empty() => {}
+                Ok(Ok(line)) => {
+                    return serde_json::from_str(&line)
+                        .map_err(|e| MlxError::Protocol(format!("{e}: {line}")))
+                }
+                Ok(Err(e)) => return Err(self.exited(e.to_string())),
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(self.exited("stdout closed".to_string()))
+                }
+                Err(RecvTimeoutError::Timeout) if last_event.elapsed() >= opts.idle_timeout => {
+                    return Err(MlxError::Timeout(opts.idle_timeout))
+                }
+                Err(RecvTimeoutError::Timeout) => continue,
+            }
+            last_event = Instant::now();
+        }
+    }
+
+    fn exited(&mut self, reason: String) -> MlxError {
+        let deadline = Instant::now() + SHUTDOWN_GRACE;
+        let status = loop {
+            match self.child.try_wait() {
+                Ok(Some(status)) => break Some(status),
+                Ok(None) if Instant::now() < deadline => std::thread::sleep(POLL_INTERVAL),
+                _ => break None,
+            }
+        };
+        // The stderr reader finishes once the process is gone.
+        std::thread::sleep(POLL_INTERVAL);
+        let stderr = self
+            .stderr
+            .lock()
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default();
+        let mut msg = status.map_or(reason, |s| s.to_string());
+        if !stderr.is_empty() {
+            msg.push_str(": ");
+            msg.push_str(&stderr);
+        }
+        MlxError::Exited(msg)
+    }
+
+    /// Closing stdin asks the runner to exit; it is killed if it lingers.
+    fn shutdown(mut self) {
+        drop(self.stdin.take());
+        let deadline = Instant::now() + SHUTDOWN_GRACE;
+        while Instant::now() < deadline {
+            if let Ok(Some(_)) = self.child.try_wait() {
+                return;
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
 }
@@ -0,0 +1,133 @@
+//! Deterministic MLX runner served by the `opscinema-mlx-stub` binary so the
+//! subprocess protocol can be exercised without MLX. Generation echoes the
+//! prompt one word per token; digests are derived from the model name.
+
+use super::mlx::{RunnerEvent, RunnerRequest};
+use std::io::{BufRead, Write};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::time::Duration;
+
+/// Models under this prefix fail to load with `model_not_found`.
+pub const MISSING_MODEL_PREFIX: &str = "missing/";
+
+pub fn digest_for(model: &str) -> String {
+    format!("blake3:{}", blake3::hash(model.as_bytes()).to_hex())
+}
+
+pub fn tokens_for(prompt: &str) -> Vec<String> {
+    format!("echo: {prompt}")
+        .split_whitespace()
+        .enumerate()
+        .map(|(i, word)| {
+            if i == 0 {
+                word.to_string()
+            } else {
+                format!(" {word}")
+            }
+        })
+        .collect()
+}
+
+/// Serves requests from `input` until it closes, sleeping `token_delay`
+/// before each token so callers can cancel mid-stream.
+pub fn serve(
+    input: impl BufRead + Send + 'static,
+    mut output: impl Write,
+    token_delay: Duration,
+) -> std::io::Result<()> {
+    let (tx, requests) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in input.lines() {
+            let Ok(line) = line else { break };
+            if tx
+                .send(serde_json::from_str::<RunnerRequest>(&line))
+                .is_err()
+            {
+                break;
+            }
+        }
+    });
+
+    let mut loaded = false;
+    while let Ok(request) = requests.recv() {
+        match request {
+            Ok(RunnerRequest::Load { model } | RunnerRequest::Digest { model })
+                if model.starts_with(MISSING_MODEL_PREFIX) =>
+            {
+                emit(
+                    &mut output,
+                    &RunnerEvent::Error {
+                        message: format!("no weights for {model}"),
+                        code: Some("model_not_found".to_string()),
+                    },
+                )?
+            }
+            Ok(RunnerRequest::Digest { model }) => {
+                let digest = digest_for(&model);
+                emit(&mut output, &RunnerEvent::Digest { model, digest })?;
+            }
+            Ok(RunnerRequest::Load { model }) => {
+                let digest = digest_for(&model);
+                emit(&mut output, &RunnerEvent::Loaded { model, digest })?;
+                loaded = true;
+            }
+            Ok(RunnerRequest::Generate { prompt, max_tokens }) if loaded => {
+                generate(&mut output, &requests, &prompt, max_tokens, token_delay)?
+            }
+            Ok(RunnerRequest::Generate { .. }) => emit(
+                &mut output,
+                &RunnerEvent::Error {
+                    message: "generate before load".to_string(),
+                    code: None,
+                },
+            )?,
+            // Nothing is running, so there is nothing to acknowledge.
+            Ok(RunnerRequest::Cancel) => {}
+            Err(e) => emit(
+                &mut output,
+                &RunnerEvent::Error {
+                    message: format!("bad request: {e}"),
+                    code: None,
+                },
+            )?,
+        }
+    }
+    Ok(())
+}
+
+fn generate(
+    output: &mut impl Write,
+    requests: &Receiver<serde_json::Result<RunnerRequest>>,
+    prompt: &str,
+    max_tokens: u32,
+    token_delay: Duration,
+) -> std::io::Result<()> {
+    let mut sent = 0;
+    for token in tokens_for(prompt).into_iter().take(max_tokens as usize) {
+        std::thread::sleep(token_delay);
+        match requests.try_recv() {
+            Ok(Ok(RunnerRequest::Cancel)) => return emit(output, &RunnerEvent::Cancelled),
+            Err(TryRecvError::Empty) => {}
+            Err(TryRecvError::Disconnected) => return Ok(()),
+            // Anything else mid-generation is a caller bug; stop and report it.
+            _ => {
+                return emit(
+                    output,
+                    &RunnerEvent::Error {
+                        message: "request while generating".to_string(),
+                        code: None,
+                    },
+                )
+            }
+        }
+        emit(output, &RunnerEvent::Token { text: token })?;
+        sent += 1;
+    }
+    emit(output, &RunnerEvent::Done { tokens: sent })
+}
+
+fn emit(output: &mut impl Write, event: &RunnerEvent) -> std::io::Result<()> {
+    let line = serde_json::to_string(event).map_err(std::io::Error::other)?;
+    writeln!(output, "{line}")?;
+    output.flush()
+}
@@ -0,0 +1,232 @@
+//! In-process stand-in for an Ollama server so adapter tests run without a
+//! model runtime. Serves `/api/tags`, a chunked `/api/pull` stream and
+//! `/api/generate` (which echoes the prompt unless a generator is set) on an
+//! ephemeral local port.
+
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Models the mock can pull, by name, with their digests.
+pub const PULLABLE: &[(&str, &str)] = &[(
+    "llama3.1:latest",
+    "sha256:46e0c10c039e019119339687c3c1757cc81b9da49709a3b3924863ba87ca666e",
+)];
+pub const PREINSTALLED: (&str, &str) = (
+    "qwen2.5:7b",
+    "sha256:845dbda0ea48ed749caafd9e6037047aa19acfcfd82e704d7ca97d631a0b697e",
+);
+
+type Generator = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+#[derive(Default)]
+struct State {
+    installed: BTreeMap<String, String>,
+    requests: Vec<String>,
+    delay: Option<Duration>,
+    generator: Option<Generator>,
+}
+
+pub struct MockOllama {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl MockOllama {
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock ollama");
+        let addr = listener.local_addr().expect("mock addr");
+        let state = Arc::new(Mutex::new(State::default()));
+        state
+            .lock()
+            .expect("mock state")
+            .installed
+            .insert(PREINSTALLED.0.to_string(), PREINSTALLED.1.to_string());
+        let stop = Arc::new(AtomicBool::new(false));
+        let (thread_state, thread_stop) = (state.clone(), stop.clone());
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                if thread_stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                if let Ok(stream) = stream {
+                    let state = thread_state.clone();
+                    std::thread::spawn(move || {
+                        let _ = serve(stream, &state);
+                    });
+                }
+            }
+        });
+        Self { addr, state, stop }
+    }
+
+    /// `host:port` to pass as the Ollama host (and to allowlist).
+    pub fn host(&self) -> String {
+        self.addr.to_string()
+    }
+
+    /// `METHOD /path` of every request served so far.
+    pub fn requests(&self) -> Vec<String> {
+        self.state.lock().expect("mock state").requests.clone()
+    }
+
+    /// Stalls every response by `delay` before the first byte.
+    pub fn set_delay(&self, delay: Option<Duration>) {
+        self.state.lock().expect("mock state").delay = delay;
+    }
+
+    /// Installs `name` with `digest`, replacing any earlier install, as if
+    /// the model had been re-pulled with different weights.
+    pub fn set_installed(&self, name: &str, digest: &str) {
+        self.state
+            .lock()
+            .expect("mock state")
+            .installed
+            .insert(name.to_string(), digest.to_string());
+    }
+
+    /// Answers `/api/generate` with `generate(prompt)` instead of the echo.
+    pub fn set_generator(&self, generate: impl Fn(&str) -> String + Send + Sync + 'static) {
+        self.state.lock().expect("mock state").generator = Some(Arc::new(generate));
+    }
+}
+
+impl Drop for MockOllama {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        let _ = TcpStream::connect(self.addr);
+    }
+}
+
+fn serve(stream: TcpStream, state: &Mutex<State>) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    let body = serde_json::from_slice::<serde_json::Value>(&body).unwrap_or_default();
+    let model = body["model"].as_str().unwrap_or_default().to_string();
+
+    let delay = {
+        let mut state = state.lock().expect("mock state");
+        state.requests.push(format!("{method} {path}"));
+        state.delay
+    };
+    if let Some(delay) = delay {
+        std::thread::sleep(delay);
+    }
+
+    let mut out = stream;
+    match (method.as_str(), path.as_str()) {
+        ("GET", "/api/tags") => {
+            let models = state
+                .lock()
+                .expect("mock state")
+                .installed
+                .iter()
+                .map(|(name, digest)| serde_json::json!({ "name": name, "model": name, "digest": digest, "size": 1024 }))
+                .collect::<Vec<_>>();
+            respond(&mut out, 200, &serde_json::json!({ "models": models }))
+        }
+        ("POST", "/api/pull") => {
+            let name = if model.contains(':') {
+                model.clone()
+            } else {
+                format!("{model}:latest")
+            };
+            let lines = match PULLABLE.iter().find(|(n, _)| *n == name) {
+                Some((name, digest)) => {
+                    state
+                        .lock()
+                        .expect("mock state")
+                        .installed
+                        .insert(name.to_string(), digest.to_string());
+                    let layer = &digest["sha256:".len().."sha256:".len() + 12];
+                    vec![
+                        serde_json::json!({ "status": "pulling manifest" }),
+                        serde_json::json!({ "status": format!("pulling {layer}"), "digest": digest, "total": 4096, "completed": 1024 }),
+                        serde_json::json!({ "status": format!("pulling {layer}"), "digest": digest, "total": 4096, "completed": 4096 }),
+                        serde_json::json!({ "status": "verifying sha256 digest" }),
+                        serde_json::json!({ "status": "writing manifest" }),
+                        serde_json::json!({ "status": "success" }),
+                    ]
+                }
+                None => vec![
+                    serde_json::json!({ "status": "pulling manifest" }),
+                    serde_json::json!({ "error": "pull model manifest: file does not exist" }),
+                ],
+            };
+            write!(
+                out,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/x-ndjson\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n"
+            )?;
+            for line in lines {
+                let chunk = format!("{line}\n");
+                write!(out, "{:x}\r\n{chunk}\r\n", chunk.len())?;
+                out.flush()?;
+            }
+            write!(out, "0\r\n\r\n")?;
+            out.flush()
+        }
+        ("POST", "/api/generate") => {
+            let (installed, generator) = {
+                let state = state.lock().expect("mock state");
+                (
+                    state.installed.contains_key(&model),
+                    state.generator.clone(),
+                )
+            };
+            if installed {
+                let prompt = body["prompt"].as_str().unwrap_or_default();
+                let response = match generator {
+                    Some(generate) => generate(prompt),
+                    None => format!("echo: {prompt}"),
+                };
+                respond(
+                    &mut out,
+                    200,
+                    &serde_json::json!({ "model": model, "response": response, "done": true }),
+                )
+            } else {
+                respond(
+                    &mut out,
+                    404,
+                    &serde_json::json!({ "error": format!("model '{model}' not found") }),
+                )
+            }
+        }
+        _ => respond(&mut out, 404, &serde_json::json!({ "error": "not found" })),
+    }
+}
+
+fn respond(out: &mut TcpStream, status: u16, body: &serde_json::Value) -> std::io::Result<()> {
+    let body = body.to_string();
+    write!(
+        out,
+        "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        if status == 200 { "OK" } else { "Not Found" },
+        body.len()
+    )?;
+    out.flush()
+}
@@ -1,2 +1,7 @@
+mod http;
 pub mod mlx;
+#[cfg(feature = "mlx-stub")]
+pub mod mlx_stub;
+#[cfg(test)]
+pub mod mock_ollama;
 pub mod ollama;
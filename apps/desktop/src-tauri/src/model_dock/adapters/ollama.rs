@@ -1,21 +1,221 @@
+use super::http::{self, Endpoint, Timeouts};
 use crate::policy::network_allowlist::NetworkPolicy;
+use opscinema_types::AppError;
+use serde::Deserialize;
+use std::io::BufRead;
+use std::time::Duration;
 
-pub fn list_models(policy: &NetworkPolicy, host: &str) -> anyhow::Result<Vec<String>> {
-    policy.check_host(host)?;
-    Ok(vec!["llama3.1".to_string(), "qwen2.5".to_string()])
+pub const DEFAULT_PORT: u16 = 11434;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+/// Idle limit per read; generation on a cold model can take minutes.
+const DEFAULT_IO_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, thiserror::Error)]
+pub enum OllamaError {
+    #[error(transparent)]
+    Blocked(AppError),
+    #[error("invalid Ollama host: {0}")]
+    InvalidHost(String),
+    #[error("Ollama unreachable at {host}: {source}")]
+    Unreachable {
+        host: String,
+        source: std::io::Error,
+    },
+    #[error("Ollama request to {path} timed out")]
+    Timeout { path: String },
+    #[error("model {0} not found")]
+    ModelNotFound(String),
+    #[error("Ollama returned HTTP {status}: {message}")]
+    Http { status: u16, message: String },
+    #[error("Ollama pull failed: {0}")]
+    PullFailed(String),
+    #[error("unexpected Ollama response: {0}")]
+    InvalidResponse(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct OllamaModel {
+    pub name: String,
+    pub digest: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct PullProgress {
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub digest: Option<String>,
+    #[serde(default)]
+    pub total: Option<u64>,
+    #[serde(default)]
+    pub completed: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagsResponse {
+    #[serde(default)]
+    models: Vec<OllamaModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullLine {
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(flatten)]
+    progress: PullProgress,
+}
+
+#[derive(Debug, Deserialize)]
+struct GenerateResponse {
+    response: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorBody {
+    error: String,
+}
+
+/// Reads are bounded by `OPSCINEMA_OLLAMA_TIMEOUT_MS` when set.
+fn timeouts() -> Timeouts {
+    let io = std::env::var("OPSCINEMA_OLLAMA_TIMEOUT_MS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_IO_TIMEOUT);
+    Timeouts {
+        connect: CONNECT_TIMEOUT.min(io),
+        io,
+    }
 }
 
-pub fn pull_model(policy: &NetworkPolicy, host: &str, model: &str) -> anyhow::Result<String> {
-    policy.check_host(host)?;
-    Ok(format!("pulled:{host}:{model}"))
+fn endpoint(policy: &NetworkPolicy, host: &str) -> Result<Endpoint, OllamaError> {
+    policy.check_host(host).map_err(OllamaError::Blocked)?;
+    Endpoint::parse(host, DEFAULT_PORT).map_err(OllamaError::InvalidHost)
+}
+
+fn io_error(endpoint: &Endpoint, path: &str, e: std::io::Error) -> OllamaError {
+    match e.kind() {
+        std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock => OllamaError::Timeout {
+            path: path.to_string(),
+        },
+        _ => OllamaError::Unreachable {
+            host: format!("{}:{}", endpoint.host, endpoint.port),
+            source: e,
+        },
+    }
+}
+
+/// Non-2xx responses become errors carrying Ollama's `{"error": ...}` text.
+fn check_status(
+    endpoint: &Endpoint,
+    path: &str,
+    model: Option<&str>,
+    response: http::Response,
+) -> Result<http::Response, OllamaError> {
+    if (200..300).contains(&response.status) {
+        return Ok(response);
+    }
+    let status = response.status;
+    let body = response.text().map_err(|e| io_error(endpoint, path, e))?;
+    let message = serde_json::from_str::<ErrorBody>(&body)
+        .map(|b| b.error)
+        .unwrap_or(body);
+    match (status, model) {
+        (404, Some(model)) => Err(OllamaError::ModelNotFound(model.to_string())),
+        _ => Err(OllamaError::Http { status, message }),
+    }
+}
+
+pub fn list_models(policy: &NetworkPolicy, host: &str) -> Result<Vec<OllamaModel>, OllamaError> {
+    let endpoint = endpoint(policy, host)?;
+    let path = "/api/tags";
+    let response =
+        http::get(&endpoint, path, timeouts()).map_err(|e| io_error(&endpoint, path, e))?;
+    let body = check_status(&endpoint, path, None, response)?
+        .text()
+        .map_err(|e| io_error(&endpoint, path, e))?;
+    let tags: TagsResponse = serde_json::from_str(&body)
+        .map_err(|e| OllamaError::InvalidResponse(format!("{path}: {e}")))?;
+    Ok(tags.models)
+}
+
+/// Streams `/api/pull` progress to `on_progress`, then returns the pulled
+/// model as `/api/tags` reports it so its digest comes from the server.
+pub fn pull_model(
+    policy: &NetworkPolicy,
+    host: &str,
+    model: &str,
+    mut on_progress: impl FnMut(&PullProgress),
+) -> Result<OllamaModel, OllamaError> {
+    let endpoint = endpoint(policy, host)?;
+    let path = "/api/pull";
+    let response = http::post_json(
+        &endpoint,
+        path,
+        &serde_json::json!({ "model": model, "stream": true }),
+        timeouts(),
+    )
+    .map_err(|e| io_error(&endpoint, path, e))?;
+    let response = check_status(&endpoint, path, Some(model), response)?;
+    let mut succeeded = false;
+    for line in response.body.lines() {
+        let line = line.map_err(|e| io_error(&endpoint, path, e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let parsed: PullLine = serde_json::from_str(&line)
+            .map_err(|e| OllamaError::InvalidResponse(format!("{path}: {e}")))?;
+        if let Some(error) = parsed.error {
+            return Err(if error.contains("file does not exist") {
+                OllamaError::ModelNotFound(model.to_string())
+            } else {
+                OllamaError::PullFailed(error)
+            });
+        }
+        succeeded |= parsed.progress.status == "success";
+        on_progress(&parsed.progress);
+    }
+    if !succeeded {
+        return Err(OllamaError::InvalidResponse(format!(
+            "{path}: stream ended before success"
+        )));
+    }
+    find_model(policy, host, model)
+}
+
+/// The installed model `/api/tags` lists under `model`; an untagged name
+/// matches its `:latest` tag.
+pub fn find_model(
+    policy: &NetworkPolicy,
+    host: &str,
+    model: &str,
+) -> Result<OllamaModel, OllamaError> {
+    let tagged = format!("{model}:latest");
+    list_models(policy, host)?
+        .into_iter()
+        .find(|m| m.name == model || (!model.contains(':') && m.name == tagged))
+        .ok_or_else(|| OllamaError::ModelNotFound(model.to_string()))
 }
 
 pub fn run_prompt(
     policy: &NetworkPolicy,
     host: &str,
-    model_id: &str,
+    model: &str,
     prompt: &str,
-) -> anyhow::Result<String> {
-    policy.check_host(host)?;
-    Ok(format!("ollama:{host}:{model_id}:{prompt}"))
+) -> Result<String, OllamaError> {
+    let endpoint = endpoint(policy, host)?;
+    let path = "/api/generate";
+    let response = http::post_json(
+        &endpoint,
+        path,
+        &serde_json::json!({ "model": model, "prompt": prompt, "stream": false }),
+        timeouts(),
+    )
+    .map_err(|e| io_error(&endpoint, path, e))?;
+    let body = check_status(&endpoint, path, Some(model), response)?
+        .text()
+        .map_err(|e| io_error(&endpoint, path, e))?;
+    let generated: GenerateResponse = serde_json::from_str(&body)
+        .map_err(|e| OllamaError::InvalidResponse(format!("{path}: {e}")))?;
+    Ok(generated.response)
 }
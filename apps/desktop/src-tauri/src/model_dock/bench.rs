@@ -1,11 +1,440 @@
+//! Deterministic model benchmarks over the golden session fixture. Each frame
+//! of the fixture is replayed as evidence into one case for the benchmarked
+//! role, using the prompt and output schema the live pipeline would. Replies
+//! are scored for schema validity, citation validity and grounding in the
+//! frame's OCR text; latency is measured but kept out of the score.
+
+use crate::evidence::grounding;
+use crate::policy::provider_schema::{
+    self, AnchorGroundingOutput, ProviderOutput, ScreenExplanationOutput, StepNarrativeOutput,
+};
+use crate::steps::narrate;
 use crate::storage::repo_models;
 use crate::storage::DbConn;
-use opscinema_types::{BenchListResponse, BenchRecord};
+use opscinema_types::{
+    BBoxNorm, BenchListResponse, BenchMetrics, BenchRecord, EvidenceItem, EvidenceLocator,
+    EvidenceLocatorType, Step, StructuredText, TextBlock, TextBlockProvenance,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::time::Instant;
+use uuid::Uuid;
+
+pub const GOLDEN_SESSION: &str = include_str!("../../tests/fixtures/golden_session/session.json");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BenchRole {
+    TutorialGeneration,
+    ScreenExplainer,
+    AnchorGrounding,
+}
+
+impl BenchRole {
+    pub const ALL: [BenchRole; 3] = [
+        BenchRole::TutorialGeneration,
+        BenchRole::ScreenExplainer,
+        BenchRole::AnchorGrounding,
+    ];
+
+    /// The `ModelRoles` field the role is assigned through.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BenchRole::TutorialGeneration => "tutorial_generation",
+            BenchRole::ScreenExplainer => "screen_explainer",
+            BenchRole::AnchorGrounding => "anchor_grounding",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|role| role.as_str() == name)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GoldenSession {
+    pub name: String,
+    pub frames: Vec<GoldenFrame>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GoldenFrame {
+    pub frame_event_id: Uuid,
+    pub frame_ms: i64,
+    pub step_title: String,
+    pub window: Option<GoldenWindow>,
+    pub ocr: Vec<GoldenSpan>,
+    pub click: Option<GoldenClick>,
+    /// Label of the element an anchor should be grounded on, if any.
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GoldenWindow {
+    pub evidence_id: Uuid,
+    pub bundle_id: String,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GoldenSpan {
+    pub evidence_id: Uuid,
+    pub text: String,
+    pub bbox_norm: BBoxNorm,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GoldenClick {
+    pub evidence_id: Uuid,
+    pub x: u32,
+    pub y: u32,
+}
+
+pub fn golden_session() -> anyhow::Result<GoldenSession> {
+    Ok(serde_json::from_str(GOLDEN_SESSION)?)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BenchCase {
+    pub frame_event_id: Uuid,
+    pub prompt_asset_id: String,
+    pub output_asset_id: String,
+    pub latency_ms: u64,
+    pub schema_valid: bool,
+    pub violations: Vec<String>,
+    pub citation_valid_pct: u8,
+    pub grounding_pct: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BenchReport {
+    pub fixture: String,
+    pub benchmark: String,
+    pub model_id: String,
+    pub score: i32,
+    pub metrics: BenchMetrics,
+    pub cases: Vec<BenchCase>,
+}
 
-pub fn record(conn: &DbConn, model_id: &str, score: i32) -> anyhow::Result<BenchRecord> {
-    repo_models::record_benchmark(conn, model_id, score)
+/// Runs every case of `role` through `generate`. Each prompt and raw reply is
+/// kept through `store`, which returns the asset id it was stored under.
+pub fn run(
+    session: &GoldenSession,
+    role: BenchRole,
+    model_id: &str,
+    mut generate: impl FnMut(&str) -> anyhow::Result<String>,
+    mut store: impl FnMut(&[u8]) -> anyhow::Result<String>,
+) -> anyhow::Result<BenchReport> {
+    let mut cases = Vec::new();
+    for (idx, frame) in session.frames.iter().enumerate() {
+        let Some(prompt) = prompt_for(role, idx, frame) else {
+            continue;
+        };
+        let started = Instant::now();
+        let raw = generate(&prompt)?;
+        let latency_ms = started.elapsed().as_millis() as u64;
+        let scored = score(role, frame, &raw);
+        cases.push(BenchCase {
+            frame_event_id: frame.frame_event_id,
+            prompt_asset_id: store(prompt.as_bytes())?,
+            output_asset_id: store(raw.as_bytes())?,
+            latency_ms,
+            schema_valid: scored.violations.is_empty(),
+            violations: scored.violations,
+            citation_valid_pct: scored.citation_valid_pct,
+            grounding_pct: scored.grounding_pct,
+        });
+    }
+    let metrics = metrics(&cases);
+    Ok(BenchReport {
+        fixture: session.name.clone(),
+        benchmark: role.as_str().to_string(),
+        model_id: model_id.to_string(),
+        score: (i32::from(metrics.schema_valid_pct)
+            + i32::from(metrics.citation_valid_pct)
+            + i32::from(metrics.grounding_pct))
+            / 3,
+        metrics,
+        cases,
+    })
+}
+
+pub fn record(
+    conn: &DbConn,
+    report: &BenchReport,
+    report_asset_id: &str,
+) -> anyhow::Result<BenchRecord> {
+    repo_models::record_benchmark(
+        conn,
+        &report.model_id,
+        &report.benchmark,
+        &report.metrics,
+        report.score,
+        report_asset_id,
+    )
 }
 
 pub fn list(conn: &DbConn) -> anyhow::Result<BenchListResponse> {
     repo_models::list_benchmarks(conn)
 }
+
+/// The frame's evidence as the event log would derive it.
+fn frame_evidence(frame: &GoldenFrame) -> Vec<EvidenceItem> {
+    let item = |evidence_id, kind: &str, bbox_norm, note| EvidenceItem {
+        evidence_id,
+        kind: kind.to_string(),
+        source_id: frame.frame_event_id.to_string(),
+        locators: vec![EvidenceLocator {
+            locator_type: EvidenceLocatorType::FrameBbox,
+            asset_id: None,
+            frame_ms: Some(frame.frame_ms),
+            bbox_norm,
+            text_offset: None,
+            note,
+        }],
+    };
+    let mut out = Vec::new();
+    out.extend(frame.window.iter().map(|w| {
+        item(
+            w.evidence_id,
+            "WindowMeta",
+            None,
+            Some(format!("{}:{}", w.bundle_id, w.title)),
+        )
+    }));
+    out.extend(frame.ocr.iter().map(|span| {
+        item(
+            span.evidence_id,
+            "OcrSpan",
+            Some(span.bbox_norm.clone()),
+            Some(span.text.clone()),
+        )
+    }));
+    out.extend(frame.click.iter().map(|c| {
+        item(
+            c.evidence_id,
+            "Click",
+            Some(BBoxNorm {
+                x: c.x,
+                y: c.y,
+                w: 0,
+                h: 0,
+            }),
+            None,
+        )
+    }));
+    out
+}
+
+fn prompt_evidence(frame: &GoldenFrame) -> Vec<narrate::PromptEvidence> {
+    frame_evidence(frame)
+        .iter()
+        .filter_map(|item| {
+            Some(narrate::PromptEvidence {
+                evidence_id: item.evidence_id,
+                description: narrate::describe(item)?,
+            })
+        })
+        .collect()
+}
+
+fn prompt_for(role: BenchRole, idx: usize, frame: &GoldenFrame) -> Option<String> {
+    let quoted = |span: &GoldenSpan| format!("\"{}\"", span.text);
+    match role {
+        BenchRole::TutorialGeneration => {
+            let offered = prompt_evidence(frame);
+            let step = Step {
+                step_id: frame.frame_event_id,
+                order_index: idx as u32,
+                title: frame.step_title.clone(),
+                body: StructuredText {
+                    blocks: vec![TextBlock {
+                        block_id: "b1".to_string(),
+                        text: format!("{}.", frame.step_title),
+                        provenance: TextBlockProvenance::Generated,
+                        evidence_refs: offered.iter().map(|e| e.evidence_id).collect(),
+                    }],
+                },
+                risk_tags: vec![],
+                branch_label: None,
+            };
+            Some(narrate::build_prompt(&step, &offered))
+        }
+        BenchRole::ScreenExplainer => {
+            let window = frame
+                .window
+                .as_ref()
+                .map(|w| format!("Frontmost window: \"{}\" ({})\n", w.title, w.bundle_id))
+                .unwrap_or_default();
+            let text = frame
+                .ocr
+                .iter()
+                .map(|span| format!("- {}", quoted(span)))
+                .collect::<Vec<_>>()
+                .join("\n");
+            Some(format!(
+                "Explain what this screen of a software tutorial shows.\n\
+                 Frame: {id}\n{window}On-screen text:\n{text}\n\
+                 Describe only what the text above shows and quote on-screen text exactly.\n\
+                 Reply with JSON only: {{\"frame_event_id\":\"{id}\",\"summary\":\"...\"}}",
+                id = frame.frame_event_id,
+            ))
+        }
+        BenchRole::AnchorGrounding => {
+            let target = frame.target.as_ref()?;
+            let text = frame
+                .ocr
+                .iter()
+                .map(|span| {
+                    let b = &span.bbox_norm;
+                    format!("- {} at ({}, {}, {}, {})", quoted(span), b.x, b.y, b.w, b.h)
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            Some(format!(
+                "Locate the UI element labelled \"{target}\" on the frame at {ms} ms.\n\
+                 On-screen text with boxes (x, y, w, h in 0..=10000):\n{text}\n\
+                 Reply with JSON only: {{\"locators\":[{{\"locator_type\":\"frame_bbox\",\
+                 \"asset_id\":null,\"frame_ms\":{ms},\"bbox_norm\":{{\"x\":0,\"y\":0,\"w\":0,\"h\":0}},\
+                 \"text_offset\":null,\"note\":null}}]}}",
+                ms = frame.frame_ms,
+            ))
+        }
+    }
+}
+
+struct Scored {
+    violations: Vec<String>,
+    citation_valid_pct: u8,
+    grounding_pct: u8,
+}
+
+impl Scored {
+    fn invalid(violations: Vec<String>) -> Self {
+        Self {
+            violations,
+            citation_valid_pct: 0,
+            grounding_pct: 0,
+        }
+    }
+}
+
+fn parsed<T: ProviderOutput>(raw: &str) -> Result<T, Scored> {
+    provider_schema::parse::<T>(raw)
+        .map_err(|violations| Scored::invalid(violations.iter().map(ToString::to_string).collect()))
+}
+
+fn score(role: BenchRole, frame: &GoldenFrame, raw: &str) -> Scored {
+    let visible = grounding::normalize(
+        &frame
+            .ocr
+            .iter()
+            .map(|span| span.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+    let result = match role {
+        BenchRole::TutorialGeneration => parsed::<StepNarrativeOutput>(raw).map(|output| {
+            let offered = prompt_evidence(frame)
+                .into_iter()
+                .map(|e| e.evidence_id)
+                .collect::<BTreeSet<_>>();
+            let (body, _) = narrate::narrated_body(frame.frame_event_id, &output, &offered);
+            let text = body
+                .blocks
+                .iter()
+                .map(|b| b.text.as_str())
+                .collect::<Vec<_>>()
+                .join(" ");
+            Scored {
+                violations: vec![],
+                citation_valid_pct: pct(body.blocks.len(), output.blocks.len()),
+                grounding_pct: if body.blocks.is_empty() {
+                    0
+                } else {
+                    grounded_pct(&text, &visible)
+                },
+            }
+        }),
+        BenchRole::ScreenExplainer => parsed::<ScreenExplanationOutput>(raw).map(|output| Scored {
+            violations: vec![],
+            citation_valid_pct: if output.frame_event_id == frame.frame_event_id {
+                100
+            } else {
+                0
+            },
+            grounding_pct: grounded_pct(&output.summary, &visible),
+        }),
+        BenchRole::AnchorGrounding => parsed::<AnchorGroundingOutput>(raw).map(|output| {
+            let target = frame
+                .ocr
+                .iter()
+                .find(|span| Some(&span.text) == frame.target.as_ref())
+                .map(|span| &span.bbox_norm);
+            let on_frame = output
+                .locators
+                .iter()
+                .filter(|l| l.frame_ms == Some(frame.frame_ms))
+                .filter_map(|l| l.bbox_norm.as_ref())
+                .collect::<Vec<_>>();
+            Scored {
+                violations: vec![],
+                citation_valid_pct: pct(on_frame.len(), output.locators.len()),
+                grounding_pct: target
+                    .and_then(|t| on_frame.iter().map(|b| overlap_pct(b, t)).max())
+                    .unwrap_or(0),
+            }
+        }),
+    };
+    result.unwrap_or_else(|invalid| invalid)
+}
+
+/// Share of the text's claims that the frame's OCR text shows; text with no
+/// claims is fully grounded.
+fn grounded_pct(text: &str, visible: &str) -> u8 {
+    let claims = grounding::claims(text);
+    let ungrounded = grounding::ungrounded_claims(&claims, visible);
+    if claims.is_empty() {
+        100
+    } else {
+        pct(claims.len() - ungrounded.len(), claims.len())
+    }
+}
+
+/// Intersection over union of two boxes, as a percentage.
+fn overlap_pct(a: &BBoxNorm, b: &BBoxNorm) -> u8 {
+    let span =
+        |a0: u32, a1: u32, b0: u32, b1: u32| u64::from(a1.min(b1).saturating_sub(a0.max(b0)));
+    let inter = span(a.x, a.x + a.w, b.x, b.x + b.w) * span(a.y, a.y + a.h, b.y, b.y + b.h);
+    let area = |b: &BBoxNorm| u64::from(b.w) * u64::from(b.h);
+    let union = area(a) + area(b) - inter;
+    (inter * 100).checked_div(union).unwrap_or(0) as u8
+}
+
+fn pct(part: usize, whole: usize) -> u8 {
+    (part.min(whole) * 100).checked_div(whole).unwrap_or(0) as u8
+}
+
+fn metrics(cases: &[BenchCase]) -> BenchMetrics {
+    let n = cases.len();
+    let mean = |f: fn(&BenchCase) -> u8| -> u8 {
+        cases
+            .iter()
+            .map(|c| usize::from(f(c)))
+            .sum::<usize>()
+            .checked_div(n)
+            .unwrap_or(0) as u8
+    };
+    BenchMetrics {
+        cases: n as u32,
+        schema_valid_pct: pct(cases.iter().filter(|c| c.schema_valid).count(), n),
+        citation_valid_pct: mean(|c| c.citation_valid_pct),
+        grounding_pct: mean(|c| c.grounding_pct),
+        mean_latency_ms: cases
+            .iter()
+            .map(|c| c.latency_ms)
+            .sum::<u64>()
+            .checked_div(n as u64)
+            .unwrap_or(0),
+        max_latency_ms: cases.iter().map(|c| c.latency_ms).max().unwrap_or(0),
+    }
+}
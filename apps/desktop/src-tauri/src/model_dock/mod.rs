@@ -2,3 +2,4 @@ pub mod adapters;
 pub mod bench;
 pub mod registry;
 pub mod roles;
+pub mod verify;
@@ -6,9 +6,10 @@ pub fn register(
     conn: &DbConn,
     provider: &str,
     label: &str,
+    model_name: &str,
     digest: &str,
 ) -> anyhow::Result<ModelProfile> {
-    repo_models::insert_model(conn, provider, label, digest)
+    repo_models::insert_model(conn, provider, label, model_name, digest)
 }
 
 pub fn list(conn: &DbConn) -> anyhow::Result<ModelsListResponse> {
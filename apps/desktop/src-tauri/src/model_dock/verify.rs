@@ -0,0 +1,110 @@
+//! Checks registered model digests against what the provider actually has
+//! installed: the Ollama manifest digest from `/api/tags`, or the weights
+//! digest the MLX runner reports on load. Pins in export manifests are only
+//! trusted once the installed model still matches them.
+
+use super::adapters::{mlx, ollama};
+use crate::policy::network_allowlist::NetworkPolicy;
+use opscinema_export_manifest::ModelPin;
+use opscinema_types::{ExportWarning, ModelProfile};
+
+pub const MODEL_DIGEST_MISMATCH_WARNING_CODE: &str = "MODEL_DIGEST_MISMATCH";
+pub const MODEL_DIGEST_UNVERIFIED_WARNING_CODE: &str = "MODEL_DIGEST_UNVERIFIED";
+
+/// Where installed models are looked up.
+#[derive(Debug, Clone)]
+pub struct ProviderTargets {
+    pub policy: NetworkPolicy,
+    pub ollama_host: String,
+    pub mlx: mlx::RunOptions,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProbeError {
+    #[error(transparent)]
+    Ollama(#[from] ollama::OllamaError),
+    #[error(transparent)]
+    Mlx(#[from] mlx::MlxError),
+    #[error("provider {0} does not report model digests")]
+    Unsupported(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DigestCheck {
+    Verified,
+    /// The provider has the model installed under another digest.
+    Mismatch {
+        installed: String,
+    },
+    /// The provider no longer has the model.
+    NotInstalled,
+    /// The provider could not be asked.
+    Unverified {
+        reason: String,
+    },
+}
+
+/// Digest of `model_name` as `provider` has it installed now.
+pub fn installed_digest(
+    targets: &ProviderTargets,
+    provider: &str,
+    model_name: &str,
+) -> Result<String, ProbeError> {
+    match provider {
+        "ollama" => {
+            Ok(ollama::find_model(&targets.policy, &targets.ollama_host, model_name)?.digest)
+        }
+        "mlx" => Ok(mlx::probe_digest(&targets.mlx, model_name)?),
+        other => Err(ProbeError::Unsupported(other.to_string())),
+    }
+}
+
+/// Digests compare case-insensitively and without their algorithm prefix:
+/// `/api/tags` may report bare hex where a user typed `sha256:<hex>`.
+pub fn same_digest(a: &str, b: &str) -> bool {
+    let bare = |d: &str| {
+        let d = d.trim();
+        d.split_once(':')
+            .map_or(d, |(_, hex)| hex)
+            .to_ascii_lowercase()
+    };
+    !a.trim().is_empty() && bare(a) == bare(b)
+}
+
+pub fn check(model: &ModelProfile, targets: &ProviderTargets) -> DigestCheck {
+    match installed_digest(targets, &model.provider, &model.model_name) {
+        Ok(installed) if same_digest(&model.digest, &installed) => DigestCheck::Verified,
+        Ok(installed) => DigestCheck::Mismatch { installed },
+        Err(ProbeError::Ollama(ollama::OllamaError::ModelNotFound(_)))
+        | Err(ProbeError::Mlx(mlx::MlxError::ModelNotFound(_))) => DigestCheck::NotInstalled,
+        Err(e) => DigestCheck::Unverified {
+            reason: e.to_string(),
+        },
+    }
+}
+
+/// The export warning for a pin whose model failed its check, if any.
+pub fn pin_warning(pin: &ModelPin, model_name: &str, check: &DigestCheck) -> Option<ExportWarning> {
+    let (code, problem) = match check {
+        DigestCheck::Verified => return None,
+        DigestCheck::Mismatch { installed } => (
+            MODEL_DIGEST_MISMATCH_WARNING_CODE,
+            format!("installed digest is {installed}"),
+        ),
+        DigestCheck::NotInstalled => (
+            MODEL_DIGEST_MISMATCH_WARNING_CODE,
+            "model is no longer installed".to_string(),
+        ),
+        DigestCheck::Unverified { reason } => (
+            MODEL_DIGEST_UNVERIFIED_WARNING_CODE,
+            format!("could not verify: {reason}"),
+        ),
+    };
+    Some(ExportWarning {
+        code: code.to_string(),
+        message: format!(
+            "role={} model={} ({model_name}) pinned at {}: {problem}",
+            pin.role, pin.model_id, pin.digest
+        ),
+    })
+}
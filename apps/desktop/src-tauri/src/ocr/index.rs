@@ -1,14 +1,114 @@
-use opscinema_types::{OcrBlock, OcrSearchHit};
-
-pub fn search(session_blocks: &[OcrBlock], query: &str, frame_ms: i64) -> Vec<OcrSearchHit> {
-    let q = query.to_lowercase();
-    session_blocks
-        .iter()
-        .filter(|block| block.text.to_lowercase().contains(&q))
-        .map(|block| OcrSearchHit {
-            frame_ms,
-            block_id: block.ocr_block_id.clone(),
-            snippet: block.text.clone(),
-        })
+use opscinema_types::TextOffset;
+
+/// A user search over OCR text, compiled for the FTS5 index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OcrQuery {
+    /// FTS5 `MATCH` expression; every term is quoted so punctuation in error
+    /// strings is never parsed as query syntax.
+    pub fts: String,
+    /// Lowercased terms and phrases, for matching text outside the index.
+    pub terms: Vec<String>,
+}
+
+impl OcrQuery {
+    /// Plain-text fallback with the same semantics as the index: every term
+    /// must appear (prefix terms as substrings).
+    pub fn matches_text(&self, text: &str) -> bool {
+        let text = fold(text);
+        self.terms
+            .iter()
+            .all(|t| find_all(&text, &fold(t)).next().is_some())
+    }
+
+    /// Char ranges of every term occurrence in `text`, merged and sorted;
+    /// empty unless [`Self::matches_text`] holds.
+    pub fn highlights(&self, text: &str) -> Vec<TextOffset> {
+        let text = fold(text);
+        let mut ranges = Vec::new();
+        for term in &self.terms {
+            let term = fold(term);
+            let before = ranges.len();
+            ranges.extend(find_all(&text, &term).map(|start| (start, start + term.len())));
+            if ranges.len() == before {
+                return vec![];
+            }
+        }
+        ranges.sort_unstable();
+        let mut merged: Vec<TextOffset> = Vec::new();
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if start as u32 <= last.end => last.end = last.end.max(end as u32),
+                _ => merged.push(TextOffset {
+                    start: start as u32,
+                    end: end as u32,
+                }),
+            }
+        }
+        merged
+    }
+}
+
+/// Lowercases char by char so offsets into the result are offsets into the
+/// original text.
+fn fold(text: &str) -> Vec<char> {
+    text.chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
         .collect()
 }
+
+fn find_all<'a>(text: &'a [char], term: &'a [char]) -> impl Iterator<Item = usize> + 'a {
+    let windows = if term.is_empty() {
+        [].windows(1)
+    } else {
+        text.windows(term.len())
+    };
+    windows
+        .enumerate()
+        .filter(move |(_, w)| *w == term)
+        .map(|(idx, _)| idx)
+}
+
+/// Parses `"quoted phrases"`, `prefix*` terms and bare terms, all required.
+/// Returns `None` when nothing searchable remains.
+pub fn parse_query(raw: &str) -> Option<OcrQuery> {
+    let mut parts: Vec<(String, bool)> = Vec::new();
+    let mut chars = raw.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let phrase = chars.by_ref().take_while(|c| *c != '"').collect::<String>();
+            let prefix = chars.next_if_eq(&'*').is_some();
+            parts.push((phrase, prefix));
+        } else {
+            let mut word = String::new();
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                if c != '"' {
+                    word.push(c);
+                }
+            }
+            let prefix = word.len() > 1 && word.ends_with('*');
+            parts.push((word.trim_end_matches('*').to_string(), prefix));
+        }
+    }
+
+    let mut fts = Vec::new();
+    let mut terms = Vec::new();
+    for (text, prefix) in parts {
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !text.chars().any(char::is_alphanumeric) {
+            continue;
+        }
+        fts.push(format!(
+            "\"{}\"{}",
+            text.replace('"', "\"\""),
+            if prefix { "*" } else { "" }
+        ));
+        terms.push(text.to_lowercase());
+    }
+    (!fts.is_empty()).then(|| OcrQuery {
+        fts: fts.join(" "),
+        terms,
+    })
+}
@@ -1,3 +1,6 @@
 pub mod index;
 pub mod pipeline;
+pub mod registry;
+#[cfg(feature = "ocr-tesseract")]
+pub mod tesseract;
 pub mod vision;
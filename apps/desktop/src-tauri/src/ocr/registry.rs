@@ -0,0 +1,44 @@
+use crate::ocr::vision::{StubVisionProvider, VisionProvider};
+use crate::platform::macos::vision_ocr;
+use opscinema_types::OcrProviderKind;
+
+/// Providers compiled into this build and usable on this host.
+pub fn available() -> Vec<OcrProviderKind> {
+    let mut kinds = vec![OcrProviderKind::Auto, OcrProviderKind::Stub];
+    if cfg!(target_os = "macos") {
+        kinds.push(OcrProviderKind::AppleVision);
+    }
+    if cfg!(feature = "ocr-tesseract") {
+        kinds.push(OcrProviderKind::Tesseract);
+    }
+    kinds
+}
+
+pub fn is_available(kind: OcrProviderKind) -> bool {
+    available().contains(&kind)
+}
+
+/// Resolves the configured provider. `OPSCINEMA_PROVIDER_MODE=stub` still
+/// forces stub output, and `auto` mode falls back to it when the real engine
+/// fails, so fixture runs stay deterministic whatever is selected.
+pub fn provider_for(kind: OcrProviderKind) -> anyhow::Result<Box<dyn VisionProvider>> {
+    if !is_available(kind) {
+        anyhow::bail!("OCR provider {kind:?} is not available in this build")
+    }
+    let provider: Box<dyn VisionProvider> = match kind {
+        OcrProviderKind::Stub => Box::new(StubVisionProvider),
+        OcrProviderKind::AppleVision => vision_ocr::provider(),
+        OcrProviderKind::Tesseract => tesseract()?,
+        OcrProviderKind::Auto if cfg!(target_os = "macos") => vision_ocr::provider(),
+        OcrProviderKind::Auto if cfg!(feature = "ocr-tesseract") => tesseract()?,
+        OcrProviderKind::Auto => vision_ocr::provider(),
+    };
+    Ok(provider)
+}
+
+fn tesseract() -> anyhow::Result<Box<dyn VisionProvider>> {
+    #[cfg(feature = "ocr-tesseract")]
+    return Ok(crate::ocr::tesseract::provider());
+    #[cfg(not(feature = "ocr-tesseract"))]
+    anyhow::bail!("built without the ocr-tesseract feature")
+}
@@ -0,0 +1,275 @@
+use crate::capture::coord::normalize_bbox;
+use crate::ocr::vision::{StubVisionProvider, VisionProvider};
+use crate::platform::macos::vision_ocr::{self, ProviderMode};
+use opscinema_types::OcrBlock;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const DEFAULT_BINARY: &str = "tesseract";
+const DEFAULT_LANGUAGES: &str = "eng";
+/// TSV row level for single words; lines are rebuilt from these.
+const WORD_LEVEL: &str = "5";
+const PAGE_LEVEL: &str = "1";
+
+/// Runs the `tesseract` CLI on each frame and turns its word-level TSV into
+/// one block per text line. `OPSCINEMA_TESSERACT_BIN` and
+/// `OPSCINEMA_TESSERACT_LANG` (tesseract `-l` syntax, e.g. `eng+deu`)
+/// override the binary and languages.
+pub struct TesseractProvider {
+    binary: String,
+    languages: String,
+}
+
+impl TesseractProvider {
+    pub fn new(binary: impl Into<String>, languages: impl Into<String>) -> Self {
+        Self {
+            binary: binary.into(),
+            languages: languages.into(),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(
+            std::env::var("OPSCINEMA_TESSERACT_BIN").unwrap_or_else(|_| DEFAULT_BINARY.to_string()),
+            std::env::var("OPSCINEMA_TESSERACT_LANG")
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_LANGUAGES.to_string()),
+        )
+    }
+}
+
+impl VisionProvider for TesseractProvider {
+    fn recognize(&self, png_bytes: &[u8]) -> anyhow::Result<Vec<OcrBlock>> {
+        let mut child = Command::new(&self.binary)
+            .args(["stdin", "stdout", "-l", &self.languages, "tsv"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| anyhow::anyhow!("failed to start {}: {e}", self.binary))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(png_bytes)?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "tesseract failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        parse_tsv(
+            &String::from_utf8_lossy(&output.stdout),
+            &language_tag(&self.languages),
+        )
+    }
+}
+
+/// Tesseract wrapped in the same test hooks as the Vision provider:
+/// `OPSCINEMA_VISION_RAW_JSON` replaces engine output, `OPSCINEMA_PROVIDER_MODE`
+/// `stub` skips the engine and `auto` falls back to stub output on failure.
+pub fn provider() -> Box<dyn VisionProvider> {
+    Box::new(HybridTesseractProvider(TesseractProvider::from_env()))
+}
+
+struct HybridTesseractProvider(TesseractProvider);
+
+impl VisionProvider for HybridTesseractProvider {
+    fn recognize(&self, png_bytes: &[u8]) -> anyhow::Result<Vec<OcrBlock>> {
+        if let Ok(raw) = std::env::var("OPSCINEMA_VISION_RAW_JSON") {
+            return vision_ocr::parse_provider_blocks(raw.as_bytes());
+        }
+        match ProviderMode::from_env() {
+            ProviderMode::Stub => StubVisionProvider.recognize(png_bytes),
+            ProviderMode::Real => self.0.recognize(png_bytes),
+            ProviderMode::Auto => self
+                .0
+                .recognize(png_bytes)
+                .or_else(|_| StubVisionProvider.recognize(png_bytes)),
+        }
+    }
+}
+
+/// Groups word rows by (page, block, paragraph, line). The bbox is the union of
+/// the words, confidence their mean, and coordinates are normalized to the
+/// page row's size.
+pub(crate) fn parse_tsv(tsv: &str, language: &str) -> anyhow::Result<Vec<OcrBlock>> {
+    let mut page: Option<(f64, f64)> = None;
+    let mut lines: BTreeMap<[u32; 4], Line> = BTreeMap::new();
+    for (idx, row) in tsv.lines().enumerate().skip(1) {
+        let cols = row.split('\t').collect::<Vec<_>>();
+        if cols.len() < 12 {
+            continue;
+        }
+        let num = |i: usize| -> anyhow::Result<f64> {
+            cols[i]
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| anyhow::anyhow!("tesseract tsv row {}: bad column {i}", idx + 1))
+        };
+        match cols[0] {
+            PAGE_LEVEL if page.is_none() => page = Some((num(8)?, num(9)?)),
+            WORD_LEVEL => {
+                let text = cols[11..].join("\t");
+                let conf = num(10)?;
+                if text.trim().is_empty() || conf < 0.0 {
+                    continue;
+                }
+                let key = [1, 2, 3, 4].map(|i| cols[i].trim().parse::<u32>().unwrap_or(0));
+                let (x, y, w, h) = (num(6)?, num(7)?, num(8)?, num(9)?);
+                lines
+                    .entry(key)
+                    .or_default()
+                    .push(text.trim(), conf, x, y, w, h);
+            }
+            _ => {}
+        }
+    }
+    let Some((page_w, page_h)) = page.filter(|(w, h)| *w > 0.0 && *h > 0.0) else {
+        if lines.is_empty() {
+            return Ok(vec![]);
+        }
+        anyhow::bail!("tesseract tsv has no page row")
+    };
+
+    Ok(lines
+        .into_values()
+        .map(|line| {
+            let bbox_norm = normalize_bbox(
+                line.x0,
+                line.y0,
+                line.x1 - line.x0,
+                line.y1 - line.y0,
+                page_w,
+                page_h,
+            );
+            let text = line.words.join(" ");
+            OcrBlock {
+                ocr_block_id: format!(
+                    "ocr:{}",
+                    blake3::hash(
+                        format!(
+                            "{}:{}:{}:{}:{}",
+                            text, bbox_norm.x, bbox_norm.y, bbox_norm.w, bbox_norm.h
+                        )
+                        .as_bytes()
+                    )
+                    .to_hex()
+                ),
+                bbox_norm,
+                confidence: (line.conf_sum / line.words.len() as f64)
+                    .round()
+                    .clamp(0.0, 100.0) as u8,
+                language: Some(language.to_string()),
+                text,
+            }
+        })
+        .collect())
+}
+
+/// BCP 47 tag for the first configured tesseract language.
+fn language_tag(languages: &str) -> String {
+    let first = languages.split('+').next().unwrap_or(DEFAULT_LANGUAGES);
+    match first {
+        "eng" => "en",
+        "deu" => "de",
+        "fra" => "fr",
+        "spa" => "es",
+        "ita" => "it",
+        "por" => "pt",
+        "nld" => "nl",
+        "jpn" => "ja",
+        "chi_sim" => "zh-Hans",
+        "chi_tra" => "zh-Hant",
+        other => other,
+    }
+    .to_string()
+}
+
+struct Line {
+    words: Vec<String>,
+    conf_sum: f64,
+    x0: f64,
+    y0: f64,
+    x1: f64,
+    y1: f64,
+}
+
+impl Default for Line {
+    fn default() -> Self {
+        Self {
+            words: vec![],
+            conf_sum: 0.0,
+            x0: f64::MAX,
+            y0: f64::MAX,
+            x1: f64::MIN,
+            y1: f64::MIN,
+        }
+    }
+}
+
+impl Line {
+    fn push(&mut self, word: &str, conf: f64, x: f64, y: f64, w: f64, h: f64) {
+        self.words.push(word.to_string());
+        self.conf_sum += conf;
+        self.x0 = self.x0.min(x);
+        self.y0 = self.y0.min(y);
+        self.x1 = self.x1.max(x + w);
+        self.y1 = self.y1.max(y + h);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{language_tag, parse_tsv};
+    use opscinema_types::BBoxNorm;
+
+    const HEADER: &str = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext";
+
+    #[test]
+    fn groups_words_into_normalized_lines() {
+        let tsv = [
+            HEADER,
+            "1\t1\t0\t0\t0\t0\t0\t0\t1000\t500\t-1\t",
+            "4\t1\t1\t1\t1\t0\t100\t50\t300\t20\t-1\t",
+            "5\t1\t1\t1\t1\t1\t100\t50\t100\t20\t90.5\tORA-12541:",
+            "5\t1\t1\t1\t1\t2\t210\t52\t90\t18\t80.5\tTNS:no",
+            "5\t1\t1\t1\t1\t3\t310\t52\t90\t18\t87\tlistener",
+            "5\t1\t1\t1\t2\t1\t100\t100\t50\t20\t-1\t ",
+            "5\t1\t2\t1\t1\t1\t500\t400\t250\t50\t70\tRetry",
+        ]
+        .join("\n");
+        let blocks = parse_tsv(&tsv, "en").expect("parse");
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].text, "ORA-12541: TNS:no listener");
+        assert_eq!(
+            blocks[0].bbox_norm,
+            BBoxNorm {
+                x: 1000,
+                y: 1000,
+                w: 3000,
+                h: 400
+            }
+        );
+        assert_eq!(blocks[0].confidence, 86);
+        assert_eq!(blocks[0].language.as_deref(), Some("en"));
+        assert_eq!(blocks[1].text, "Retry");
+        assert_ne!(blocks[0].ocr_block_id, blocks[1].ocr_block_id);
+    }
+
+    #[test]
+    fn rejects_malformed_rows_and_handles_blank_pages() {
+        let blank = [HEADER, "1\t1\t0\t0\t0\t0\t0\t0\t1000\t500\t-1\t"].join("\n");
+        assert!(parse_tsv(&blank, "en").expect("blank").is_empty());
+        let bad = [
+            HEADER,
+            "1\t1\t0\t0\t0\t0\t0\t0\t1000\t500\t-1\t",
+            "5\t1\t1\t1\t1\t1\tx\t50\t100\t20\t90\tword",
+        ]
+        .join("\n");
+        assert!(parse_tsv(&bad, "en").is_err());
+        assert_eq!(language_tag("deu+eng"), "de");
+        assert_eq!(language_tag("kor"), "kor");
+    }
+}
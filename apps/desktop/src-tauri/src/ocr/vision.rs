@@ -1,4 +1,5 @@
 use crate::capture::coord::normalize_bbox;
+use crate::capture::screen::STUB_TEXT_KEYWORD;
 use opscinema_types::OcrBlock;
 
 pub trait VisionProvider: Send + Sync {
@@ -10,11 +11,12 @@ pub struct StubVisionProvider;
 
 impl VisionProvider for StubVisionProvider {
     fn recognize(&self, png_bytes: &[u8]) -> anyhow::Result<Vec<OcrBlock>> {
-        let text = String::from_utf8_lossy(png_bytes);
+        let text = crate::image::png_text(png_bytes, STUB_TEXT_KEYWORD)
+            .unwrap_or_else(|| String::from_utf8_lossy(png_bytes).to_string());
         Ok(vec![OcrBlock {
             ocr_block_id: format!("ocr:{}", blake3::hash(png_bytes).to_hex()),
             bbox_norm: normalize_bbox(10.0, 10.0, 200.0, 60.0, 1920.0, 1080.0),
-            text: text.to_string(),
+            text,
             confidence: 90,
             language: Some("en".to_string()),
         }])
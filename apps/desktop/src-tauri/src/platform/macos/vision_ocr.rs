@@ -5,14 +5,14 @@ use std::path::PathBuf;
 use std::process::Command;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum ProviderMode {
+pub(crate) enum ProviderMode {
     Stub,
     Auto,
     Real,
 }
 
 impl ProviderMode {
-    fn from_env() -> Self {
+    pub(crate) fn from_env() -> Self {
         match std::env::var("OPSCINEMA_PROVIDER_MODE")
             .unwrap_or_else(|_| "auto".to_string())
             .to_lowercase()
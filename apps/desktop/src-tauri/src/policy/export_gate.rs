@@ -1,9 +1,14 @@
-use opscinema_types::{AppError, AppErrorCode, ExportWarning, Step, TextBlockProvenance};
+use crate::model_dock::verify::MODEL_DIGEST_UNVERIFIED_WARNING_CODE;
+use opscinema_types::{
+    AppError, AppErrorCode, EvidenceRefIssue, EvidenceRefIssueKind, ExportWarning, Step,
+    TextBlockProvenance,
+};
 
 #[derive(Debug, Clone)]
 pub struct ExportGateInput {
     pub steps: Vec<Step>,
     pub missing_evidence: Vec<String>,
+    pub invalid_evidence_refs: Vec<EvidenceRefIssue>,
     pub degraded_anchor_ids: Vec<String>,
     pub warnings: Vec<ExportWarning>,
 }
@@ -28,11 +33,67 @@ pub fn ensure_generated_blocks_have_evidence(steps: &[Step]) -> Result<(), AppEr
     Ok(())
 }
 
+/// Blocks on the first category of unresolvable refs, each with its own
+/// message: dangling, then other-session, then invalidated.
+pub fn ensure_evidence_refs_resolve(issues: &[EvidenceRefIssue]) -> Result<(), AppError> {
+    for (kind, message, action_hint) in [
+        (
+            EvidenceRefIssueKind::Dangling,
+            "Evidence refs do not resolve to any captured evidence",
+            "Remove the refs or cite evidence from this session",
+        ),
+        (
+            EvidenceRefIssueKind::OtherSession,
+            "Evidence refs point at another session's evidence",
+            "Cite evidence captured in this session",
+        ),
+        (
+            EvidenceRefIssueKind::Invalidated,
+            "Evidence refs point at invalidated evidence",
+            "Reacquire or manually fix the anchor, or cite other evidence",
+        ),
+    ] {
+        let refs = issues
+            .iter()
+            .filter(|issue| issue.kind == kind)
+            .map(|issue| {
+                format!(
+                    "step={} block={} evidence={}",
+                    issue.step_id, issue.block_id, issue.evidence_id
+                )
+            })
+            .collect::<Vec<_>>();
+        if !refs.is_empty() {
+            return Err(AppError {
+                code: AppErrorCode::ExportGateFailed,
+                message: message.to_string(),
+                details: Some(refs.join("; ")),
+                recoverable: true,
+                action_hint: Some(action_hint.to_string()),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Whether `warning` fails the TutorialPack strict gate. A pinned model whose
+/// provider could not be asked is recorded but does not block: nothing is
+/// known to be wrong, only unchecked.
+pub fn blocks_strict_gate(warning: &ExportWarning) -> bool {
+    warning.code != MODEL_DIGEST_UNVERIFIED_WARNING_CODE
+}
+
 pub fn tutorial_pack_gate(input: &ExportGateInput) -> Result<(), AppError> {
     ensure_generated_blocks_have_evidence(&input.steps)?;
+    ensure_evidence_refs_resolve(&input.invalid_evidence_refs)?;
+    let blocking_warnings = input
+        .warnings
+        .iter()
+        .filter(|w| blocks_strict_gate(w))
+        .count();
     if !input.missing_evidence.is_empty()
         || !input.degraded_anchor_ids.is_empty()
-        || !input.warnings.is_empty()
+        || blocking_warnings > 0
     {
         return Err(AppError {
             code: AppErrorCode::ExportGateFailed,
@@ -41,7 +102,7 @@ pub fn tutorial_pack_gate(input: &ExportGateInput) -> Result<(), AppError> {
                 "missing_evidence={} degraded_anchors={} warnings={}",
                 input.missing_evidence.len(),
                 input.degraded_anchor_ids.len(),
-                input.warnings.len()
+                blocking_warnings
             )),
             recoverable: true,
             action_hint: Some("Resolve evidence coverage, anchors, and warnings".to_string()),
@@ -52,6 +113,7 @@ pub fn tutorial_pack_gate(input: &ExportGateInput) -> Result<(), AppError> {
 
 pub fn proof_bundle_gate(input: &ExportGateInput) -> Result<(), AppError> {
     ensure_generated_blocks_have_evidence(&input.steps)?;
+    ensure_evidence_refs_resolve(&input.invalid_evidence_refs)?;
     if !input.missing_evidence.is_empty() {
         return Err(AppError {
             code: AppErrorCode::ExportGateFailed,
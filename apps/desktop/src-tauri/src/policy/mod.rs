@@ -1,4 +1,5 @@
 pub mod export_gate;
 pub mod network_allowlist;
 pub mod permissions;
+pub mod provider_schema;
 pub mod redaction;
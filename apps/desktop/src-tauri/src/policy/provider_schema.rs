@@ -0,0 +1,442 @@
+//! Strict validation of model-backed output. Every provider reply is parsed as
+//! JSON and checked against the schemars schema of its output type (plus the
+//! type's semantic checks) before it may reach the step, screen or anchor
+//! models. Invalid replies are retried with the violations fed back as a
+//! repair hint, up to a bounded number of attempts.
+
+use opscinema_types::{BBoxNorm, EvidenceLocator};
+use schemars::schema::{InstanceType, RootSchema, Schema, SchemaObject, SingleOrVec};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// First attempt plus two repairs.
+pub const MAX_ATTEMPTS: u32 = 3;
+const BBOX_NORM_MAX: u32 = 10_000;
+
+pub trait ProviderOutput: DeserializeOwned + Serialize + JsonSchema {
+    /// Stable name used in events and assets.
+    const KIND: &'static str;
+
+    /// Constraints the schema cannot express.
+    fn check(&self) -> Vec<SchemaViolation> {
+        Vec::new()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaViolation {
+    /// JSON path of the offending value, `$` for the root.
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+fn violation(path: &str, message: impl Into<String>) -> SchemaViolation {
+    SchemaViolation {
+        path: path.to_string(),
+        message: message.into(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ScreenExplanationOutput {
+    pub frame_event_id: uuid::Uuid,
+    #[schemars(length(min = 1, max = 4000))]
+    pub summary: String,
+}
+
+impl ProviderOutput for ScreenExplanationOutput {
+    const KIND: &'static str = "screen_explanation";
+
+    fn check(&self) -> Vec<SchemaViolation> {
+        if self.summary.trim().is_empty() {
+            return vec![violation("$.summary", "must not be blank")];
+        }
+        Vec::new()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AnchorGroundingOutput {
+    pub locators: Vec<EvidenceLocator>,
+}
+
+impl ProviderOutput for AnchorGroundingOutput {
+    const KIND: &'static str = "anchor_grounding";
+
+    fn check(&self) -> Vec<SchemaViolation> {
+        let mut out = Vec::new();
+        for (i, locator) in self.locators.iter().enumerate() {
+            if let Some(BBoxNorm { x, y, w, h }) = locator.bbox_norm {
+                let path = format!("$.locators[{i}].bbox_norm");
+                if w == 0 || h == 0 {
+                    out.push(violation(&path, "box must have positive size"));
+                }
+                if x.saturating_add(w) > BBOX_NORM_MAX || y.saturating_add(h) > BBOX_NORM_MAX {
+                    out.push(violation(
+                        &path,
+                        format!("box exceeds normalized bounds 0..={BBOX_NORM_MAX}"),
+                    ));
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Narrative for one step. Citations stay inline in `text` as `[ev:<id>]`
+/// markers; they are resolved after validation, block by block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct StepNarrativeOutput {
+    #[schemars(length(min = 1, max = 8))]
+    pub blocks: Vec<NarrativeBlock>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct NarrativeBlock {
+    #[schemars(length(min = 1, max = 2000))]
+    pub text: String,
+}
+
+impl ProviderOutput for StepNarrativeOutput {
+    const KIND: &'static str = "step_narrative";
+
+    fn check(&self) -> Vec<SchemaViolation> {
+        self.blocks
+            .iter()
+            .enumerate()
+            .filter(|(_, block)| block.text.trim().is_empty())
+            .map(|(i, _)| violation(&format!("$.blocks[{i}].text"), "must not be blank"))
+            .collect()
+    }
+}
+
+/// Parses `raw` as `T`, collecting every schema and semantic violation.
+pub fn parse<T: ProviderOutput>(raw: &str) -> Result<T, Vec<SchemaViolation>> {
+    let value: Value = serde_json::from_str(raw.trim())
+        .map_err(|e| vec![violation("$", format!("not valid JSON: {e}"))])?;
+    let schema = schemars::schema_for!(T);
+    let violations = validate(&schema, &value);
+    if !violations.is_empty() {
+        return Err(violations);
+    }
+    let parsed: T =
+        serde_json::from_value(value).map_err(|e| vec![violation("$", e.to_string())])?;
+    let violations = parsed.check();
+    if violations.is_empty() {
+        Ok(parsed)
+    } else {
+        Err(violations)
+    }
+}
+
+/// Validates `value` against a draft-07 schema as generated by schemars.
+pub fn validate(schema: &RootSchema, value: &Value) -> Vec<SchemaViolation> {
+    let mut out = Vec::new();
+    Validator { root: schema }.object(&schema.schema, value, "$", &mut out);
+    out
+}
+
+struct Validator<'a> {
+    root: &'a RootSchema,
+}
+
+impl Validator<'_> {
+    fn schema(&self, schema: &Schema, value: &Value, path: &str, out: &mut Vec<SchemaViolation>) {
+        match schema {
+            Schema::Bool(true) => {}
+            Schema::Bool(false) => out.push(violation(path, "no value is allowed here")),
+            Schema::Object(obj) => self.object(obj, value, path, out),
+        }
+    }
+
+    fn object(
+        &self,
+        obj: &SchemaObject,
+        value: &Value,
+        path: &str,
+        out: &mut Vec<SchemaViolation>,
+    ) {
+        if let Some(reference) = &obj.reference {
+            let name = reference.trim_start_matches("#/definitions/");
+            match self.root.definitions.get(name) {
+                Some(target) => self.schema(target, value, path, out),
+                None => out.push(violation(
+                    path,
+                    format!("unresolved schema ref {reference}"),
+                )),
+            }
+        }
+        if let Some(types) = &obj.instance_type {
+            let allowed: &[InstanceType] = match types {
+                SingleOrVec::Single(t) => std::slice::from_ref(t.as_ref()),
+                SingleOrVec::Vec(ts) => ts,
+            };
+            if !allowed.iter().any(|t| type_matches(*t, value)) {
+                let names = allowed
+                    .iter()
+                    .map(|t| format!("{t:?}").to_lowercase())
+                    .collect::<Vec<_>>()
+                    .join(" or ");
+                out.push(violation(
+                    path,
+                    format!("expected {names}, got {}", type_name(value)),
+                ));
+                return;
+            }
+        }
+        if let Some(values) = &obj.enum_values {
+            if !values.contains(value) {
+                let allowed = values
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                out.push(violation(path, format!("{value} is not one of {allowed}")));
+            }
+        }
+        if let Some(expected) = &obj.const_value {
+            if expected != value {
+                out.push(violation(path, format!("expected {expected}")));
+            }
+        }
+        if let Some(format) = &obj.format {
+            check_format(format, value, path, out);
+        }
+        if let Some(sub) = &obj.subschemas {
+            for schema in sub.all_of.iter().flatten() {
+                self.schema(schema, value, path, out);
+            }
+            if let Some(any_of) = &sub.any_of {
+                let matched = any_of.iter().any(|schema| {
+                    let mut scratch = Vec::new();
+                    self.schema(schema, value, path, &mut scratch);
+                    scratch.is_empty()
+                });
+                if !matched {
+                    out.push(violation(path, "matches none of the allowed shapes"));
+                }
+            }
+            if let Some(one_of) = &sub.one_of {
+                let matched = one_of
+                    .iter()
+                    .filter(|schema| {
+                        let mut scratch = Vec::new();
+                        self.schema(schema, value, path, &mut scratch);
+                        scratch.is_empty()
+                    })
+                    .count();
+                if matched != 1 {
+                    out.push(violation(
+                        path,
+                        format!("must match exactly one shape, matched {matched}"),
+                    ));
+                }
+            }
+        }
+        if let (Some(n), Some(num)) = (value.as_f64(), &obj.number) {
+            if num.minimum.is_some_and(|min| n < min)
+                || num.exclusive_minimum.is_some_and(|min| n <= min)
+            {
+                out.push(violation(path, format!("{n} is below the minimum")));
+            }
+            if num.maximum.is_some_and(|max| n > max)
+                || num.exclusive_maximum.is_some_and(|max| n >= max)
+            {
+                out.push(violation(path, format!("{n} is above the maximum")));
+            }
+        }
+        if let (Some(s), Some(string)) = (value.as_str(), &obj.string) {
+            let len = s.chars().count() as u32;
+            if let Some(min) = string.min_length.filter(|min| len < *min) {
+                out.push(violation(
+                    path,
+                    format!("has {len} characters, expected at least {min}"),
+                ));
+            }
+            if let Some(max) = string.max_length.filter(|max| len > *max) {
+                out.push(violation(
+                    path,
+                    format!("has {len} characters, expected at most {max}"),
+                ));
+            }
+            if let Some(pattern) = &string.pattern {
+                match regex::Regex::new(pattern) {
+                    Ok(re) if re.is_match(s) => {}
+                    Ok(_) => out.push(violation(path, format!("does not match {pattern}"))),
+                    Err(e) => out.push(violation(path, format!("bad schema pattern: {e}"))),
+                }
+            }
+        }
+        if let (Some(items), Some(array)) = (value.as_array(), &obj.array) {
+            let len = items.len() as u32;
+            if let Some(min) = array.min_items.filter(|min| len < *min) {
+                out.push(violation(
+                    path,
+                    format!("has {len} items, expected at least {min}"),
+                ));
+            }
+            if let Some(max) = array.max_items.filter(|max| len > *max) {
+                out.push(violation(
+                    path,
+                    format!("has {len} items, expected at most {max}"),
+                ));
+            }
+            match &array.items {
+                Some(SingleOrVec::Single(schema)) => {
+                    for (i, item) in items.iter().enumerate() {
+                        self.schema(schema, item, &format!("{path}[{i}]"), out);
+                    }
+                }
+                Some(SingleOrVec::Vec(schemas)) => {
+                    for (i, (schema, item)) in schemas.iter().zip(items).enumerate() {
+                        self.schema(schema, item, &format!("{path}[{i}]"), out);
+                    }
+                }
+                None => {}
+            }
+        }
+        if let (Some(map), Some(object)) = (value.as_object(), &obj.object) {
+            for key in &object.required {
+                if !map.contains_key(key) {
+                    out.push(violation(&format!("{path}.{key}"), "is required"));
+                }
+            }
+            for (key, item) in map {
+                let item_path = format!("{path}.{key}");
+                match (object.properties.get(key), &object.additional_properties) {
+                    (Some(schema), _) => self.schema(schema, item, &item_path, out),
+                    (None, Some(extra)) => match extra.as_ref() {
+                        Schema::Bool(false) => {
+                            out.push(violation(&item_path, "is not a known field"))
+                        }
+                        schema => self.schema(schema, item, &item_path, out),
+                    },
+                    (None, None) => {}
+                }
+            }
+        }
+    }
+}
+
+fn type_matches(t: InstanceType, value: &Value) -> bool {
+    match t {
+        InstanceType::Null => value.is_null(),
+        InstanceType::Boolean => value.is_boolean(),
+        InstanceType::Object => value.is_object(),
+        InstanceType::Array => value.is_array(),
+        InstanceType::Number => value.is_number(),
+        InstanceType::String => value.is_string(),
+        InstanceType::Integer => value.is_i64() || value.is_u64(),
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Integer widths and uuids carry their bounds in `format` rather than in
+/// `minimum`/`maximum`.
+fn check_format(format: &str, value: &Value, path: &str, out: &mut Vec<SchemaViolation>) {
+    let max = match format {
+        "uint8" => Some(u8::MAX as u64),
+        "uint16" => Some(u16::MAX as u64),
+        "uint32" => Some(u32::MAX as u64),
+        _ => None,
+    };
+    if let (Some(max), Some(n)) = (max, value.as_u64()) {
+        if n > max {
+            out.push(violation(path, format!("{n} does not fit {format}")));
+        }
+    }
+    if format == "uuid" {
+        if let Some(s) = value.as_str() {
+            if uuid::Uuid::parse_str(s).is_err() {
+                out.push(violation(path, format!("{s:?} is not a uuid")));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rejection {
+    /// 1-based attempt number.
+    pub attempt: u32,
+    pub raw: String,
+    pub violations: Vec<SchemaViolation>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProviderOutputError {
+    #[error(transparent)]
+    Generate(anyhow::Error),
+    #[error("{kind} output failed schema validation after {} attempts", rejections.len())]
+    Invalid {
+        kind: &'static str,
+        rejections: Vec<Rejection>,
+    },
+}
+
+/// Asks `generate` for output until it validates as `T`. The first call gets
+/// no hint; each retry gets the previous attempt's violations as a repair
+/// hint. `on_reject` sees every invalid attempt before the next one starts.
+pub fn generate_validated<T: ProviderOutput>(
+    max_attempts: u32,
+    mut generate: impl FnMut(Option<&str>) -> anyhow::Result<String>,
+    mut on_reject: impl FnMut(&Rejection) -> anyhow::Result<()>,
+) -> Result<T, ProviderOutputError> {
+    let mut rejections: Vec<Rejection> = Vec::new();
+    for attempt in 1..=max_attempts.max(1) {
+        let hint = rejections.last().map(|r| repair_hint::<T>(&r.violations));
+        let raw = generate(hint.as_deref()).map_err(ProviderOutputError::Generate)?;
+        match parse::<T>(&raw) {
+            Ok(parsed) => return Ok(parsed),
+            Err(violations) => {
+                let rejection = Rejection {
+                    attempt,
+                    raw,
+                    violations,
+                };
+                on_reject(&rejection).map_err(ProviderOutputError::Generate)?;
+                rejections.push(rejection);
+            }
+        }
+    }
+    Err(ProviderOutputError::Invalid {
+        kind: T::KIND,
+        rejections,
+    })
+}
+
+/// Repair instructions appended to the next prompt: the violations, then the
+/// schema the reply must satisfy.
+pub fn repair_hint<T: ProviderOutput>(violations: &[SchemaViolation]) -> String {
+    let schema = serde_json::to_string(&schemars::schema_for!(T)).unwrap_or_default();
+    let listed = violations
+        .iter()
+        .map(|v| format!("- {v}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "Your previous reply was rejected:\n{listed}\nReply with JSON only, matching this schema:\n{schema}"
+    )
+}
@@ -1,15 +1,361 @@
+use crate::image::{decode_png, encode_png, fill_box};
+use crate::util::canon_json::to_canonical_json;
+use crate::util::hash::blake3_hex;
+use opscinema_export_manifest::ManifestWarning;
+use opscinema_types::{
+    BBoxNorm, EvidenceItem, EvidenceLocatorType, PiiDetector, RedactionRule, Step,
+};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// Event that replaces a session's redaction rules.
+pub const RULES_SET_EVENT: &str = "RedactionRulesSet";
+/// Manifest warning code recording applied redactions.
+pub const REDACTION_WARNING_CODE: &str = "REDACTED";
+const REDACTION_FILL: [u8; 3] = [0, 0, 0];
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
-pub struct RedactionRule {
-    pub pattern: String,
-    pub replacement: String,
+pub struct RedactionRulesSetPayload {
+    pub rules: Vec<RedactionRule>,
+}
+
+/// Rules for sessions that never set their own: every PII detector.
+pub fn default_rules() -> Vec<RedactionRule> {
+    PiiDetector::ALL
+        .into_iter()
+        .map(|detector| RedactionRule::Pii { detector })
+        .collect()
+}
+
+/// Where in a bundle a redaction was applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RedactionScope {
+    StepText,
+    EvidenceText,
+    EvidenceAsset,
+    Keyframe,
+}
+
+impl RedactionScope {
+    fn describe(self) -> &'static str {
+        match self {
+            RedactionScope::StepText => "step text",
+            RedactionScope::EvidenceText => "evidence snippets",
+            RedactionScope::EvidenceAsset => "evidence assets",
+            RedactionScope::Keyframe => "keyframe images",
+        }
+    }
+}
+
+/// Redactions applied while building one bundle, counted by scope and rule
+/// label.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RedactionReport {
+    counts: BTreeMap<(RedactionScope, String), u32>,
+}
+
+impl RedactionReport {
+    fn record(&mut self, scope: RedactionScope, label: &str) {
+        *self.counts.entry((scope, label.to_string())).or_default() += 1;
+    }
+
+    pub fn total(&self) -> u32 {
+        self.counts.values().sum()
+    }
+
+    pub fn count(&self, scope: RedactionScope, label: &str) -> u32 {
+        self.counts
+            .get(&(scope, label.to_string()))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// One warning per scope and label, e.g. `2 email redaction(s) in step text`.
+    pub fn manifest_warnings(&self) -> Vec<ManifestWarning> {
+        self.counts
+            .iter()
+            .map(|((scope, label), count)| ManifestWarning {
+                code: REDACTION_WARNING_CODE.to_string(),
+                message: format!("{count} {label} redaction(s) in {}", scope.describe()),
+            })
+            .collect()
+    }
+}
+
+struct TextMatcher {
+    label: String,
+    regex: Regex,
+    /// Capture group holding the secret; group 0 unless the pattern has to
+    /// consume a boundary character before it.
+    group: usize,
+    validate: fn(&str) -> bool,
+}
+
+struct RegionRule {
+    label: String,
+    bbox_norm: BBoxNorm,
+    frame_ms: Option<i64>,
+}
+
+/// Compiled session rules.
+pub struct Redactor {
+    text: Vec<TextMatcher>,
+    regions: Vec<RegionRule>,
+    rules_hash: String,
+}
+
+impl Redactor {
+    /// Fails on an invalid user regex or one that matches the empty string.
+    pub fn compile(rules: &[RedactionRule]) -> anyhow::Result<Self> {
+        let mut text = Vec::new();
+        let mut regions = Vec::new();
+        for rule in rules {
+            match rule {
+                RedactionRule::Pii { detector } => text.extend(detector_matchers(*detector)?),
+                RedactionRule::Regex { pattern, label } => {
+                    let regex = Regex::new(pattern).map_err(|e| {
+                        anyhow::anyhow!("invalid redaction pattern {pattern:?}: {e}")
+                    })?;
+                    if regex.is_match("") {
+                        anyhow::bail!("redaction pattern {pattern:?} matches empty text");
+                    }
+                    text.push(TextMatcher {
+                        label: label.clone().unwrap_or_else(|| "regex".to_string()),
+                        regex,
+                        group: 0,
+                        validate: |_| true,
+                    });
+                }
+                RedactionRule::Region {
+                    bbox_norm,
+                    frame_ms,
+                    label,
+                } => regions.push(RegionRule {
+                    label: label.clone().unwrap_or_else(|| "region".to_string()),
+                    bbox_norm: bbox_norm.clone(),
+                    frame_ms: *frame_ms,
+                }),
+            }
+        }
+        Ok(Self {
+            text,
+            regions,
+            rules_hash: blake3_hex(to_canonical_json(&rules)?.as_bytes()),
+        })
+    }
+
+    /// Recorded in the manifest policy attestations.
+    pub fn rules_hash(&self) -> &str {
+        &self.rules_hash
+    }
+
+    /// Byte spans to redact, overlapping matches merged; the earliest match
+    /// names the span.
+    fn spans<'a>(&'a self, text: &str) -> Vec<(usize, usize, &'a str)> {
+        let mut found = Vec::new();
+        for matcher in &self.text {
+            for caps in matcher.regex.captures_iter(text) {
+                if let Some(m) = caps.get(matcher.group) {
+                    if (matcher.validate)(m.as_str()) {
+                        found.push((m.start(), m.end(), matcher.label.as_str()));
+                    }
+                }
+            }
+        }
+        found.sort_by_key(|(start, end, _)| (*start, std::cmp::Reverse(*end)));
+        let mut merged: Vec<(usize, usize, &str)> = Vec::new();
+        for (start, end, label) in found {
+            match merged.last_mut() {
+                Some(last) if start < last.1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end, label)),
+            }
+        }
+        merged
+    }
+
+    pub fn matches(&self, text: &str) -> bool {
+        !self.spans(text).is_empty()
+    }
+
+    /// Replaces every match with `[REDACTED:<label>]`.
+    pub fn redact_text(
+        &self,
+        text: &str,
+        scope: RedactionScope,
+        report: &mut RedactionReport,
+    ) -> String {
+        let spans = self.spans(text);
+        if spans.is_empty() {
+            return text.to_string();
+        }
+        let mut out = String::with_capacity(text.len());
+        let mut cursor = 0;
+        for (start, end, label) in spans {
+            out.push_str(&text[cursor..start]);
+            out.push_str(&format!("[REDACTED:{label}]"));
+            report.record(scope, label);
+            cursor = end;
+        }
+        out.push_str(&text[cursor..]);
+        out
+    }
+
+    pub fn redact_steps(&self, steps: &mut [Step], report: &mut RedactionReport) {
+        for step in steps {
+            step.title = self.redact_text(&step.title, RedactionScope::StepText, report);
+            for block in &mut step.body.blocks {
+                block.text = self.redact_text(&block.text, RedactionScope::StepText, report);
+            }
+        }
+    }
+
+    /// Scrubs locator notes, which carry OCR text and window titles.
+    pub fn redact_evidence(&self, items: &mut [EvidenceItem], report: &mut RedactionReport) {
+        for locator in items.iter_mut().flat_map(|item| item.locators.iter_mut()) {
+            if let Some(note) = &locator.note {
+                locator.note = Some(self.redact_text(note, RedactionScope::EvidenceText, report));
+            }
+        }
+    }
+
+    /// Scrubs every string in a JSON asset (OCR blocks, provider output)
+    /// except identifiers.
+    pub fn redact_json(&self, value: &mut serde_json::Value, report: &mut RedactionReport) {
+        match value {
+            serde_json::Value::String(text) => {
+                *text = self.redact_text(text, RedactionScope::EvidenceAsset, report);
+            }
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    self.redact_json(item, report);
+                }
+            }
+            serde_json::Value::Object(map) => {
+                for (key, item) in map.iter_mut() {
+                    if key != "id" && !key.ends_with("_id") {
+                        self.redact_json(item, report);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Boxes to paint over the keyframe captured at `frame_ms`: region rules
+    /// for that frame (or all frames) and every OCR block on it whose text a
+    /// text rule matches.
+    pub fn frame_boxes(&self, frame_ms: i64, evidence: &[EvidenceItem]) -> Vec<(BBoxNorm, String)> {
+        let mut boxes = self
+            .regions
+            .iter()
+            .filter(|r| r.frame_ms.is_none_or(|ms| ms == frame_ms))
+            .map(|r| (r.bbox_norm.clone(), r.label.clone()))
+            .collect::<Vec<_>>();
+        for locator in evidence
+            .iter()
+            .flat_map(|item| item.locators.iter())
+            .filter(|l| {
+                l.locator_type == EvidenceLocatorType::OcrBbox && l.frame_ms == Some(frame_ms)
+            })
+        {
+            let (Some(bbox), Some(note)) = (&locator.bbox_norm, &locator.note) else {
+                continue;
+            };
+            if let Some((_, _, label)) = self.spans(note).first() {
+                boxes.push((bbox.clone(), label.to_string()));
+            }
+        }
+        boxes
+    }
+
+    /// Paints `boxes` black. Bytes come back untouched when there is nothing
+    /// to paint.
+    pub fn redact_png(
+        &self,
+        png_bytes: &[u8],
+        boxes: &[(BBoxNorm, String)],
+        report: &mut RedactionReport,
+    ) -> anyhow::Result<Vec<u8>> {
+        if boxes.is_empty() {
+            return Ok(png_bytes.to_vec());
+        }
+        let mut image = decode_png(png_bytes)?;
+        for (bbox, label) in boxes {
+            fill_box(&mut image, bbox, REDACTION_FILL);
+            report.record(RedactionScope::Keyframe, label);
+        }
+        encode_png(&image, &[])
+    }
+}
+
+fn detector_matchers(detector: PiiDetector) -> anyhow::Result<Vec<TextMatcher>> {
+    let matcher = |pattern: &str, group: usize, validate: fn(&str) -> bool| {
+        Regex::new(pattern).map(|regex| TextMatcher {
+            label: detector.as_str().to_string(),
+            regex,
+            group,
+            validate,
+        })
+    };
+    match detector {
+        PiiDetector::Email => Ok(vec![matcher(
+            r"(?i)\b[a-z0-9._%+-]+@[a-z0-9-]+(?:\.[a-z0-9-]+)*\.[a-z]{2,}\b",
+            0,
+            |_| true,
+        )?]),
+        PiiDetector::Ipv4 => Ok(vec![matcher(r"\b\d{1,3}(?:\.\d{1,3}){3}\b", 0, |m| {
+            m.parse::<Ipv4Addr>().is_ok()
+        })?]),
+        // Group 1 skips the leading boundary character so a candidate never
+        // starts mid-word (`std::fs`); the parse rejects times and the rest.
+        PiiDetector::Ipv6 => Ok(vec![matcher(
+            r"(?i)(?:^|[^0-9a-z_:.])([0-9a-f:]*:[0-9a-f:]*:[0-9a-f:.]*[0-9a-f])",
+            1,
+            |m| m.parse::<Ipv6Addr>().is_ok(),
+        )?]),
+        PiiDetector::AwsKey => Ok(vec![
+            matcher(r"\b(?:AKIA|ASIA|AROA|AIDA)[0-9A-Z]{16}\b", 0, |_| true)?,
+            matcher(
+                r#"(?i)aws_?secret_?access_?key["']?\s*[:=]\s*["']?[A-Za-z0-9/+=]{40}"#,
+                0,
+                |_| true,
+            )?,
+        ]),
+        PiiDetector::Jwt => Ok(vec![matcher(
+            r"\beyJ[A-Za-z0-9_-]{4,}\.eyJ[A-Za-z0-9_-]{4,}\.[A-Za-z0-9_-]*",
+            0,
+            |_| true,
+        )?]),
+        PiiDetector::CreditCard => Ok(vec![matcher(r"\b\d(?:[ -]?\d){12,18}\b", 0, luhn_valid)?]),
+    }
 }
 
-pub fn apply_redactions(input: &str, rules: &[RedactionRule]) -> String {
-    let mut out = input.to_string();
-    for rule in rules {
-        out = out.replace(&rule.pattern, &rule.replacement);
+fn luhn_valid(candidate: &str) -> bool {
+    let digits = candidate
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .collect::<Vec<_>>();
+    if !(13..=19).contains(&digits.len()) {
+        return false;
     }
-    out
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(idx, d)| {
+            if idx % 2 == 1 {
+                let doubled = d * 2;
+                if doubled > 9 {
+                    doubled - 9
+                } else {
+                    doubled
+                }
+            } else {
+                *d
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
 }
@@ -1,4 +1,6 @@
 pub mod derive;
 pub mod edit_ops;
+pub mod narrate;
 pub mod replay;
+pub mod segment;
 pub mod validate;
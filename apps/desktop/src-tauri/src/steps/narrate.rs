@@ -0,0 +1,205 @@
+//! Evidence-constrained narration of generated steps. Each step is described
+//! to the tutorial model through the evidence its blocks already cite (OCR
+//! spans, window titles, clicks); the model answers with blocks that cite
+//! that evidence inline as `[ev:<id>]`. Citations become the blocks'
+//! `evidence_refs`, and a block whose citations do not resolve to the
+//! evidence offered for its step is rejected.
+
+use crate::evidence::coverage::SessionEvidenceRefs;
+use crate::policy::provider_schema::StepNarrativeOutput;
+use opscinema_types::{
+    EvidenceItem, EvidenceSet, Step, StructuredText, TextBlock, TextBlockProvenance,
+};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use uuid::Uuid;
+
+const CITE_OPEN: &str = "[ev:";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptEvidence {
+    pub evidence_id: Uuid,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RejectedBlock {
+    pub step_id: Uuid,
+    /// Position of the block in the model's reply.
+    pub index: usize,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Citations {
+    /// Block text with the citation markers removed.
+    pub text: String,
+    pub refs: Vec<Uuid>,
+    /// Marker contents that are not evidence ids.
+    pub unparsed: Vec<String>,
+}
+
+/// The describable evidence `step` cites, in citation order. Refs that do not
+/// resolve in the session (dangling, foreign or invalidated) are left out so
+/// the model is never offered them.
+pub fn prompt_evidence(
+    step: &Step,
+    evidence: &EvidenceSet,
+    refs: &SessionEvidenceRefs,
+) -> Vec<PromptEvidence> {
+    let mut seen = BTreeSet::new();
+    step.body
+        .blocks
+        .iter()
+        .flat_map(|b| b.evidence_refs.iter())
+        .filter(|id| refs.resolves(id) && seen.insert(**id))
+        .filter_map(|id| evidence.evidence.iter().find(|e| &e.evidence_id == id))
+        .filter_map(|item| {
+            Some(PromptEvidence {
+                evidence_id: item.evidence_id,
+                description: describe(item)?,
+            })
+        })
+        .collect()
+}
+
+/// How an evidence item is shown to the model; `None` for kinds the model
+/// cannot read (frames, verifier output, anchors).
+pub fn describe(item: &EvidenceItem) -> Option<String> {
+    let locator = item.locators.first()?;
+    let at = locator
+        .frame_ms
+        .map(|ms| format!(" at {ms} ms"))
+        .unwrap_or_default();
+    let description = match item.kind.as_str() {
+        "OcrSpan" => {
+            let text = locator.note.as_deref().unwrap_or_default().trim();
+            (!text.is_empty()).then(|| format!("on-screen text \"{text}\""))?
+        }
+        "WindowMeta" => {
+            let note = locator.note.as_deref().unwrap_or_default();
+            let (bundle, title) = note.split_once(':').unwrap_or((note, ""));
+            match (title.trim(), bundle.trim()) {
+                ("", "") => return None,
+                ("", app) | (app, "") => format!("frontmost window {app}{at}"),
+                (title, bundle) => format!("frontmost window \"{title}\" ({bundle}){at}"),
+            }
+        }
+        "Click" => match &locator.bbox_norm {
+            Some(pos) => format!(
+                "click at {}% across, {}% down{at}",
+                pos.x / 100,
+                pos.y / 100
+            ),
+            None => format!("click{at}"),
+        },
+        _ => return None,
+    };
+    Some(description)
+}
+
+pub fn build_prompt(step: &Step, evidence: &[PromptEvidence]) -> String {
+    let current = step
+        .body
+        .blocks
+        .iter()
+        .map(|b| format!("- {}", b.text))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let listed = evidence
+        .iter()
+        .map(|e| format!("{CITE_OPEN}{}] {}", e.evidence_id, e.description))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "You are writing one step of a software tutorial from a screen recording.\n\
+         Step {}: {}\n\
+         Draft:\n{current}\n\
+         Evidence recorded for this step:\n{listed}\n\
+         Rewrite the step as 1 to 8 short instructions for the reader. Each \
+         instruction must cite the evidence it relies on inline as \
+         {CITE_OPEN}<evidence id>], using only the ids listed above. Quote \
+         on-screen text exactly as recorded and do not name anything the \
+         evidence does not show.\n\
+         Reply with JSON only: {{\"blocks\":[{{\"text\":\"...\"}}]}}",
+        step.order_index + 1,
+        step.title,
+    )
+}
+
+/// Splits the `[ev:<id>]` markers out of `text`. Only the one space that
+/// separated a marker from its surroundings goes with it; everything else,
+/// including commands, indentation and newlines, is kept byte for byte.
+pub fn parse_citations(text: &str) -> Citations {
+    let mut out = Citations::default();
+    let mut stripped = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(CITE_OPEN) {
+        let Some(len) = rest[start..].find(']') else {
+            break;
+        };
+        stripped.push_str(&rest[..start]);
+        let token = rest[start + CITE_OPEN.len()..start + len].trim();
+        match Uuid::parse_str(token) {
+            Ok(id) if !out.refs.contains(&id) => out.refs.push(id),
+            Ok(_) => {}
+            Err(_) => out.unparsed.push(token.to_string()),
+        }
+        rest = &rest[start + len + 1..];
+        let next = rest.chars().next();
+        let closes_gap = next.is_none_or(|c| c.is_whitespace() || ",.;:!?)".contains(c));
+        if stripped.ends_with(' ') && closes_gap {
+            stripped.pop();
+        } else if (stripped.is_empty() || stripped.ends_with('\n')) && next == Some(' ') {
+            rest = &rest[1..];
+        }
+    }
+    stripped.push_str(rest);
+    out.text = stripped;
+    out
+}
+
+/// Turns a validated narrative into a step body. Blocks keep only the
+/// citations they make; a block citing nothing, citing evidence outside
+/// `offered`, or empty once its markers are removed is rejected.
+pub fn narrated_body(
+    step_id: Uuid,
+    output: &StepNarrativeOutput,
+    offered: &BTreeSet<Uuid>,
+) -> (StructuredText, Vec<RejectedBlock>) {
+    let mut blocks = Vec::new();
+    let mut rejected = Vec::new();
+    for (index, block) in output.blocks.iter().enumerate() {
+        let cited = parse_citations(&block.text);
+        let unresolved = cited
+            .refs
+            .iter()
+            .filter(|id| !offered.contains(id))
+            .map(ToString::to_string)
+            .chain(cited.unparsed.iter().cloned())
+            .collect::<Vec<_>>();
+        let reason = if !unresolved.is_empty() {
+            Some(format!("unresolved citations: {}", unresolved.join(",")))
+        } else if cited.refs.is_empty() {
+            Some("cites no evidence".to_string())
+        } else if cited.text.trim().is_empty() {
+            Some("no text besides citations".to_string())
+        } else {
+            None
+        };
+        match reason {
+            Some(reason) => rejected.push(RejectedBlock {
+                step_id,
+                index,
+                reason,
+            }),
+            None => blocks.push(TextBlock {
+                block_id: format!("b{}", blocks.len() + 1),
+                text: cited.text,
+                provenance: TextBlockProvenance::Generated,
+                evidence_refs: cited.refs,
+            }),
+        }
+    }
+    (StructuredText { blocks }, rejected)
+}
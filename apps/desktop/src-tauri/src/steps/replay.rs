@@ -1,7 +1,9 @@
 use crate::steps::derive::StepsCandidatesGeneratedPayload;
 use crate::steps::edit_ops::apply_edit;
+use crate::storage::event_store::{self, EventFilter, EventRow};
+use crate::storage::repo_snapshots::{self, SnapshotKind};
 use opscinema_types::{Step, StepEditOp};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 pub fn replay_from_event_payloads(initial: &str, edits: &[String]) -> anyhow::Result<Vec<Step>> {
@@ -19,33 +21,77 @@ struct StepEditAppliedPayload {
     op: StepEditOp,
 }
 
-pub fn replay_session_steps(
-    conn: &crate::storage::DbConn,
-    session_id: Uuid,
-) -> anyhow::Result<Vec<Step>> {
-    let events = crate::storage::event_store::query_events(conn, session_id, None, 100_000)?;
-    let mut steps = Vec::new();
-    let mut has_initial = false;
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StepsReplayState {
+    has_initial: bool,
+    steps: Vec<Step>,
+}
 
-    for row in events {
+impl StepsReplayState {
+    fn apply(&mut self, row: &EventRow) -> anyhow::Result<()> {
         match row.event_type.as_str() {
             "StepsCandidatesGenerated" => {
                 let payload: StepsCandidatesGeneratedPayload =
                     serde_json::from_str(&row.payload_canon_json)?;
-                steps = payload.steps;
-                has_initial = true;
+                self.steps = payload.steps;
+                self.has_initial = true;
             }
             "StepEditApplied" => {
-                if !has_initial {
-                    continue;
+                if !self.has_initial {
+                    return Ok(());
                 }
                 let payload: StepEditAppliedPayload =
                     serde_json::from_str(&row.payload_canon_json)?;
-                apply_edit(&mut steps, &payload.op)?;
+                apply_edit(&mut self.steps, &payload.op)?;
             }
             _ => {}
         }
+        Ok(())
     }
+}
 
-    Ok(steps)
+/// Replays steps from the latest valid checkpoint, applying only events after
+/// it, and advances the checkpoint to the last replayed event.
+pub fn replay_session_steps(
+    conn: &crate::storage::DbConn,
+    session_id: Uuid,
+) -> anyhow::Result<Vec<Step>> {
+    let (mut state, from_seq) =
+        match repo_snapshots::load_checkpoint(conn, SnapshotKind::Steps, session_id)? {
+            Some(checkpoint) => (checkpoint.state, checkpoint.head_seq),
+            None => (StepsReplayState::default(), 0),
+        };
+    let mut last = None;
+    for row in event_store::stream_events(conn, session_id, replay_filter().after(from_seq)) {
+        let row = row?;
+        state.apply(&row)?;
+        last = Some(row);
+    }
+    if let Some(last) = last {
+        repo_snapshots::save_checkpoint(
+            conn,
+            SnapshotKind::Steps,
+            session_id,
+            last.seq,
+            &last.event_hash,
+            &state,
+        )?;
+    }
+    Ok(state.steps)
+}
+
+/// Replays steps from the first event without reading or writing checkpoints.
+pub fn rebuild_session_steps(
+    conn: &crate::storage::DbConn,
+    session_id: Uuid,
+) -> anyhow::Result<Vec<Step>> {
+    let mut state = StepsReplayState::default();
+    for row in event_store::stream_events(conn, session_id, replay_filter()) {
+        state.apply(&row?)?;
+    }
+    Ok(state.steps)
+}
+
+fn replay_filter() -> EventFilter {
+    EventFilter::types(&["StepsCandidatesGenerated", "StepEditApplied"])
 }
@@ -0,0 +1,347 @@
+use opscinema_types::{
+    BBoxNorm, EvidenceItem, EvidenceSet, Step, StructuredText, TextBlock, TextBlockProvenance,
+};
+use std::collections::{BTreeMap, BTreeSet};
+use uuid::Uuid;
+
+/// dHash bits that must flip between consecutive decodable keyframes before
+/// the screen counts as changed.
+pub const FRAME_CHANGE_BITS: u32 = 12;
+/// Consecutive OCR frames sharing less than this percentage of their
+/// (normalized) lines are treated as different screens.
+pub const OCR_TEXT_OVERLAP_MIN_PCT: usize = 50;
+const SNIPPET_CHARS: usize = 72;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentBoundary {
+    SessionStart,
+    FrameChange,
+    AfterClick,
+    WindowChange,
+    OcrTextChange,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OcrSpanRef {
+    pub evidence_id: Uuid,
+    pub text: String,
+    pub bbox_norm: Option<BBoxNorm>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClickRef {
+    pub evidence_id: Uuid,
+    pub pos: Option<BBoxNorm>,
+}
+
+/// A stretch of the session showing one screen state, ended by the click
+/// that acts on it (if any).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    pub boundary: SegmentBoundary,
+    pub start_ms: i64,
+    pub frames: Vec<Uuid>,
+    pub clicks: Vec<ClickRef>,
+    /// Frontmost window in effect, as `(evidence_id, bundle_id, title)`.
+    pub window: Option<(Uuid, String, String)>,
+    /// Spans of the latest OCR frame inside the segment.
+    pub ocr: Vec<OcrSpanRef>,
+}
+
+enum Signal<'a> {
+    Window(&'a EvidenceItem, String, String),
+    Frame(&'a EvidenceItem, Option<u64>),
+    Ocr(Vec<OcrSpanRef>),
+    Click(ClickRef),
+}
+
+impl Signal<'_> {
+    /// Same-instant order: the window switch opens a segment, the frame and
+    /// its text are observed, then the click acts on them.
+    fn rank(&self) -> u8 {
+        match self {
+            Signal::Window(..) => 0,
+            Signal::Frame(..) => 1,
+            Signal::Ocr(..) => 2,
+            Signal::Click(..) => 3,
+        }
+    }
+}
+
+/// Splits the session timeline into segments on keyframe dHash jumps, clicks,
+/// frontmost window changes and OCR text shifts. `frame_hashes` maps keyframe
+/// asset ids to their dHash; frames without one never trigger a boundary.
+pub fn segment(evidence: &EvidenceSet, frame_hashes: &BTreeMap<String, u64>) -> Vec<Segment> {
+    let mut signals: Vec<(i64, Signal<'_>)> = Vec::new();
+    let mut ocr_frames: BTreeMap<i64, Vec<OcrSpanRef>> = BTreeMap::new();
+    for item in &evidence.evidence {
+        let Some(locator) = item.locators.first() else {
+            continue;
+        };
+        let Some(frame_ms) = locator.frame_ms else {
+            continue;
+        };
+        match item.kind.as_str() {
+            "FrameKeyframe" => {
+                let hash = locator
+                    .asset_id
+                    .as_ref()
+                    .and_then(|id| frame_hashes.get(id))
+                    .copied();
+                signals.push((frame_ms, Signal::Frame(item, hash)));
+            }
+            "Click" => signals.push((
+                frame_ms,
+                Signal::Click(ClickRef {
+                    evidence_id: item.evidence_id,
+                    pos: locator.bbox_norm.clone(),
+                }),
+            )),
+            "WindowMeta" => {
+                let note = locator.note.clone().unwrap_or_default();
+                let (bundle, title) = note.split_once(':').unwrap_or((note.as_str(), ""));
+                signals.push((
+                    frame_ms,
+                    Signal::Window(item, bundle.to_string(), title.to_string()),
+                ));
+            }
+            "OcrSpan" => ocr_frames.entry(frame_ms).or_default().push(OcrSpanRef {
+                evidence_id: item.evidence_id,
+                text: locator.note.clone().unwrap_or_default(),
+                bbox_norm: locator.bbox_norm.clone(),
+            }),
+            _ => {}
+        }
+    }
+    signals.extend(
+        ocr_frames
+            .into_iter()
+            .map(|(frame_ms, spans)| (frame_ms, Signal::Ocr(spans))),
+    );
+    signals.sort_by_key(|(frame_ms, signal)| (*frame_ms, signal.rank()));
+
+    let mut segments: Vec<Segment> = Vec::new();
+    let mut current: Option<Segment> = None;
+    let mut window: Option<(Uuid, String, String)> = None;
+    let mut last_hash: Option<u64> = None;
+    let mut last_lines: Option<BTreeSet<String>> = None;
+    let mut next_boundary = SegmentBoundary::SessionStart;
+    let open = |boundary, start_ms, window: &Option<(Uuid, String, String)>| Segment {
+        boundary,
+        start_ms,
+        frames: vec![],
+        clicks: vec![],
+        window: window.clone(),
+        ocr: vec![],
+    };
+
+    for (frame_ms, signal) in signals {
+        let boundary = match &signal {
+            Signal::Window(_, bundle, title) => window
+                .as_ref()
+                .is_some_and(|(_, b, t)| b != bundle || t != title)
+                .then_some(SegmentBoundary::WindowChange),
+            Signal::Frame(_, Some(hash)) => last_hash
+                .is_some_and(|prev| (prev ^ hash).count_ones() > FRAME_CHANGE_BITS)
+                .then_some(SegmentBoundary::FrameChange),
+            Signal::Ocr(spans) => {
+                let lines = normalized_lines(spans);
+                last_lines
+                    .as_ref()
+                    .is_some_and(|prev| !texts_overlap(prev, &lines))
+                    .then_some(SegmentBoundary::OcrTextChange)
+            }
+            Signal::Frame(_, None) | Signal::Click(_) => None,
+        };
+        let same_instant = current.as_ref().is_some_and(|c| c.start_ms == frame_ms);
+        if let (Some(boundary), false) = (boundary, same_instant) {
+            // Frames that only show the screen settling after a click lead
+            // into the next step rather than forming one of their own.
+            let lead_in = match current.take() {
+                Some(prev)
+                    if prev.boundary == SegmentBoundary::AfterClick
+                        && prev.clicks.is_empty()
+                        && prev.ocr.is_empty() =>
+                {
+                    prev.frames
+                }
+                prev => {
+                    segments.extend(prev);
+                    vec![]
+                }
+            };
+            let mut next = open(boundary, frame_ms, &window);
+            next.frames = lead_in;
+            current = Some(next);
+        }
+        let seg = current.get_or_insert_with(|| open(next_boundary, frame_ms, &window));
+        match signal {
+            Signal::Window(item, bundle, title) => {
+                window = Some((item.evidence_id, bundle, title));
+                seg.window = window.clone();
+            }
+            Signal::Frame(item, hash) => {
+                seg.frames.push(item.evidence_id);
+                if hash.is_some() {
+                    last_hash = hash;
+                }
+            }
+            Signal::Ocr(spans) => {
+                last_lines = Some(normalized_lines(&spans));
+                seg.ocr = spans;
+            }
+            Signal::Click(click) => {
+                seg.clicks.push(click);
+                segments.extend(current.take());
+                next_boundary = SegmentBoundary::AfterClick;
+            }
+        }
+    }
+    segments.extend(current);
+    segments
+}
+
+/// One generated step per segment. Every block cites the evidence it was
+/// written from: the action block the frames, clicks and window, the text
+/// block the OCR spans.
+pub fn candidate_steps(segments: &[Segment], step_id_for: impl Fn(usize) -> Uuid) -> Vec<Step> {
+    segments
+        .iter()
+        .enumerate()
+        .map(|(idx, seg)| {
+            let app = seg.window.as_ref().and_then(|(_, bundle, title)| {
+                [title, bundle]
+                    .into_iter()
+                    .find(|v| !v.trim().is_empty())
+                    .map(|v| v.trim().to_string())
+            });
+            let in_app = app.as_ref().map(|a| format!(" in {a}")).unwrap_or_default();
+            let click = seg.clicks.last();
+            let target = click.and_then(|c| click_target(c, &seg.ocr));
+            let (title, action) = match (click, &target, seg.boundary) {
+                (Some(_), Some(span), _) => {
+                    let label = snippet(&span.text);
+                    (
+                        format!("Click \"{label}\""),
+                        format!("Click \"{label}\"{in_app}."),
+                    )
+                }
+                (Some(_), None, _) => (
+                    app.as_ref()
+                        .map(|a| format!("Click in {a}"))
+                        .unwrap_or_else(|| "Click on screen".to_string()),
+                    format!("Click at the highlighted position{in_app}."),
+                ),
+                (None, _, SegmentBoundary::WindowChange) => {
+                    let app = app.clone().unwrap_or_else(|| "the next window".to_string());
+                    (
+                        format!("Switch to {app}"),
+                        format!("Bring {app} to the front."),
+                    )
+                }
+                (None, _, _) if !seg.ocr.is_empty() => (
+                    "Open screen and verify key text".to_string(),
+                    format!("Open the target screen{in_app}."),
+                ),
+                (None, _, _) => (
+                    "Open target screen".to_string(),
+                    format!("Navigate to the target screen{in_app}"),
+                ),
+            };
+
+            let mut action_refs = seg.frames.clone();
+            action_refs.extend(seg.clicks.iter().map(|c| c.evidence_id));
+            action_refs.extend(target.iter().map(|span| span.evidence_id));
+            action_refs.extend(seg.window.iter().map(|(id, _, _)| *id));
+            let mut blocks = vec![generated_block("b1", action, action_refs)];
+
+            let mut text = String::new();
+            let mut text_refs = Vec::new();
+            for span in &seg.ocr {
+                if text.chars().count() >= SNIPPET_CHARS {
+                    break;
+                }
+                let trimmed = span.text.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                if !text.is_empty() {
+                    text.push(' ');
+                }
+                text.push_str(trimmed);
+                text_refs.push(span.evidence_id);
+            }
+            if !text_refs.is_empty() {
+                blocks.push(generated_block(
+                    "b2",
+                    format!(
+                        "Verify the visible text contains: \"{}\"",
+                        text.chars().take(SNIPPET_CHARS).collect::<String>()
+                    ),
+                    text_refs,
+                ));
+            }
+            blocks.retain(|b| !b.evidence_refs.is_empty());
+
+            Step {
+                step_id: step_id_for(idx),
+                order_index: idx as u32,
+                title,
+                body: StructuredText { blocks },
+                risk_tags: vec![],
+                branch_label: None,
+            }
+        })
+        .collect()
+}
+
+fn generated_block(block_id: &str, text: String, refs: Vec<Uuid>) -> TextBlock {
+    let mut seen = BTreeSet::new();
+    TextBlock {
+        block_id: block_id.to_string(),
+        text,
+        provenance: TextBlockProvenance::Generated,
+        evidence_refs: refs.into_iter().filter(|id| seen.insert(*id)).collect(),
+    }
+}
+
+/// The OCR span under the click, smallest first when boxes nest.
+fn click_target<'a>(click: &ClickRef, spans: &'a [OcrSpanRef]) -> Option<&'a OcrSpanRef> {
+    let pos = click.pos.as_ref()?;
+    spans
+        .iter()
+        .filter(|span| !span.text.trim().is_empty())
+        .filter(|span| {
+            span.bbox_norm.as_ref().is_some_and(|b| {
+                pos.x >= b.x && pos.x <= b.x + b.w && pos.y >= b.y && pos.y <= b.y + b.h
+            })
+        })
+        .min_by_key(|span| {
+            span.bbox_norm
+                .as_ref()
+                .map(|b| u64::from(b.w) * u64::from(b.h))
+        })
+}
+
+fn snippet(text: &str) -> String {
+    text.trim().chars().take(SNIPPET_CHARS).collect()
+}
+
+fn normalized_lines(spans: &[OcrSpanRef]) -> BTreeSet<String> {
+    spans
+        .iter()
+        .map(|s| {
+            s.text
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .to_lowercase()
+        })
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn texts_overlap(a: &BTreeSet<String>, b: &BTreeSet<String>) -> bool {
+    let union = a.union(b).count();
+    union == 0 || a.intersection(b).count() * 100 >= union * OCR_TEXT_OVERLAP_MIN_PCT
+}
@@ -1,15 +1,30 @@
-use crate::evidence::coverage;
-use opscinema_types::{StepModel, StepsValidateExportResponse};
+use crate::evidence::coverage::{self, SessionEvidenceRefs};
+use opscinema_types::{EvidenceRefIssueKind, StepModel, StepsValidateExportResponse};
 
-pub fn validate_for_export(model: &StepModel) -> StepsValidateExportResponse {
+pub fn validate_for_export(
+    model: &StepModel,
+    refs: &SessionEvidenceRefs,
+) -> StepsValidateExportResponse {
     let mut errors = Vec::new();
     if model.schema_version != 1 {
         errors.push("unsupported schema_version".to_string());
     }
-    let coverage = coverage::evaluate(&model.steps);
-    if !coverage.pass {
+    let coverage = coverage::evaluate(&model.steps, refs);
+    if !coverage.missing_generated_block_ids.is_empty() {
         errors.push("evidence coverage failed".to_string());
     }
+    for issue in &coverage.invalid_refs {
+        errors.push(format!(
+            "evidence ref {} on block {} is {}",
+            issue.evidence_id,
+            issue.block_id,
+            match issue.kind {
+                EvidenceRefIssueKind::Dangling => "dangling",
+                EvidenceRefIssueKind::OtherSession => "from another session",
+                EvidenceRefIssueKind::Invalidated => "invalidated",
+            }
+        ));
+    }
 
     StepsValidateExportResponse {
         schema_valid: model.schema_version == 1,
@@ -1,3 +1,4 @@
+use crate::storage::migrations;
 use rusqlite::Connection;
 use std::path::{Path, PathBuf};
 
@@ -8,7 +9,7 @@ pub struct Storage {
 }
 
 impl Storage {
-    pub fn open<P: AsRef<Path>>(db_path: P, assets_root: P) -> rusqlite::Result<Self> {
+    pub fn open<P: AsRef<Path>>(db_path: P, assets_root: P) -> anyhow::Result<Self> {
         let db_path = db_path.as_ref().to_path_buf();
         let assets_root = assets_root.as_ref().to_path_buf();
         if let Some(parent) = db_path.parent() {
@@ -16,15 +17,15 @@ impl Storage {
         }
         std::fs::create_dir_all(&assets_root).ok();
 
-        let conn = Connection::open(&db_path)?;
-        Self::migrate(&conn)?;
+        let mut conn = Connection::open(&db_path)?;
+        migrations::migrate(&mut conn)?;
         Ok(Self {
             db_path,
             assets_root,
         })
     }
 
-    pub fn open_in_memory() -> rusqlite::Result<Self> {
+    pub fn open_in_memory() -> anyhow::Result<Self> {
         let tmp = tempfile_dir();
         let db_path = tmp.join("state.sqlite");
         let assets_root = tmp.join("assets");
@@ -34,11 +35,6 @@ impl Storage {
     pub fn conn(&self) -> rusqlite::Result<Connection> {
         Connection::open(&self.db_path)
     }
-
-    fn migrate(conn: &Connection) -> rusqlite::Result<()> {
-        let sql = include_str!("schema/0001_init.sql");
-        conn.execute_batch(sql)
-    }
 }
 
 fn tempfile_dir() -> PathBuf {
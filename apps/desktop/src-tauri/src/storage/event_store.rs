@@ -3,9 +3,13 @@ use crate::util::hash::blake3_hex;
 use crate::util::time::now_utc_iso;
 use anyhow::Context;
 use rusqlite::{params, OptionalExtension};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use uuid::Uuid;
 
+/// Rows fetched per round trip by [`EventCursor`].
+pub const EVENT_PAGE_SIZE: u32 = 1_000;
+
 #[derive(Debug, Clone)]
 pub struct EventRow {
     pub session_id: String,
@@ -40,10 +44,20 @@ pub fn append_event<T: Serialize>(
         .unwrap_or((0, String::new()));
 
     let seq = head_seq + 1;
-    let event_id = Uuid::new_v4();
+    let deterministic = std::env::var("OPSCINEMA_DETERMINISTIC_IDS")
+        .map(|v| v == "1")
+        .unwrap_or(false);
+    let event_id = if deterministic {
+        Uuid::new_v5(
+            &Uuid::NAMESPACE_URL,
+            format!("{session_id}:event:{seq}").as_bytes(),
+        )
+    } else {
+        Uuid::new_v4()
+    };
     let payload_canon_json = to_canonical_json(payload).context("canonicalize payload")?;
     let prev = if head_hash.is_empty() {
-        "GENESIS".to_string()
+        genesis_hash(session_id)
     } else {
         head_hash.clone()
     };
@@ -62,7 +76,7 @@ pub fn append_event<T: Serialize>(
             event_id.to_string(),
             event_type,
             payload_canon_json,
-            if head_hash.is_empty() { None::<String> } else { Some(head_hash.clone()) },
+            prev,
             event_hash,
             now_utc_iso(),
         ],
@@ -114,62 +128,288 @@ pub fn query_events(
     Ok(rows)
 }
 
-pub fn validate_hash_chain(conn: &rusqlite::Connection, session_id: Uuid) -> anyhow::Result<()> {
-    let mut stmt = conn.prepare(
-        "SELECT seq, event_type, payload_canon_json, prev_event_hash, event_hash
-         FROM events
-         WHERE session_id=?1
-         ORDER BY seq ASC",
-    )?;
-    let rows = stmt.query_map(params![session_id.to_string()], |r| {
-        Ok((
-            r.get::<_, i64>(0)?,
-            r.get::<_, String>(1)?,
-            r.get::<_, String>(2)?,
-            r.get::<_, Option<String>>(3)?,
-            r.get::<_, String>(4)?,
-        ))
-    })?;
-
-    let mut expected_prev: Option<String> = None;
-    let mut last_seq = 0_i64;
-    let mut last_hash = String::new();
-    for row in rows {
-        let (seq, event_type, payload_canon_json, prev_event_hash, event_hash) = row?;
-        let prev = expected_prev
-            .clone()
-            .unwrap_or_else(|| "GENESIS".to_string());
+/// Restricts an event stream to a seq window and, optionally, a set of event types.
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    pub event_types: Vec<&'static str>,
+    pub after_seq: Option<i64>,
+    pub through_seq: Option<i64>,
+}
+
+impl EventFilter {
+    pub fn types(event_types: &[&'static str]) -> Self {
+        Self {
+            event_types: event_types.to_vec(),
+            ..Self::default()
+        }
+    }
+
+    pub fn after(mut self, seq: i64) -> Self {
+        self.after_seq = Some(seq);
+        self
+    }
+
+    pub fn through(mut self, seq: i64) -> Self {
+        self.through_seq = Some(seq);
+        self
+    }
+}
+
+/// Streams a session's events in seq order, fetching one keyset page at a time
+/// so callers never hold the whole log in memory.
+pub struct EventCursor<'c> {
+    conn: &'c rusqlite::Connection,
+    session_id: String,
+    filter: EventFilter,
+    page_size: u32,
+    next_after: i64,
+    buffer: VecDeque<EventRow>,
+    exhausted: bool,
+}
+
+impl EventCursor<'_> {
+    fn fetch_page(&mut self) -> anyhow::Result<()> {
+        let mut sql = String::from(
+            "SELECT session_id, seq, event_id, event_type, payload_canon_json, event_hash
+             FROM events
+             WHERE session_id=?1 AND seq>?2 AND seq<=?3",
+        );
+        if !self.filter.event_types.is_empty() {
+            let placeholders = (0..self.filter.event_types.len())
+                .map(|i| format!("?{}", i + 5))
+                .collect::<Vec<_>>()
+                .join(",");
+            sql.push_str(&format!(" AND event_type IN ({placeholders})"));
+        }
+        sql.push_str(" ORDER BY seq ASC LIMIT ?4");
+
+        let mut values: Vec<rusqlite::types::Value> = vec![
+            self.session_id.clone().into(),
+            self.next_after.into(),
+            self.filter.through_seq.unwrap_or(i64::MAX).into(),
+            i64::from(self.page_size).into(),
+        ];
+        values.extend(
+            self.filter
+                .event_types
+                .iter()
+                .map(|t| rusqlite::types::Value::from(t.to_string())),
+        );
+
+        let mut stmt = self.conn.prepare_cached(&sql)?;
+        let rows = stmt
+            .query_map(rusqlite::params_from_iter(values), |r| {
+                Ok(EventRow {
+                    session_id: r.get(0)?,
+                    seq: r.get(1)?,
+                    event_id: r.get(2)?,
+                    event_type: r.get(3)?,
+                    payload_canon_json: r.get(4)?,
+                    event_hash: r.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        if rows.len() < self.page_size as usize {
+            self.exhausted = true;
+        }
+        if let Some(last) = rows.last() {
+            self.next_after = last.seq;
+        }
+        self.buffer.extend(rows);
+        Ok(())
+    }
+}
+
+impl Iterator for EventCursor<'_> {
+    type Item = anyhow::Result<EventRow>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.is_empty() && !self.exhausted {
+            if let Err(err) = self.fetch_page() {
+                self.exhausted = true;
+                return Some(Err(err));
+            }
+        }
+        self.buffer.pop_front().map(Ok)
+    }
+}
+
+pub fn stream_events(
+    conn: &rusqlite::Connection,
+    session_id: Uuid,
+    filter: EventFilter,
+) -> EventCursor<'_> {
+    stream_events_paged(conn, session_id, filter, EVENT_PAGE_SIZE)
+}
+
+pub fn stream_events_paged(
+    conn: &rusqlite::Connection,
+    session_id: Uuid,
+    filter: EventFilter,
+    page_size: u32,
+) -> EventCursor<'_> {
+    EventCursor {
+        conn,
+        session_id: session_id.to_string(),
+        next_after: filter.after_seq.unwrap_or(0),
+        filter,
+        page_size: page_size.max(1),
+        buffer: VecDeque::new(),
+        exhausted: false,
+    }
+}
+
+/// An event row exactly as persisted, including its chain links.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredEvent {
+    pub seq: i64,
+    pub event_id: String,
+    pub event_type: String,
+    pub payload_canon_json: String,
+    pub prev_event_hash: Option<String>,
+    pub event_hash: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainHead {
+    pub seq: i64,
+    pub hash: String,
+}
+
+/// The `prev_event_hash` of a session's first event.
+pub fn genesis_hash(session_id: Uuid) -> String {
+    blake3_hex(format!("{session_id}\nGENESIS\n").as_bytes())
+}
+
+/// The head hash `create_session` gave new sessions before [`genesis_hash`];
+/// first events of those sessions link to it.
+pub fn legacy_genesis_hash(session_id: Uuid, label: &str) -> String {
+    blake3_hex(format!("{session_id}:{label}").as_bytes())
+}
+
+/// Recomputes every event hash in seq order and checks each link. The first
+/// event must link to the session's [`genesis_hash`] or, for sessions created
+/// before it, to [`legacy_genesis_hash`], so a chain cut at the front does not
+/// verify. A first event appended to a session without a head hash carries no
+/// `prev_event_hash` and is hashed against the `GENESIS` marker.
+pub fn verify_chain<I>(session_id: Uuid, label: &str, events: I) -> anyhow::Result<ChainHead>
+where
+    I: IntoIterator<Item = anyhow::Result<StoredEvent>>,
+{
+    let mut head = ChainHead {
+        seq: 0,
+        hash: String::new(),
+    };
+    for event in events {
+        let event = event?;
+        let seq = event.seq;
+        if seq != head.seq + 1 {
+            anyhow::bail!("event seq gap at seq {seq}")
+        }
+        let prev = if head.seq == 0 {
+            match event.prev_event_hash.clone() {
+                None => "GENESIS".to_string(),
+                Some(prev)
+                    if prev == genesis_hash(session_id)
+                        || prev == legacy_genesis_hash(session_id, label) =>
+                {
+                    prev
+                }
+                Some(_) => anyhow::bail!("first event does not link to the session genesis"),
+            }
+        } else {
+            if event.prev_event_hash.as_deref() != Some(head.hash.as_str()) {
+                anyhow::bail!("prev_event_hash mismatch at seq {seq}")
+            }
+            head.hash.clone()
+        };
         let hash_input = format!(
             "{}\n{}\n{}\n{}\n{}\n",
-            session_id, seq, event_type, payload_canon_json, prev
+            session_id, seq, event.event_type, event.payload_canon_json, prev
         );
-        let recomputed = blake3_hex(hash_input.as_bytes());
-        if recomputed != event_hash {
+        if blake3_hex(hash_input.as_bytes()) != event.event_hash {
             anyhow::bail!("event hash mismatch at seq {seq}")
         }
+        head = ChainHead {
+            seq,
+            hash: event.event_hash,
+        };
+    }
+    Ok(head)
+}
 
-        if expected_prev.is_none() {
-            if prev_event_hash.is_some() {
-                anyhow::bail!("genesis event has unexpected prev hash")
-            }
-        } else if prev_event_hash != expected_prev {
-            anyhow::bail!("prev_event_hash mismatch at seq {seq}")
-        }
+const STORED_EVENTS_SQL: &str =
+    "SELECT seq, event_id, event_type, payload_canon_json, prev_event_hash, event_hash, created_at
+     FROM events
+     WHERE session_id=?1
+     ORDER BY seq ASC";
+
+fn stored_event_from_row(r: &rusqlite::Row<'_>) -> rusqlite::Result<StoredEvent> {
+    Ok(StoredEvent {
+        seq: r.get(0)?,
+        event_id: r.get(1)?,
+        event_type: r.get(2)?,
+        payload_canon_json: r.get(3)?,
+        prev_event_hash: r.get(4)?,
+        event_hash: r.get(5)?,
+        created_at: r.get(6)?,
+    })
+}
 
-        expected_prev = Some(event_hash.clone());
-        last_seq = seq;
-        last_hash = event_hash;
+pub fn for_each_stored_event(
+    conn: &rusqlite::Connection,
+    session_id: Uuid,
+    mut f: impl FnMut(StoredEvent) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let mut stmt = conn.prepare(STORED_EVENTS_SQL)?;
+    for row in stmt.query_map(params![session_id.to_string()], stored_event_from_row)? {
+        f(row?)?;
     }
+    Ok(())
+}
+
+pub fn insert_stored_event(
+    conn: &rusqlite::Connection,
+    session_id: Uuid,
+    event: &StoredEvent,
+) -> anyhow::Result<()> {
+    conn.execute(
+        "INSERT INTO events(session_id, seq, event_id, event_type, payload_canon_json, prev_event_hash, event_hash, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            session_id.to_string(),
+            event.seq,
+            event.event_id,
+            event.event_type,
+            event.payload_canon_json,
+            event.prev_event_hash,
+            event.event_hash,
+            event.created_at,
+        ],
+    )?;
+    Ok(())
+}
 
-    let head: Option<(i64, String)> = conn
+pub fn validate_hash_chain(conn: &rusqlite::Connection, session_id: Uuid) -> anyhow::Result<()> {
+    let head: Option<(i64, String, String)> = conn
         .query_row(
-            "SELECT head_seq, head_hash FROM sessions WHERE session_id=?1",
+            "SELECT head_seq, head_hash, label FROM sessions WHERE session_id=?1",
             params![session_id.to_string()],
-            |r| Ok((r.get(0)?, r.get(1)?)),
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
         )
         .optional()?;
-    if let Some((head_seq, head_hash)) = head {
-        if head_seq != last_seq || (!head_hash.is_empty() && head_hash != last_hash) {
+    let label = head.as_ref().map_or("", |(_, _, label)| label.as_str());
+    let mut stmt = conn.prepare(STORED_EVENTS_SQL)?;
+    let rows = stmt.query_map(params![session_id.to_string()], stored_event_from_row)?;
+    let last = verify_chain(
+        session_id,
+        label,
+        rows.map(|row| row.map_err(anyhow::Error::from)),
+    )?;
+
+    if let Some((head_seq, head_hash, _)) = head {
+        if head_seq != last.seq || (last.seq > 0 && head_hash != last.hash) {
             anyhow::bail!("session head does not match validated chain")
         }
     }
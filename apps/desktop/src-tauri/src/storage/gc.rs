@@ -1,4 +1,5 @@
 use crate::storage::asset_store::AssetStore;
+use crate::storage::event_store::{stream_events, EventFilter};
 use serde::Serialize;
 use std::collections::BTreeSet;
 use uuid::Uuid;
@@ -22,17 +23,20 @@ struct StorageGcRanPayload {
 
 pub fn collect_referenced_asset_ids(
     conn: &rusqlite::Connection,
+    store: &AssetStore,
 ) -> anyhow::Result<BTreeSet<String>> {
     let mut refs = BTreeSet::new();
 
     // assets referenced directly in event payloads
-    let mut stmt = conn.prepare("SELECT payload_canon_json FROM events")?;
-    let rows = stmt
+    let mut stmt = conn.prepare("SELECT DISTINCT session_id FROM events")?;
+    let session_ids = stmt
         .query_map([], |r| r.get::<_, String>(0))?
         .collect::<Result<Vec<_>, _>>()?;
-    for raw in rows {
-        if let Ok(v) = serde_json::from_str::<serde_json::Value>(&raw) {
-            collect_asset_ids_from_json(&v, &mut refs);
+    for session_id in session_ids {
+        for event in stream_events(conn, Uuid::parse_str(&session_id)?, EventFilter::default()) {
+            if let Ok(v) = serde_json::from_str::<serde_json::Value>(&event?.payload_canon_json) {
+                collect_asset_ids_from_json(&v, &mut refs);
+            }
         }
     }
 
@@ -57,6 +61,22 @@ pub fn collect_referenced_asset_ids(
         }
     }
 
+    // bench reports are recorded only in `benchmarks`; each report names the
+    // prompt and output assets of its cases
+    let mut stmt =
+        conn.prepare("SELECT report_asset_id FROM benchmarks WHERE report_asset_id IS NOT NULL")?;
+    let report_ids = stmt
+        .query_map([], |r| r.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    for report_id in report_ids {
+        if let Ok(raw) = std::fs::read(store.path_for(&report_id)) {
+            if let Ok(v) = serde_json::from_slice::<serde_json::Value>(&raw) {
+                collect_asset_ids_from_json(&v, &mut refs);
+            }
+        }
+        refs.insert(report_id);
+    }
+
     // snapshots may include asset IDs inside JSON
     for table in ["steps_snapshot", "anchors_snapshot"] {
         let mut stmt = conn.prepare(&format!("SELECT {} FROM {}", json_col(table), table))?;
@@ -78,7 +98,7 @@ pub fn gc_orphan_assets(
     store: &AssetStore,
     dry_run: bool,
 ) -> anyhow::Result<GcReport> {
-    let referenced = collect_referenced_asset_ids(conn)?;
+    let referenced = collect_referenced_asset_ids(conn, store)?;
 
     let mut stmt = conn.prepare("SELECT asset_id FROM assets")?;
     let all_ids = stmt
@@ -150,7 +170,7 @@ fn json_col(table: &str) -> &'static str {
     }
 }
 
-fn collect_asset_ids_from_json(v: &serde_json::Value, refs: &mut BTreeSet<String>) {
+pub(crate) fn collect_asset_ids_from_json(v: &serde_json::Value, refs: &mut BTreeSet<String>) {
     match v {
         serde_json::Value::Object(map) => {
             for (k, val) in map {
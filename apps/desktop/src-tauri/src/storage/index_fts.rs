@@ -1,14 +1,101 @@
-use opscinema_types::{OcrBlock, OcrSearchHit};
-
-pub fn search_blocks(blocks: &[OcrBlock], query: &str, frame_ms: i64) -> Vec<OcrSearchHit> {
-    let q = query.to_lowercase();
-    blocks
-        .iter()
-        .filter(|b| b.text.to_lowercase().contains(&q))
-        .map(|b| OcrSearchHit {
-            frame_ms,
-            block_id: b.ocr_block_id.clone(),
-            snippet: b.text.clone(),
-        })
-        .collect()
+use crate::ocr::index::OcrQuery;
+use opscinema_types::{BBoxNorm, OcrSearchHit, TextOffset};
+use rusqlite::params;
+use uuid::Uuid;
+
+/// Upper bound on hits returned by one search.
+pub const SEARCH_HIT_LIMIT: u32 = 500;
+const SNIPPET_TOKENS: u32 = 16;
+const MARK_OPEN: char = '\u{1}';
+const MARK_CLOSE: char = '\u{2}';
+
+/// bm25-ranked hits for one session, each with its own block's frame and bbox.
+pub fn search(
+    conn: &rusqlite::Connection,
+    session_id: Uuid,
+    query: &OcrQuery,
+    limit: u32,
+) -> anyhow::Result<Vec<OcrSearchHit>> {
+    Ok(ranked_hits(conn, Some(session_id), query, limit)?
+        .into_iter()
+        .map(|(_, hit)| hit)
+        .collect())
+}
+
+/// bm25-ranked hits across every session, tagged with the owning session.
+pub fn search_all_sessions(
+    conn: &rusqlite::Connection,
+    query: &OcrQuery,
+    limit: u32,
+) -> anyhow::Result<Vec<(Uuid, OcrSearchHit)>> {
+    ranked_hits(conn, None, query, limit)
+}
+
+fn ranked_hits(
+    conn: &rusqlite::Connection,
+    session_id: Option<Uuid>,
+    query: &OcrQuery,
+    limit: u32,
+) -> anyhow::Result<Vec<(Uuid, OcrSearchHit)>> {
+    let mut stmt = conn.prepare(
+        "SELECT b.ocr_block_id, b.frame_ms, b.bbox_json,
+                snippet(ocr_blocks_fts, 0, char(1), char(2), '…', ?4), b.session_id
+         FROM ocr_blocks_fts
+         JOIN ocr_blocks b ON b.id = ocr_blocks_fts.rowid
+         WHERE ocr_blocks_fts MATCH ?1 AND (?2 IS NULL OR ocr_blocks_fts.session_id = ?2)
+         ORDER BY bm25(ocr_blocks_fts), b.frame_ms, b.ocr_block_id
+         LIMIT ?3",
+    )?;
+    let hits = stmt
+        .query_map(
+            params![
+                query.fts,
+                session_id.map(|id| id.to_string()),
+                limit,
+                SNIPPET_TOKENS
+            ],
+            |r| {
+                let marked: String = r.get(3)?;
+                let (snippet, highlights) = split_marks(&marked);
+                let hit = OcrSearchHit {
+                    block_id: r.get(0)?,
+                    frame_ms: r.get(1)?,
+                    bbox_norm: serde_json::from_str(&r.get::<_, String>(2)?).unwrap_or(BBoxNorm {
+                        x: 0,
+                        y: 0,
+                        w: 0,
+                        h: 0,
+                    }),
+                    snippet,
+                    highlights,
+                };
+                let session_id =
+                    Uuid::parse_str(&r.get::<_, String>(4)?).unwrap_or_else(|_| Uuid::nil());
+                Ok((session_id, hit))
+            },
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(hits)
+}
+
+fn split_marks(marked: &str) -> (String, Vec<TextOffset>) {
+    let mut text = String::with_capacity(marked.len());
+    let mut highlights = Vec::new();
+    let mut len = 0u32;
+    let mut open = None;
+    for c in marked.chars() {
+        match c {
+            MARK_OPEN => open = Some(len),
+            MARK_CLOSE => {
+                if let Some(start) = open.take() {
+                    highlights.push(TextOffset { start, end: len });
+                }
+            }
+            c => {
+                text.push(c);
+                len += 1;
+            }
+        }
+    }
+    (text, highlights)
 }
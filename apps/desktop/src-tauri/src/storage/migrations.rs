@@ -0,0 +1,181 @@
+use crate::util::hash::blake3_hex;
+use crate::util::time::now_utc_iso;
+use rusqlite::{params, Connection};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+impl Migration {
+    pub fn checksum(&self) -> String {
+        blake3_hex(self.sql.as_bytes())
+    }
+}
+
+/// Ordered, append-only list of schema migrations. Files under `schema/` are
+/// immutable once merged; new changes get the next version number.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "0001_init",
+        sql: include_str!("schema/0001_init.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "0002_snapshot_checkpoints",
+        sql: include_str!("schema/0002_snapshot_checkpoints.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "0003_events_type_index",
+        sql: include_str!("schema/0003_events_type_index.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "0004_trusted_signing_keys",
+        sql: include_str!("schema/0004_trusted_signing_keys.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "0005_ocr_blocks_fts",
+        sql: include_str!("schema/0005_ocr_blocks_fts.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "0006_session_metadata",
+        sql: include_str!("schema/0006_session_metadata.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "0007_benchmark_metrics",
+        sql: include_str!("schema/0007_benchmark_metrics.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "0008_model_digests",
+        sql: include_str!("schema/0008_model_digests.sql"),
+    },
+    Migration {
+        version: 9,
+        name: "0009_ocr_blocks_fts_content",
+        sql: include_str!("schema/0009_ocr_blocks_fts_content.sql"),
+    },
+    Migration {
+        version: 10,
+        name: "0010_evidence_owners",
+        sql: include_str!("schema/0010_evidence_owners.sql"),
+    },
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub name: String,
+    pub checksum: String,
+}
+
+pub fn latest_version() -> i64 {
+    latest_version_of(MIGRATIONS)
+}
+
+pub fn migrate(conn: &mut Connection) -> anyhow::Result<()> {
+    apply_migrations(conn, MIGRATIONS)
+}
+
+pub fn applied_migrations(conn: &Connection) -> anyhow::Result<Vec<AppliedMigration>> {
+    let mut stmt =
+        conn.prepare("SELECT version, name, checksum FROM schema_migrations ORDER BY version ASC")?;
+    let rows = stmt
+        .query_map([], |r| {
+            Ok(AppliedMigration {
+                version: r.get(0)?,
+                name: r.get(1)?,
+                checksum: r.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+pub(crate) fn apply_migrations(
+    conn: &mut Connection,
+    migrations: &[Migration],
+) -> anyhow::Result<()> {
+    ensure_ordered(migrations)?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (
+           version INTEGER PRIMARY KEY,
+           name TEXT NOT NULL,
+           checksum TEXT NOT NULL,
+           applied_at TEXT NOT NULL
+         );",
+    )?;
+
+    let applied = applied_migrations(conn)?;
+    let supported = latest_version_of(migrations);
+    if let Some(newest) = applied.last() {
+        if newest.version > supported {
+            anyhow::bail!(
+                "database schema version {} is newer than this build supports ({supported}); refusing to open",
+                newest.version
+            )
+        }
+    }
+
+    let known: BTreeMap<i64, &Migration> = migrations.iter().map(|m| (m.version, m)).collect();
+    for row in &applied {
+        let Some(migration) = known.get(&row.version) else {
+            anyhow::bail!("applied migration {} is unknown to this build", row.version)
+        };
+        if migration.checksum() != row.checksum {
+            anyhow::bail!(
+                "checksum mismatch for applied migration {} ({}); schema files are immutable once applied",
+                row.version,
+                row.name
+            )
+        }
+    }
+
+    let current = applied.last().map(|m| m.version).unwrap_or(0);
+    for migration in migrations.iter().filter(|m| m.version > current) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration.sql).map_err(|e| {
+            anyhow::anyhow!(
+                "migration {} ({}) failed: {e}",
+                migration.version,
+                migration.name
+            )
+        })?;
+        tx.execute(
+            "INSERT INTO schema_migrations(version, name, checksum, applied_at) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                migration.version,
+                migration.name,
+                migration.checksum(),
+                now_utc_iso()
+            ],
+        )?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+fn latest_version_of(migrations: &[Migration]) -> i64 {
+    migrations.last().map(|m| m.version).unwrap_or(0)
+}
+
+fn ensure_ordered(migrations: &[Migration]) -> anyhow::Result<()> {
+    for (expected, migration) in (1_i64..).zip(migrations) {
+        if migration.version != expected {
+            anyhow::bail!(
+                "migration list is not contiguous: expected version {expected}, found {}",
+                migration.version
+            )
+        }
+    }
+    Ok(())
+}
@@ -3,13 +3,17 @@ pub mod db;
 pub mod event_store;
 pub mod gc;
 pub mod index_fts;
+pub mod migrations;
+pub mod repo_evidence;
 pub mod repo_exports;
 pub mod repo_jobs;
 pub mod repo_models;
 pub mod repo_ocr;
 pub mod repo_sessions;
+pub mod repo_snapshots;
 pub mod repo_timeline;
 pub mod repo_verifiers;
+pub mod signing_keys;
 
 pub use db::Storage;
 pub type DbConn = rusqlite::Connection;
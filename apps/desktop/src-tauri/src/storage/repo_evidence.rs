@@ -0,0 +1,73 @@
+//! `evidence_id -> session_id` index, so a ref to another session's evidence
+//! can be resolved without deriving every session's evidence graph. Each
+//! session is indexed up to a watermark seq and caught up incrementally.
+
+use rusqlite::{params, OptionalExtension};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Sessions whose log has grown past their indexed seq, as
+/// `(session_id, indexed_seq, head_seq)`.
+pub fn stale_sessions(conn: &rusqlite::Connection) -> anyhow::Result<Vec<(Uuid, i64, i64)>> {
+    let mut stmt = conn.prepare(
+        "SELECT s.session_id, COALESCE(h.indexed_seq, 0), s.head_seq
+         FROM sessions s
+         LEFT JOIN evidence_owner_heads h ON h.session_id = s.session_id
+         WHERE s.head_seq > COALESCE(h.indexed_seq, 0)
+         ORDER BY s.session_id",
+    )?;
+    let rows = stmt
+        .query_map([], |r| {
+            Ok((r.get::<_, String>(0)?, r.get::<_, i64>(1)?, r.get(2)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|(id, indexed, head)| Some((Uuid::parse_str(&id).ok()?, indexed, head)))
+        .collect())
+}
+
+/// Records `session_id` as the owner of `evidence_ids`, derived from its
+/// events through `indexed_seq`.
+pub fn record_owners(
+    conn: &rusqlite::Connection,
+    session_id: Uuid,
+    evidence_ids: impl IntoIterator<Item = Uuid>,
+    indexed_seq: i64,
+) -> anyhow::Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    {
+        let mut insert = tx.prepare(
+            "INSERT OR IGNORE INTO evidence_owners(evidence_id, session_id) VALUES (?1, ?2)",
+        )?;
+        for evidence_id in evidence_ids {
+            insert.execute(params![evidence_id.to_string(), session_id.to_string()])?;
+        }
+    }
+    tx.execute(
+        "INSERT INTO evidence_owner_heads(session_id, indexed_seq) VALUES (?1, ?2)
+         ON CONFLICT(session_id) DO UPDATE SET indexed_seq=MAX(indexed_seq, excluded.indexed_seq)",
+        params![session_id.to_string(), indexed_seq],
+    )?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Owning session of each indexed id in `evidence_ids`; unindexed ids are
+/// left out.
+pub fn owners_of<'a>(
+    conn: &rusqlite::Connection,
+    evidence_ids: impl IntoIterator<Item = &'a Uuid>,
+) -> anyhow::Result<BTreeMap<Uuid, Uuid>> {
+    let mut stmt = conn.prepare("SELECT session_id FROM evidence_owners WHERE evidence_id=?1")?;
+    let mut owners = BTreeMap::new();
+    for evidence_id in evidence_ids {
+        let owner = stmt
+            .query_row(params![evidence_id.to_string()], |r| r.get::<_, String>(0))
+            .optional()?;
+        if let Some(owner) = owner.and_then(|o| Uuid::parse_str(&o).ok()) {
+            owners.insert(*evidence_id, owner);
+        }
+    }
+    Ok(owners)
+}
@@ -1,5 +1,7 @@
 use crate::util::time::now_utc_iso;
-use opscinema_types::{BenchListResponse, BenchRecord, ModelProfile, ModelRoles, ModelRolesUpdate};
+use opscinema_types::{
+    BenchListResponse, BenchMetrics, BenchRecord, ModelProfile, ModelRoles, ModelRolesUpdate,
+};
 use rusqlite::params;
 use uuid::Uuid;
 
@@ -7,31 +9,35 @@ pub fn insert_model(
     conn: &rusqlite::Connection,
     provider: &str,
     label: &str,
+    model_name: &str,
     digest: &str,
 ) -> anyhow::Result<ModelProfile> {
     let model_id = format!("{}:{}", provider, digest);
     conn.execute(
-        "INSERT OR REPLACE INTO models(model_id, provider, label, digest, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
-        params![model_id, provider, label, digest, now_utc_iso()],
+        "INSERT OR REPLACE INTO models(model_id, provider, label, model_name, digest, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![model_id, provider, label, model_name, digest, now_utc_iso()],
     )?;
     Ok(ModelProfile {
         model_id,
         provider: provider.to_string(),
         label: label.to_string(),
+        model_name: model_name.to_string(),
         digest: digest.to_string(),
     })
 }
 
 pub fn list_models(conn: &rusqlite::Connection) -> anyhow::Result<Vec<ModelProfile>> {
-    let mut stmt = conn
-        .prepare("SELECT model_id, provider, label, digest FROM models ORDER BY created_at DESC")?;
+    let mut stmt = conn.prepare(
+        "SELECT model_id, provider, label, COALESCE(model_name, label), digest FROM models ORDER BY created_at DESC",
+    )?;
     let rows = stmt
         .query_map([], |r| {
             Ok(ModelProfile {
                 model_id: r.get(0)?,
                 provider: r.get(1)?,
                 label: r.get(2)?,
-                digest: r.get(3)?,
+                model_name: r.get(3)?,
+                digest: r.get(4)?,
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
@@ -91,20 +97,29 @@ pub fn get_roles(conn: &rusqlite::Connection) -> anyhow::Result<ModelRoles> {
 pub fn record_benchmark(
     conn: &rusqlite::Connection,
     model_id: &str,
+    benchmark: &str,
+    metrics: &BenchMetrics,
     score: i32,
+    report_asset_id: &str,
 ) -> anyhow::Result<BenchRecord> {
     let bench = BenchRecord {
         bench_id: Uuid::new_v4(),
         model_id: model_id.to_string(),
+        benchmark: benchmark.to_string(),
         score,
+        metrics: Some(metrics.clone()),
+        report_asset_id: Some(report_asset_id.to_string()),
         created_at: chrono::Utc::now(),
     };
     conn.execute(
-        "INSERT INTO benchmarks(bench_id, model_id, score, created_at) VALUES (?1, ?2, ?3, ?4)",
+        "INSERT INTO benchmarks(bench_id, model_id, benchmark, score, metrics_json, report_asset_id, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
         params![
             bench.bench_id.to_string(),
             bench.model_id,
+            bench.benchmark,
             bench.score,
+            serde_json::to_string(metrics)?,
+            report_asset_id,
             now_utc_iso()
         ],
     )?;
@@ -113,15 +128,20 @@ pub fn record_benchmark(
 
 pub fn list_benchmarks(conn: &rusqlite::Connection) -> anyhow::Result<BenchListResponse> {
     let mut stmt = conn.prepare(
-        "SELECT bench_id, model_id, score, created_at FROM benchmarks ORDER BY created_at DESC",
+        "SELECT bench_id, model_id, benchmark, score, metrics_json, report_asset_id, created_at FROM benchmarks ORDER BY created_at DESC, rowid DESC",
     )?;
     let benches = stmt
         .query_map([], |r| {
             Ok(BenchRecord {
                 bench_id: Uuid::parse_str(&r.get::<_, String>(0)?).unwrap_or_else(|_| Uuid::nil()),
                 model_id: r.get(1)?,
-                score: r.get(2)?,
-                created_at: chrono::DateTime::parse_from_rfc3339(&r.get::<_, String>(3)?)
+                benchmark: r.get(2)?,
+                score: r.get(3)?,
+                metrics: r
+                    .get::<_, Option<String>>(4)?
+                    .and_then(|json| serde_json::from_str(&json).ok()),
+                report_asset_id: r.get(5)?,
+                created_at: chrono::DateTime::parse_from_rfc3339(&r.get::<_, String>(6)?)
                     .map(|dt| dt.with_timezone(&chrono::Utc))
                     .unwrap_or_else(|_| chrono::Utc::now()),
             })
@@ -129,3 +149,62 @@ pub fn list_benchmarks(conn: &rusqlite::Connection) -> anyhow::Result<BenchListR
         .collect::<Result<Vec<_>, _>>()?;
     Ok(BenchListResponse { benches })
 }
+
+/// Which model, at which installed digest, produced a generated block in the
+/// step edit at `edit_seq`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratedBlockModel {
+    pub step_id: Uuid,
+    pub block_id: String,
+    pub edit_seq: i64,
+    pub role: String,
+    pub model_id: String,
+    pub digest: String,
+}
+
+pub fn record_block_models(
+    conn: &rusqlite::Connection,
+    session_id: Uuid,
+    rows: &[GeneratedBlockModel],
+) -> anyhow::Result<()> {
+    let created_at = now_utc_iso();
+    for row in rows {
+        conn.execute(
+            "INSERT OR REPLACE INTO generated_block_models(session_id, step_id, block_id, edit_seq, role, model_id, digest, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                session_id.to_string(),
+                row.step_id.to_string(),
+                row.block_id,
+                row.edit_seq,
+                row.role,
+                row.model_id,
+                row.digest,
+                created_at
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/// Block history of a session, oldest edit first.
+pub fn list_block_models(
+    conn: &rusqlite::Connection,
+    session_id: Uuid,
+) -> anyhow::Result<Vec<GeneratedBlockModel>> {
+    let mut stmt = conn.prepare(
+        "SELECT step_id, block_id, edit_seq, role, model_id, digest FROM generated_block_models WHERE session_id=?1 ORDER BY edit_seq, step_id, block_id",
+    )?;
+    let rows = stmt
+        .query_map(params![session_id.to_string()], |r| {
+            Ok(GeneratedBlockModel {
+                step_id: Uuid::parse_str(&r.get::<_, String>(0)?).unwrap_or_else(|_| Uuid::nil()),
+                block_id: r.get(1)?,
+                edit_seq: r.get(2)?,
+                role: r.get(3)?,
+                model_id: r.get(4)?,
+                digest: r.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
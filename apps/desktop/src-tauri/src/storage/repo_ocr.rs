@@ -1,4 +1,4 @@
-use crate::storage::DbConn;
+use crate::storage::{index_fts, DbConn};
 use opscinema_types::{BBoxNorm, OcrBlock, OcrSearchResponse, OcrStatus};
 use uuid::Uuid;
 
@@ -9,10 +9,15 @@ pub fn upsert_blocks(
     frame_ms: i64,
     blocks: &[OcrBlock],
 ) -> anyhow::Result<()> {
+    // `ocr_blocks_fts` follows through triggers on `ocr_blocks`; an update
+    // (not a REPLACE, which would skip the delete trigger) keeps the id stable.
     for block in blocks {
         conn.execute(
-            "INSERT OR REPLACE INTO ocr_blocks(session_id, frame_event_id, ocr_block_id, frame_ms, text, bbox_json, confidence, language)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            "INSERT INTO ocr_blocks(session_id, frame_event_id, ocr_block_id, frame_ms, text, bbox_json, confidence, language)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT(session_id, frame_event_id, ocr_block_id) DO UPDATE SET
+               frame_ms=excluded.frame_ms, text=excluded.text, bbox_json=excluded.bbox_json,
+               confidence=excluded.confidence, language=excluded.language",
             rusqlite::params![
                 session_id.to_string(),
                 frame_event_id.to_string(),
@@ -87,6 +92,23 @@ pub fn list_blocks_for_frame(
     Ok(rows)
 }
 
+/// `(frame_event_id, ocr_block_id, text)` of every OCR block in the session,
+/// in insertion order.
+pub fn list_block_texts(
+    conn: &DbConn,
+    session_id: Uuid,
+) -> anyhow::Result<Vec<(String, String, String)>> {
+    let mut stmt = conn.prepare(
+        "SELECT frame_event_id, ocr_block_id, text FROM ocr_blocks WHERE session_id=?1 ORDER BY id",
+    )?;
+    let rows = stmt
+        .query_map(rusqlite::params![session_id.to_string()], |r| {
+            Ok((r.get(0)?, r.get(1)?, r.get(2)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
 pub fn status(conn: &DbConn, session_id: Uuid) -> anyhow::Result<OcrStatus> {
     let count = conn.query_row(
         "SELECT COUNT(*) FROM ocr_blocks WHERE session_id=?1",
@@ -100,15 +122,9 @@ pub fn status(conn: &DbConn, session_id: Uuid) -> anyhow::Result<OcrStatus> {
 }
 
 pub fn search(conn: &DbConn, session_id: Uuid, query: &str) -> anyhow::Result<OcrSearchResponse> {
-    let q = query.to_lowercase();
-    let hits = list_blocks_by_session(conn, session_id)?
-        .into_iter()
-        .filter(|(block, _)| block.text.to_lowercase().contains(&q))
-        .map(|(block, frame_ms)| opscinema_types::OcrSearchHit {
-            frame_ms,
-            block_id: block.ocr_block_id,
-            snippet: block.text,
-        })
-        .collect();
+    let Some(query) = crate::ocr::index::parse_query(query) else {
+        return Ok(OcrSearchResponse { hits: vec![] });
+    };
+    let hits = index_fts::search(conn, session_id, &query, index_fts::SEARCH_HIT_LIMIT)?;
     Ok(OcrSearchResponse { hits })
 }
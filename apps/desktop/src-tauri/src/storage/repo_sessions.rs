@@ -1,11 +1,15 @@
-use crate::util::hash::blake3_hex;
+use crate::storage::event_store;
 use crate::util::time::now_utc_iso;
 use opscinema_types::{SessionDetail, SessionSummary};
 use rusqlite::{params, OptionalExtension};
 use std::collections::BTreeMap;
 use uuid::Uuid;
 
-pub fn create_session(conn: &rusqlite::Connection, label: &str) -> anyhow::Result<SessionSummary> {
+pub fn create_session(
+    conn: &rusqlite::Connection,
+    label: &str,
+    metadata: &BTreeMap<String, String>,
+) -> anyhow::Result<SessionSummary> {
     let deterministic = std::env::var("OPSCINEMA_DETERMINISTIC_IDS")
         .map(|v| v == "1")
         .unwrap_or(false);
@@ -15,11 +19,17 @@ pub fn create_session(conn: &rusqlite::Connection, label: &str) -> anyhow::Resul
         Uuid::new_v4()
     };
     let created_at = now_utc_iso();
-    let head_hash = blake3_hex(format!("{}:{}", session_id, label).as_bytes());
+    let head_hash = event_store::genesis_hash(session_id);
 
     conn.execute(
-        "INSERT INTO sessions(session_id,label,created_at,head_seq,head_hash) VALUES (?1, ?2, ?3, 0, ?4)",
-        params![session_id.to_string(), label, created_at, head_hash],
+        "INSERT INTO sessions(session_id,label,created_at,head_seq,head_hash,metadata_json) VALUES (?1, ?2, ?3, 0, ?4, ?5)",
+        params![
+            session_id.to_string(),
+            label,
+            created_at,
+            head_hash,
+            serde_json::to_string(metadata)?
+        ],
     )?;
 
     Ok(SessionSummary {
@@ -32,6 +42,36 @@ pub fn create_session(conn: &rusqlite::Connection, label: &str) -> anyhow::Resul
     })
 }
 
+/// Inserts a session row carried over from an archive, keeping its original
+/// id, timestamps and chain head.
+pub fn insert_imported_session(
+    conn: &rusqlite::Connection,
+    session_id: Uuid,
+    label: &str,
+    created_at: &str,
+    closed_at: Option<&str>,
+    head_seq: i64,
+    head_hash: &str,
+) -> anyhow::Result<()> {
+    conn.execute(
+        "INSERT INTO sessions(session_id,label,created_at,closed_at,head_seq,head_hash) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![session_id.to_string(), label, created_at, closed_at, head_seq, head_hash],
+    )?;
+    Ok(())
+}
+
+pub fn set_metadata(
+    conn: &rusqlite::Connection,
+    session_id: Uuid,
+    metadata: &BTreeMap<String, String>,
+) -> anyhow::Result<()> {
+    conn.execute(
+        "UPDATE sessions SET metadata_json=?2 WHERE session_id=?1",
+        params![session_id.to_string(), serde_json::to_string(metadata)?],
+    )?;
+    Ok(())
+}
+
 pub fn list_sessions(
     conn: &rusqlite::Connection,
     limit: u32,
@@ -67,35 +107,48 @@ pub fn get_session(
     conn: &rusqlite::Connection,
     session_id: Uuid,
 ) -> anyhow::Result<Option<SessionDetail>> {
-    let summary = conn
+    let detail = conn
         .query_row(
-            "SELECT session_id,label,created_at,closed_at,head_seq,head_hash FROM sessions WHERE session_id=?1",
+            "SELECT session_id,label,created_at,closed_at,head_seq,head_hash,metadata_json FROM sessions WHERE session_id=?1",
             params![session_id.to_string()],
-            |r| {
-                let created_raw: String = r.get(2)?;
-                let closed_raw: Option<String> = r.get(3)?;
-                Ok(SessionSummary {
-                    session_id: Uuid::parse_str(&r.get::<_, String>(0)?).unwrap_or_else(|_| Uuid::nil()),
-                    label: r.get(1)?,
-                    created_at: chrono::DateTime::parse_from_rfc3339(&created_raw)
-                        .map(|dt| dt.with_timezone(&chrono::Utc))
-                        .unwrap_or_else(|_| chrono::Utc::now()),
-                    closed_at: closed_raw.and_then(|v| {
-                        chrono::DateTime::parse_from_rfc3339(&v)
-                            .ok()
-                            .map(|dt| dt.with_timezone(&chrono::Utc))
-                    }),
-                    head_seq: r.get(4)?,
-                    head_hash: r.get(5)?,
-                })
-            },
+            detail_from_row,
         )
         .optional()?;
+    Ok(detail)
+}
+
+/// Every session with its metadata, newest first.
+pub fn list_session_details(conn: &rusqlite::Connection) -> anyhow::Result<Vec<SessionDetail>> {
+    let mut stmt = conn.prepare(
+        "SELECT session_id,label,created_at,closed_at,head_seq,head_hash,metadata_json FROM sessions ORDER BY created_at DESC, session_id ASC",
+    )?;
+    let rows = stmt
+        .query_map([], detail_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
 
-    Ok(summary.map(|summary| SessionDetail {
-        summary,
-        metadata: BTreeMap::new(),
-    }))
+fn detail_from_row(r: &rusqlite::Row<'_>) -> rusqlite::Result<SessionDetail> {
+    let created_raw: String = r.get(2)?;
+    let closed_raw: Option<String> = r.get(3)?;
+    let metadata_raw: String = r.get(6)?;
+    Ok(SessionDetail {
+        summary: SessionSummary {
+            session_id: Uuid::parse_str(&r.get::<_, String>(0)?).unwrap_or_else(|_| Uuid::nil()),
+            label: r.get(1)?,
+            created_at: chrono::DateTime::parse_from_rfc3339(&created_raw)
+                .map(|dt| dt.with_timezone(&chrono::Utc))
+                .unwrap_or_else(|_| chrono::Utc::now()),
+            closed_at: closed_raw.and_then(|v| {
+                chrono::DateTime::parse_from_rfc3339(&v)
+                    .ok()
+                    .map(|dt| dt.with_timezone(&chrono::Utc))
+            }),
+            head_seq: r.get(4)?,
+            head_hash: r.get(5)?,
+        },
+        metadata: serde_json::from_str(&metadata_raw).unwrap_or_default(),
+    })
 }
 
 pub fn close_session(conn: &rusqlite::Connection, session_id: Uuid) -> anyhow::Result<bool> {
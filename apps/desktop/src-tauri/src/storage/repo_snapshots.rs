@@ -0,0 +1,130 @@
+use crate::util::canon_json::to_canonical_json;
+use crate::util::hash::blake3_hex;
+use crate::util::time::now_utc_iso;
+use rusqlite::{params, OptionalExtension};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotKind {
+    Steps,
+    Anchors,
+}
+
+impl SnapshotKind {
+    fn table(self) -> &'static str {
+        match self {
+            SnapshotKind::Steps => "steps_snapshot",
+            SnapshotKind::Anchors => "anchors_snapshot",
+        }
+    }
+
+    fn state_column(self) -> &'static str {
+        match self {
+            SnapshotKind::Steps => "steps_json",
+            SnapshotKind::Anchors => "anchors_json",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Checkpoint<T> {
+    pub head_seq: i64,
+    pub state: T,
+}
+
+/// Loads the replay checkpoint for `session_id`. A checkpoint that no longer
+/// agrees with the event log is discarded and `None` is returned, so the caller
+/// replays from scratch.
+pub fn load_checkpoint<T: DeserializeOwned>(
+    conn: &rusqlite::Connection,
+    kind: SnapshotKind,
+    session_id: Uuid,
+) -> anyhow::Result<Option<Checkpoint<T>>> {
+    let sql = format!(
+        "SELECT head_seq, head_hash, state_hash, {} FROM {} WHERE session_id=?1",
+        kind.state_column(),
+        kind.table()
+    );
+    let row: Option<(i64, String, String, String)> = conn
+        .query_row(&sql, params![session_id.to_string()], |r| {
+            Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?))
+        })
+        .optional()?;
+    let Some((head_seq, head_hash, state_hash, state_json)) = row else {
+        return Ok(None);
+    };
+
+    let log_hash: Option<String> = conn
+        .query_row(
+            "SELECT event_hash FROM events WHERE session_id=?1 AND seq=?2",
+            params![session_id.to_string(), head_seq],
+            |r| r.get(0),
+        )
+        .optional()?;
+    let state = if log_hash.as_deref() == Some(head_hash.as_str())
+        && blake3_hex(state_json.as_bytes()) == state_hash
+    {
+        serde_json::from_str(&state_json).ok()
+    } else {
+        None
+    };
+    match state {
+        Some(state) => Ok(Some(Checkpoint { head_seq, state })),
+        None => {
+            discard_checkpoint(conn, kind, session_id)?;
+            Ok(None)
+        }
+    }
+}
+
+pub fn discard_checkpoint(
+    conn: &rusqlite::Connection,
+    kind: SnapshotKind,
+    session_id: Uuid,
+) -> anyhow::Result<()> {
+    conn.execute(
+        &format!("DELETE FROM {} WHERE session_id=?1", kind.table()),
+        params![session_id.to_string()],
+    )?;
+    Ok(())
+}
+
+/// Records replay state as of event `head_seq`. Older checkpoints never
+/// overwrite newer ones.
+pub fn save_checkpoint<T: Serialize>(
+    conn: &rusqlite::Connection,
+    kind: SnapshotKind,
+    session_id: Uuid,
+    head_seq: i64,
+    head_hash: &str,
+    state: &T,
+) -> anyhow::Result<()> {
+    let state_json = to_canonical_json(state)?;
+    let sql = format!(
+        "INSERT INTO {table}(session_id, head_seq, {col}, updated_at, head_hash, state_hash)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(session_id) DO UPDATE SET
+           head_seq=excluded.head_seq,
+           {col}=excluded.{col},
+           updated_at=excluded.updated_at,
+           head_hash=excluded.head_hash,
+           state_hash=excluded.state_hash
+         WHERE excluded.head_seq >= {table}.head_seq",
+        table = kind.table(),
+        col = kind.state_column()
+    );
+    conn.execute(
+        &sql,
+        params![
+            session_id.to_string(),
+            head_seq,
+            state_json,
+            now_utc_iso(),
+            head_hash,
+            blake3_hex(state_json.as_bytes()),
+        ],
+    )?;
+    Ok(())
+}
@@ -1,12 +1,25 @@
-use crate::storage::event_store;
+use crate::storage::event_store::{self, EventFilter};
 use opscinema_types::{TimelineEvent, TimelineKeyframe};
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
 #[derive(Debug, Deserialize)]
 struct KeyframeCaptured {
     frame_ms: i64,
     asset_id: String,
+    /// Absent on frames captured before thumbnails and hashes were recorded.
+    #[serde(default)]
+    thumbnail_asset_id: Option<String>,
+    #[serde(default)]
+    dhash: Option<String>,
+}
+
+/// Full-size and thumbnail assets of one keyframe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyframeAssets {
+    pub asset_id: String,
+    pub thumbnail_asset_id: Option<String>,
 }
 
 pub fn get_events(
@@ -35,12 +48,11 @@ pub fn get_keyframes(
     start_ms: i64,
     end_ms: i64,
 ) -> anyhow::Result<Vec<TimelineKeyframe>> {
-    let rows = event_store::query_events(conn, session_id, None, 10_000)?;
     let mut keyframes = Vec::new();
-    for row in rows {
-        if row.event_type != "KeyframeCaptured" {
-            continue;
-        }
+    for row in
+        event_store::stream_events(conn, session_id, EventFilter::types(&["KeyframeCaptured"]))
+    {
+        let row = row?;
         if let Ok(k) = serde_json::from_str::<KeyframeCaptured>(&row.payload_canon_json) {
             if (start_ms..=end_ms).contains(&k.frame_ms) {
                 keyframes.push(TimelineKeyframe {
@@ -56,19 +68,44 @@ pub fn get_keyframes(
     Ok(keyframes)
 }
 
-pub fn get_thumbnail_asset(
+pub fn get_keyframe_assets(
     conn: &rusqlite::Connection,
     session_id: Uuid,
     frame_event_id: Uuid,
-) -> anyhow::Result<Option<String>> {
-    let rows = event_store::query_events(conn, session_id, None, 10_000)?;
-    for row in rows {
-        if row.event_id != frame_event_id.to_string() || row.event_type != "KeyframeCaptured" {
+) -> anyhow::Result<Option<KeyframeAssets>> {
+    let frame_event_id = frame_event_id.to_string();
+    for row in
+        event_store::stream_events(conn, session_id, EventFilter::types(&["KeyframeCaptured"]))
+    {
+        let row = row?;
+        if row.event_id != frame_event_id {
             continue;
         }
         if let Ok(k) = serde_json::from_str::<KeyframeCaptured>(&row.payload_canon_json) {
-            return Ok(Some(k.asset_id));
+            return Ok(Some(KeyframeAssets {
+                asset_id: k.asset_id,
+                thumbnail_asset_id: k.thumbnail_asset_id,
+            }));
         }
     }
     Ok(None)
 }
+
+/// Perceptual hashes recorded at capture, keyed by full-size asset id.
+pub fn keyframe_dhashes(
+    conn: &rusqlite::Connection,
+    session_id: Uuid,
+) -> anyhow::Result<BTreeMap<String, u64>> {
+    let mut hashes = BTreeMap::new();
+    for row in
+        event_store::stream_events(conn, session_id, EventFilter::types(&["KeyframeCaptured"]))
+    {
+        let row = row?;
+        if let Ok(k) = serde_json::from_str::<KeyframeCaptured>(&row.payload_canon_json) {
+            if let Some(hash) = k.dhash.as_deref().and_then(crate::image::dhash::from_hex) {
+                hashes.insert(k.asset_id, hash);
+            }
+        }
+    }
+    Ok(hashes)
+}
@@ -102,3 +102,31 @@ pub fn get_run(
         .optional()?;
     Ok(row)
 }
+
+/// Runs recorded for a session, oldest first.
+pub fn list_runs_for_session(
+    conn: &rusqlite::Connection,
+    session_id: uuid::Uuid,
+) -> anyhow::Result<Vec<VerifierResultDetail>> {
+    let mut stmt = conn.prepare(
+        "SELECT run_id, verifier_id, status, result_asset_id, logs_asset_id FROM verifier_runs
+         WHERE session_id=?1 ORDER BY created_at ASC, run_id ASC",
+    )?;
+    let runs = stmt
+        .query_map(params![session_id.to_string()], |r| {
+            let run_id = uuid::Uuid::parse_str(&r.get::<_, String>(0)?)
+                .unwrap_or_else(|_| uuid::Uuid::nil());
+            let logs_asset_id: Option<String> = r.get(4)?;
+            Ok(VerifierResultDetail {
+                run_id,
+                verifier_id: r.get(1)?,
+                status: r.get(2)?,
+                result_asset: AssetRef {
+                    asset_id: r.get(3)?,
+                },
+                logs_asset: logs_asset_id.map(|asset_id| AssetRef { asset_id }),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(runs)
+}
@@ -0,0 +1,5 @@
+ALTER TABLE steps_snapshot ADD COLUMN head_hash TEXT NOT NULL DEFAULT '';
+ALTER TABLE steps_snapshot ADD COLUMN state_hash TEXT NOT NULL DEFAULT '';
+
+ALTER TABLE anchors_snapshot ADD COLUMN head_hash TEXT NOT NULL DEFAULT '';
+ALTER TABLE anchors_snapshot ADD COLUMN state_hash TEXT NOT NULL DEFAULT '';
@@ -0,0 +1 @@
+CREATE INDEX IF NOT EXISTS idx_events_session_type_seq ON events(session_id, event_type, seq);
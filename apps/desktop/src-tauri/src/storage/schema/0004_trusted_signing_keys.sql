@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS trusted_signing_keys (
+  public_key TEXT PRIMARY KEY,
+  added_at TEXT NOT NULL
+);
@@ -0,0 +1,8 @@
+CREATE VIRTUAL TABLE IF NOT EXISTS ocr_blocks_fts USING fts5(
+  text,
+  session_id UNINDEXED,
+  tokenize = 'unicode61 remove_diacritics 2',
+  prefix = '2 3'
+);
+INSERT INTO ocr_blocks_fts(rowid, text, session_id)
+  SELECT rowid, text, session_id FROM ocr_blocks;
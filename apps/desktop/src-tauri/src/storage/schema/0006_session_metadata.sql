@@ -0,0 +1 @@
+ALTER TABLE sessions ADD COLUMN metadata_json TEXT NOT NULL DEFAULT '{}';
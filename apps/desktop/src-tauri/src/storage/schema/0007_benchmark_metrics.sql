@@ -0,0 +1,3 @@
+ALTER TABLE benchmarks ADD COLUMN benchmark TEXT NOT NULL DEFAULT '';
+ALTER TABLE benchmarks ADD COLUMN metrics_json TEXT;
+ALTER TABLE benchmarks ADD COLUMN report_asset_id TEXT;
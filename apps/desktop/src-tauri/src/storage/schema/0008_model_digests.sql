@@ -0,0 +1,16 @@
+ALTER TABLE models ADD COLUMN model_name TEXT;
+UPDATE models SET model_name = label WHERE model_name IS NULL;
+
+CREATE TABLE IF NOT EXISTS generated_block_models (
+  session_id TEXT NOT NULL,
+  step_id TEXT NOT NULL,
+  block_id TEXT NOT NULL,
+  edit_seq INTEGER NOT NULL,
+  role TEXT NOT NULL,
+  model_id TEXT NOT NULL,
+  digest TEXT NOT NULL,
+  created_at TEXT NOT NULL,
+  PRIMARY KEY (session_id, step_id, block_id, edit_seq)
+);
+
+CREATE INDEX IF NOT EXISTS idx_generated_block_models_digest ON generated_block_models(model_id, digest);
@@ -0,0 +1,44 @@
+-- Key the FTS index on a stable INTEGER PRIMARY KEY instead of the implicit
+-- rowid, which VACUUM may renumber, and keep it in sync with triggers so a
+-- block and its index row change in the same statement.
+DROP TABLE IF EXISTS ocr_blocks_fts;
+
+CREATE TABLE ocr_blocks_v9 (
+  id INTEGER PRIMARY KEY,
+  session_id TEXT NOT NULL,
+  frame_event_id TEXT NOT NULL,
+  ocr_block_id TEXT NOT NULL,
+  frame_ms INTEGER NOT NULL,
+  text TEXT NOT NULL,
+  bbox_json TEXT NOT NULL,
+  confidence INTEGER NOT NULL,
+  language TEXT,
+  UNIQUE (session_id, frame_event_id, ocr_block_id)
+);
+INSERT INTO ocr_blocks_v9(session_id, frame_event_id, ocr_block_id, frame_ms, text, bbox_json, confidence, language)
+  SELECT session_id, frame_event_id, ocr_block_id, frame_ms, text, bbox_json, confidence, language
+  FROM ocr_blocks ORDER BY rowid;
+DROP TABLE ocr_blocks;
+ALTER TABLE ocr_blocks_v9 RENAME TO ocr_blocks;
+
+CREATE VIRTUAL TABLE ocr_blocks_fts USING fts5(
+  text,
+  session_id UNINDEXED,
+  content = 'ocr_blocks',
+  content_rowid = 'id',
+  tokenize = 'unicode61 remove_diacritics 2',
+  prefix = '2 3'
+);
+
+CREATE TRIGGER ocr_blocks_fts_ai AFTER INSERT ON ocr_blocks BEGIN
+  INSERT INTO ocr_blocks_fts(rowid, text, session_id) VALUES (new.id, new.text, new.session_id);
+END;
+CREATE TRIGGER ocr_blocks_fts_ad AFTER DELETE ON ocr_blocks BEGIN
+  INSERT INTO ocr_blocks_fts(ocr_blocks_fts, rowid, text, session_id) VALUES ('delete', old.id, old.text, old.session_id);
+END;
+CREATE TRIGGER ocr_blocks_fts_au AFTER UPDATE ON ocr_blocks BEGIN
+  INSERT INTO ocr_blocks_fts(ocr_blocks_fts, rowid, text, session_id) VALUES ('delete', old.id, old.text, old.session_id);
+  INSERT INTO ocr_blocks_fts(rowid, text, session_id) VALUES (new.id, new.text, new.session_id);
+END;
+
+INSERT INTO ocr_blocks_fts(ocr_blocks_fts) VALUES ('rebuild');
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS evidence_owners (
+  evidence_id TEXT PRIMARY KEY,
+  session_id TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS evidence_owner_heads (
+  session_id TEXT PRIMARY KEY,
+  indexed_seq INTEGER NOT NULL
+);
@@ -0,0 +1,72 @@
+use crate::storage::db::Storage;
+use crate::util::hash::{hex_decode, hex_encode};
+use crate::util::time::now_utc_iso;
+use ed25519_dalek::SigningKey;
+use rusqlite::params;
+use std::path::PathBuf;
+
+const SIGNING_KEY_FILE: &str = "manifest_signing.key";
+
+pub fn signing_key_path(storage: &Storage) -> PathBuf {
+    storage
+        .db_path
+        .parent()
+        .map(|p| p.to_path_buf())
+        .unwrap_or_default()
+        .join("keys")
+        .join(SIGNING_KEY_FILE)
+}
+
+/// Returns this workstation's manifest signing key, generating and persisting a
+/// new one on first use. The file holds the hex-encoded 32-byte secret seed.
+pub fn load_or_create_signing_key(storage: &Storage) -> anyhow::Result<SigningKey> {
+    let path = signing_key_path(storage);
+    if path.exists() {
+        let raw = std::fs::read_to_string(&path)?;
+        let seed: [u8; 32] = hex_decode(raw.trim())
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| anyhow::anyhow!("signing key file {} is corrupt", path.display()))?;
+        return Ok(SigningKey::from_bytes(&seed));
+    }
+
+    let key = SigningKey::generate(&mut rand_core::OsRng);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, hex_encode(&key.to_bytes()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o600))?;
+    }
+    std::fs::rename(&tmp, &path)?;
+    Ok(key)
+}
+
+pub fn public_key_hex(key: &SigningKey) -> String {
+    hex_encode(key.verifying_key().as_bytes())
+}
+
+pub fn list_trusted_keys(conn: &rusqlite::Connection) -> anyhow::Result<Vec<String>> {
+    let mut stmt =
+        conn.prepare("SELECT public_key FROM trusted_signing_keys ORDER BY public_key ASC")?;
+    let keys = stmt
+        .query_map([], |r| r.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+    Ok(keys)
+}
+
+pub fn set_trusted_keys(conn: &mut rusqlite::Connection, keys: &[String]) -> anyhow::Result<()> {
+    let tx = conn.transaction()?;
+    tx.execute("DELETE FROM trusted_signing_keys", [])?;
+    let added_at = now_utc_iso();
+    for key in keys {
+        tx.execute(
+            "INSERT OR IGNORE INTO trusted_signing_keys(public_key, added_at) VALUES (?1, ?2)",
+            params![key, added_at],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
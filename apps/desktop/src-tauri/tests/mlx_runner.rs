@@ -0,0 +1,197 @@
+//! Runs `mlx_run` against the `opscinema-mlx-stub` binary. Lives outside
+//! `src/tests.rs` because Cargo only exposes binary paths to integration tests.
+
+use opscinema_desktop_backend::api;
+use opscinema_desktop_backend::model_dock::adapters::mlx::RunnerEvent;
+use opscinema_desktop_backend::model_dock::adapters::mlx_stub;
+use opscinema_desktop_backend::storage::db::Storage;
+use opscinema_types::{
+    AppErrorCode, JobStatus, JobsCancelRequest, JobsGetRequest, JobsListRequest, MlxRunRequest,
+    ModelRegisterRequest, ModelRolesUpdate, ModelsListRequest,
+};
+use std::time::{Duration, Instant};
+
+fn run(
+    backend: &api::Backend,
+    model_id: &str,
+    prompt: &str,
+) -> opscinema_types::AppResult<uuid::Uuid> {
+    api::model_dock::mlx_run(
+        backend,
+        MlxRunRequest {
+            model_id: model_id.to_string(),
+            prompt: prompt.to_string(),
+        },
+    )
+    .map(|handle| handle.job_id)
+}
+
+fn latest_mlx_job(backend: &api::Backend, status: JobStatus) -> opscinema_types::JobDetail {
+    api::jobs::jobs_list(
+        backend,
+        JobsListRequest {
+            session_id: None,
+            status: Some(status),
+        },
+    )
+    .expect("jobs")
+    .jobs
+    .into_iter()
+    .find(|j| j.job_type == "mlx_run")
+    .expect("mlx job")
+}
+
+#[test]
+fn phase11_mlx_runner_streams_tokens_records_digest_and_cancels() {
+    std::env::set_var(
+        "OPSCINEMA_MLX_RUNNER",
+        env!("CARGO_BIN_EXE_opscinema-mlx-stub"),
+    );
+    std::env::remove_var("OPSCINEMA_MLX_STUB_TOKEN_DELAY_MS");
+    let backend = api::Backend::new(Storage::open_in_memory().expect("storage"));
+
+    let weights = "mlx-community/Qwen2.5-0.5B-Instruct-4bit";
+    let job_id = run(&backend, weights, "open the settings").expect("run");
+    let job = api::jobs::jobs_get(&backend, JobsGetRequest { job_id }).expect("job");
+    assert_eq!(job.status, JobStatus::Succeeded);
+    let progress = job.progress.expect("progress");
+    assert_eq!((progress.stage.as_str(), progress.pct), ("completed", 100));
+    assert_eq!(
+        progress.counters.done,
+        mlx_stub::tokens_for("open the settings").len() as u64
+    );
+
+    let model = api::model_dock::models_list(
+        &backend,
+        ModelsListRequest {
+            include_unhealthy: true,
+        },
+    )
+    .expect("models")
+    .models
+    .into_iter()
+    .find(|m| m.provider == "mlx")
+    .expect("mlx model registered");
+    assert_eq!(model.label, weights);
+    assert_eq!(
+        model.digest,
+        mlx_stub::digest_for(weights),
+        "digest from the runner"
+    );
+    run(&backend, &model.model_id, "again").expect("run registered model");
+    api::model_dock::model_roles_set(
+        &backend,
+        ModelRolesUpdate {
+            tutorial_generation: None,
+            screen_explainer: Some(model.model_id.clone()),
+            anchor_grounding: None,
+        },
+    )
+    .expect("roles");
+    let pins = api::model_dock::collect_model_pins(&backend).expect("pins");
+    assert_eq!(pins.len(), 1);
+    assert_eq!(pins[0].digest, model.digest);
+    assert!(api::model_dock::model_pin_warnings(&backend, &pins)
+        .expect("verify pins")
+        .is_empty());
+
+    let register = |digest: String| {
+        api::model_dock::models_register(
+            &backend,
+            ModelRegisterRequest {
+                provider: "mlx".to_string(),
+                label: "Qwen 0.5B".to_string(),
+                model_name: weights.to_string(),
+                digest,
+            },
+        )
+    };
+    let verified = register(String::new()).expect("digest from the runner");
+    assert_eq!(verified.digest, model.digest);
+    let wrong = register(mlx_stub::digest_for("other-weights")).expect_err("mismatch");
+    assert_eq!(wrong.code, AppErrorCode::ValidationFailed);
+
+    // Digest probes hash the weights without loading them.
+    let mut replies = Vec::new();
+    mlx_stub::serve(
+        std::io::Cursor::new(format!("{{\"op\":\"digest\",\"model\":\"{weights}\"}}\n")),
+        &mut replies,
+        Duration::ZERO,
+    )
+    .expect("serve");
+    let replies = String::from_utf8(replies).expect("utf8");
+    assert_eq!(
+        serde_json::from_str::<RunnerEvent>(replies.trim()).expect("one event"),
+        RunnerEvent::Digest {
+            model: weights.to_string(),
+            digest: model.digest.clone(),
+        }
+    );
+
+    let missing = run(
+        &backend,
+        &format!("{}x", mlx_stub::MISSING_MODEL_PREFIX),
+        "hi",
+    )
+    .expect_err("no weights");
+    assert_eq!(missing.code, AppErrorCode::NotFound);
+    assert_eq!(
+        latest_mlx_job(&backend, JobStatus::Failed)
+            .error
+            .map(|e| e.code),
+        Some(AppErrorCode::NotFound)
+    );
+
+    std::env::set_var("OPSCINEMA_MLX_STUB_TOKEN_DELAY_MS", "100");
+    let canceller = {
+        let backend = backend.clone();
+        std::thread::spawn(move || {
+            let deadline = Instant::now() + Duration::from_secs(10);
+            while Instant::now() < deadline {
+                let generating = api::jobs::jobs_list(
+                    &backend,
+                    JobsListRequest {
+                        session_id: None,
+                        status: Some(JobStatus::Running),
+                    },
+                )
+                .expect("jobs")
+                .jobs
+                .into_iter()
+                .find(|j| {
+                    j.job_type == "mlx_run"
+                        && j.progress.as_ref().is_some_and(|p| p.stage == "generate")
+                });
+                if let Some(job) = generating {
+                    api::jobs::jobs_cancel(&backend, JobsCancelRequest { job_id: job.job_id })
+                        .expect("cancel");
+                    return;
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        })
+    };
+    let started = Instant::now();
+    let long_prompt = "word ".repeat(100);
+    let cancelled = run(&backend, weights, &long_prompt).expect_err("cancelled");
+    canceller.join().expect("canceller");
+    assert_eq!(cancelled.code, AppErrorCode::JobCancelled);
+    assert!(
+        started.elapsed() < Duration::from_secs(5),
+        "stopped mid-stream"
+    );
+    let job = latest_mlx_job(&backend, JobStatus::Cancelled);
+    assert_eq!(job.progress.map(|p| p.stage), Some("generate".to_string()));
+
+    std::env::set_var("OPSCINEMA_MLX_STUB_TOKEN_DELAY_MS", "500");
+    std::env::set_var("OPSCINEMA_MLX_TIMEOUT_MS", "100");
+    let timed_out = run(&backend, weights, "slow").expect_err("idle runner");
+    assert_eq!(timed_out.code, AppErrorCode::Io);
+    std::env::remove_var("OPSCINEMA_MLX_TIMEOUT_MS");
+    std::env::remove_var("OPSCINEMA_MLX_STUB_TOKEN_DELAY_MS");
+
+    std::env::set_var("OPSCINEMA_MLX_RUNNER", "/nonexistent/mlx-runner");
+    let spawn = run(&backend, weights, "hi").expect_err("no runner");
+    assert_eq!(spawn.code, AppErrorCode::Io);
+    std::env::remove_var("OPSCINEMA_MLX_RUNNER");
+}
@@ -17,6 +17,7 @@ const NO_REQ_COMMANDS = new Set<IpcCommand>([
   'capture_get_config',
   'model_roles_get',
   'agent_pipelines_list',
+  'export_trusted_keys_get',
 ]);
 
 export function setIpcRuntimeInvoke(invokeFn: InvokeFn): void {
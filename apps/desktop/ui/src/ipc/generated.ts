@@ -31,6 +31,8 @@ export type IpcCommand =
   'session_list' |
   'session_get' |
   'session_close' |
+  'session_export_archive' |
+  'session_import_archive' |
   'timeline_get_keyframes' |
   'timeline_get_events' |
   'timeline_get_thumbnail' |
@@ -47,6 +49,11 @@ export type IpcCommand =
   'evidence_for_step' |
   'evidence_find_text' |
   'evidence_get_coverage' |
+  'evidence_get_grounding' |
+  'evidence_graph_neighbors' |
+  'evidence_graph_path' |
+  'evidence_graph_supports' |
+  'search_global' |
   'steps_generate_candidates' |
   'steps_list' |
   'steps_get' |
@@ -84,6 +91,10 @@ export type IpcCommand =
   'agent_pipeline_report' |
   'exports_list' |
   'export_verify_bundle' |
+  'export_trusted_keys_get' |
+  'export_trusted_keys_set' |
+  'redaction_rules_get' |
+  'redaction_rules_set' |
   'jobs_list' |
   'jobs_get' |
   'jobs_cancel';
@@ -96,18 +107,20 @@ export interface IpcRequestMap {
   'app_get_build_info': Record<string, never>;
   'app_get_permissions_status': Record<string, never>;
   'settings_get': Record<string, never>;
-  'settings_set': { offline_mode: boolean; allow_input_capture: boolean; allow_window_metadata: boolean };
+  'settings_set': { offline_mode: boolean; allow_input_capture: boolean; allow_window_metadata: boolean; ocr_provider?: 'auto' | 'stub' | 'apple_vision' | 'tesseract' };
   'network_allowlist_get': Record<string, never>;
   'network_allowlist_set': { entries: string[] };
   'session_create': { label: string; metadata: Record<string, string> };
   'session_list': { limit?: number };
   'session_get': { session_id: string };
   'session_close': { session_id: string };
+  'session_export_archive': { session_id: string; output_dir: string };
+  'session_import_archive': { archive_path: string };
   'timeline_get_keyframes': { session_id: string; start_ms: number; end_ms: number };
   'timeline_get_events': { session_id: string; after_seq?: number; limit?: number };
   'timeline_get_thumbnail': { session_id: string; frame_event_id: string };
   'capture_get_config': Record<string, never>;
-  'capture_set_config': { keyframe_interval_ms: number; include_input: boolean; include_window_meta: boolean };
+  'capture_set_config': { keyframe_interval_ms: number; include_input: boolean; include_window_meta: boolean; dedup_max_hash_distance?: number };
   'capture_start': { session_id: string };
   'capture_stop': { session_id: string };
   'capture_get_status': { session_id?: string };
@@ -119,6 +132,11 @@ export interface IpcRequestMap {
   'evidence_for_step': { session_id: string; step_id: string };
   'evidence_find_text': { session_id: string; query: string };
   'evidence_get_coverage': { session_id: string };
+  'evidence_get_grounding': { session_id: string };
+  'evidence_graph_neighbors': { session_id: string; node_id: string; direction?: 'outgoing' | 'incoming' | 'both'; edge_types?: Array<'derived_from' | 'located_in' | 'cites' | 'verifies'> };
+  'evidence_graph_path': { session_id: string; from_node_id: string; to_node_id: string; max_depth?: number };
+  'evidence_graph_supports': { session_id: string; step_id: string; block_id: string };
+  'search_global': { query: string; created_after?: string; created_before?: string; bundle_id?: string; offset?: number; limit?: number };
   'steps_generate_candidates': { session_id: string };
   'steps_list': { session_id: string };
   'steps_get': { session_id: string; step_id: string };
@@ -156,6 +174,10 @@ export interface IpcRequestMap {
   'agent_pipeline_report': { run_id: string };
   'exports_list': { session_id?: string };
   'export_verify_bundle': { bundle_path: string };
+  'export_trusted_keys_get': Record<string, never>;
+  'export_trusted_keys_set': { trusted_public_keys: string[] };
+  'redaction_rules_get': { session_id: string };
+  'redaction_rules_set': { session_id: string; rules: Array<{ kind: 'pii'; detector: 'email' | 'ipv4' | 'ipv6' | 'aws_key' | 'jwt' | 'credit_card' } | { kind: 'regex'; pattern: string; label?: string } | { kind: 'region'; bbox_norm: { x: number; y: number; w: number; h: number }; frame_ms?: number; label?: string }> };
   'jobs_list': { session_id?: string; status?: 'QUEUED' | 'RUNNING' | 'SUCCEEDED' | 'FAILED' | 'CANCELLED' };
   'jobs_get': { job_id: string };
   'jobs_cancel': { job_id: string };
@@ -164,30 +186,37 @@ export interface IpcRequestMap {
 export interface IpcResponseMap {
   'app_get_build_info': { app_name: string; app_version: string; commit: string; built_at: string };
   'app_get_permissions_status': { screen_recording: boolean; accessibility: boolean; full_disk_access: boolean };
-  'settings_get': { offline_mode: boolean; allow_input_capture: boolean; allow_window_metadata: boolean };
-  'settings_set': { offline_mode: boolean; allow_input_capture: boolean; allow_window_metadata: boolean };
+  'settings_get': { offline_mode: boolean; allow_input_capture: boolean; allow_window_metadata: boolean; ocr_provider: 'auto' | 'stub' | 'apple_vision' | 'tesseract' };
+  'settings_set': { offline_mode: boolean; allow_input_capture: boolean; allow_window_metadata: boolean; ocr_provider: 'auto' | 'stub' | 'apple_vision' | 'tesseract' };
   'network_allowlist_get': { entries: string[] };
   'network_allowlist_set': { entries: string[] };
   'session_create': { session_id: string; label: string; created_at: string; closed_at?: string; head_seq: number; head_hash: string };
   'session_list': Array<{ session_id: string; label: string; created_at: string; closed_at?: string; head_seq: number; head_hash: string }>;
   'session_get': { summary: { session_id: string; label: string; created_at: string; closed_at?: string; head_seq: number; head_hash: string }; metadata: Record<string, string> };
   'session_close': { session_id: string; label: string; created_at: string; closed_at?: string; head_seq: number; head_hash: string };
+  'session_export_archive': { session_id: string; output_path: string; event_count: number; asset_count: number; head_seq: number; head_hash: string; archive_hash: string };
+  'session_import_archive': { session_id: string; output_path: string; event_count: number; asset_count: number; head_seq: number; head_hash: string; archive_hash: string };
   'timeline_get_keyframes': { keyframes: Array<{ frame_event_id: string; frame_ms: number; asset: { asset_id: string } }> };
   'timeline_get_events': { events: Array<{ seq: number; event_id: string; event_type: string; frame_ms?: number }> };
   'timeline_get_thumbnail': { asset_id: string };
-  'capture_get_config': { keyframe_interval_ms: number; include_input: boolean; include_window_meta: boolean };
-  'capture_set_config': { keyframe_interval_ms: number; include_input: boolean; include_window_meta: boolean };
+  'capture_get_config': { keyframe_interval_ms: number; include_input: boolean; include_window_meta: boolean; dedup_max_hash_distance?: number };
+  'capture_set_config': { keyframe_interval_ms: number; include_input: boolean; include_window_meta: boolean; dedup_max_hash_distance?: number };
   'capture_start': { state: 'IDLE' | 'CAPTURING' | 'STOPPED'; session_id?: string; started_at?: string };
   'capture_stop': { state: 'IDLE' | 'CAPTURING' | 'STOPPED'; session_id?: string; started_at?: string };
   'capture_get_status': { state: 'IDLE' | 'CAPTURING' | 'STOPPED'; session_id?: string; started_at?: string };
   'ocr_schedule': { job_id: string };
   'ocr_get_status': { queued_frames: number; indexed_frames: number };
-  'ocr_search': { hits: Array<{ frame_ms: number; block_id: string; snippet: string }> };
+  'ocr_search': { hits: Array<{ frame_ms: number; block_id: string; bbox_norm: { x: number; y: number; w: number; h: number }; snippet: string; highlights: Array<{ start: number; end: number }> }> };
   'ocr_get_blocks_for_frame': { blocks: Array<{ ocr_block_id: string; bbox_norm: { x: number; y: number; w: number; h: number }; text: string; confidence: number; language?: string }> };
   'evidence_for_time_range': { evidence: Array<{ evidence_id: string; kind: string; source_id: string; locators: Array<{ locator_type: string; asset_id?: string; frame_ms?: number; bbox_norm?: { x: number; y: number; w: number; h: number }; text_offset?: { start: number; end: number }; note?: string }> }> };
   'evidence_for_step': { evidence: Array<{ evidence_id: string; kind: string; source_id: string; locators: Array<{ locator_type: string; asset_id?: string; frame_ms?: number; bbox_norm?: { x: number; y: number; w: number; h: number }; text_offset?: { start: number; end: number }; note?: string }> }> };
   'evidence_find_text': { evidence: Array<{ evidence_id: string; kind: string; source_id: string; locators: Array<{ locator_type: string; asset_id?: string; frame_ms?: number; bbox_norm?: { x: number; y: number; w: number; h: number }; text_offset?: { start: number; end: number }; note?: string }> }> };
-  'evidence_get_coverage': { missing_step_ids: string[]; missing_generated_block_ids: string[]; pass: boolean };
+  'evidence_get_coverage': { missing_step_ids: string[]; missing_generated_block_ids: string[]; invalid_refs: Array<{ step_id: string; block_id: string; evidence_id: string; kind: 'dangling' | 'other_session' | 'invalidated'; owner_session_id?: string }>; pass: boolean };
+  'evidence_get_grounding': { blocks: Array<{ step_id: string; block_id: string; score: number; claims: string[]; ungrounded_claims: string[] }>; pass: boolean };
+  'evidence_graph_neighbors': { nodes: Array<{ node_id: string; node_type: 'evidence' | 'step_block'; evidence?: { evidence_id: string; kind: string; source_id: string; locators: Array<{ locator_type: string; asset_id?: string; frame_ms?: number; bbox_norm?: { x: number; y: number; w: number; h: number }; text_offset?: { start: number; end: number }; note?: string }> }; step_id?: string; block_id?: string }>; edges: Array<{ from: string; to: string; edge_type: 'derived_from' | 'located_in' | 'cites' | 'verifies' }> };
+  'evidence_graph_path': { nodes: Array<{ node_id: string; node_type: 'evidence' | 'step_block'; evidence?: { evidence_id: string; kind: string; source_id: string; locators: Array<{ locator_type: string; asset_id?: string; frame_ms?: number; bbox_norm?: { x: number; y: number; w: number; h: number }; text_offset?: { start: number; end: number }; note?: string }> }; step_id?: string; block_id?: string }>; edges: Array<{ from: string; to: string; edge_type: 'derived_from' | 'located_in' | 'cites' | 'verifies' }> };
+  'evidence_graph_supports': { nodes: Array<{ node_id: string; node_type: 'evidence' | 'step_block'; evidence?: { evidence_id: string; kind: string; source_id: string; locators: Array<{ locator_type: string; asset_id?: string; frame_ms?: number; bbox_norm?: { x: number; y: number; w: number; h: number }; text_offset?: { start: number; end: number }; note?: string }> }; step_id?: string; block_id?: string }>; edges: Array<{ from: string; to: string; edge_type: 'derived_from' | 'located_in' | 'cites' | 'verifies' }> };
+  'search_global': { groups: Array<{ session: { session_id: string; label: string; created_at: string; closed_at?: string; head_seq: number; head_hash: string }; hit_count: number; hits: Array<{ kind: 'session_label' | 'session_metadata' | 'step_title' | 'step_body' | 'window_title' | 'ocr_text'; ref_id?: string; frame_ms?: number; bundle_id?: string; snippet: string; highlights: Array<{ start: number; end: number }> }> }>; total_sessions: number; next_offset?: number };
   'steps_generate_candidates': { job_id: string };
   'steps_list': { steps: Array<{ step_id: string; title: string; order_index: number; body: { blocks: Array<{ block_id: string; text: string; provenance: 'human' | 'generated'; evidence_refs: string[] }> }; risk_tags: string[]; branch_label?: string }>; head_seq: number };
   'steps_get': { step: { step_id: string; title: string; order_index: number; body: { blocks: Array<{ block_id: string; text: string; provenance: 'human' | 'generated'; evidence_refs: string[] }> }; risk_tags: string[]; branch_label?: string }; anchors: Array<{ anchor_id: string; step_id: string; kind: string; target_signature: string; confidence: number; degraded: boolean; locators: Array<{ locator_type: string; asset_id?: string; frame_ms?: number; bbox_norm?: { x: number; y: number; w: number; h: number }; text_offset?: { start: number; end: number }; note?: string }> }> };
@@ -209,8 +238,8 @@ export interface IpcResponseMap {
   'verifier_list': { verifiers: Array<{ verifier_id: string; kind: string; timeout_secs: number; command_allowlist: string[] }> };
   'verifier_run': { job_id: string };
   'verifier_get_result': { run_id: string; verifier_id: string; status: string; result_asset: { asset_id: string }; logs_asset?: { asset_id: string } };
-  'models_list': { models: Array<{ model_id: string; provider: string; label: string; digest: string }> };
-  'models_register': { model_id: string; provider: string; label: string; digest: string };
+  'models_list': { models: Array<{ model_id: string; provider: string; label: string; model_name: string; digest: string }> };
+  'models_register': { model_id: string; provider: string; label: string; model_name: string; digest: string };
   'models_remove': { removed: boolean };
   'model_roles_get': { tutorial_generation?: string; screen_explainer?: string; anchor_grounding?: string };
   'model_roles_set': { tutorial_generation?: string; screen_explainer?: string; anchor_grounding?: string };
@@ -219,12 +248,16 @@ export interface IpcResponseMap {
   'ollama_run': { job_id: string };
   'mlx_run': { job_id: string };
   'bench_run': { job_id: string };
-  'bench_list': { benches: Array<{ bench_id: string; model_id: string; score: number; created_at: string }> };
+  'bench_list': { benches: Array<{ bench_id: string; model_id: string; benchmark: string; score: number; metrics?: { cases: number; schema_valid_pct: number; citation_valid_pct: number; grounding_pct: number; mean_latency_ms: number; max_latency_ms: number }; report_asset_id?: string; created_at: string }> };
   'agent_pipelines_list': { pipelines: string[] };
   'agent_pipeline_run': { job_id: string };
   'agent_pipeline_report': { run_id: string; diagnostics: string[] };
   'exports_list': { exports: Array<{ export_id: string; output_path: string; bundle_hash: string; warnings: Array<{ code: string; message: string }> }> };
-  'export_verify_bundle': { valid: boolean; issues: string[] };
+  'export_verify_bundle': { valid: boolean; issues: string[]; signature: 'unsigned' | 'invalid' | 'untrusted' | 'trusted'; signer_public_key?: string };
+  'export_trusted_keys_get': { local_public_key: string; trusted_public_keys: string[] };
+  'export_trusted_keys_set': { local_public_key: string; trusted_public_keys: string[] };
+  'redaction_rules_get': { session_id: string; rules: Array<{ kind: 'pii'; detector: 'email' | 'ipv4' | 'ipv6' | 'aws_key' | 'jwt' | 'credit_card' } | { kind: 'regex'; pattern: string; label?: string } | { kind: 'region'; bbox_norm: { x: number; y: number; w: number; h: number }; frame_ms?: number; label?: string }>; is_default: boolean };
+  'redaction_rules_set': { session_id: string; rules: Array<{ kind: 'pii'; detector: 'email' | 'ipv4' | 'ipv6' | 'aws_key' | 'jwt' | 'credit_card' } | { kind: 'regex'; pattern: string; label?: string } | { kind: 'region'; bbox_norm: { x: number; y: number; w: number; h: number }; frame_ms?: number; label?: string }>; is_default: boolean };
   'jobs_list': { jobs: Array<{ job_id: string; job_type: string; session_id?: string; status: 'QUEUED' | 'RUNNING' | 'SUCCEEDED' | 'FAILED' | 'CANCELLED'; created_at: string; started_at?: string; ended_at?: string; progress?: { stage: string; pct: number; counters: { done: number; total: number } }; error?: { code: string; message: string; details?: string; recoverable: boolean; action_hint?: string } }> };
   'jobs_get': { job_id: string; job_type: string; session_id?: string; status: 'QUEUED' | 'RUNNING' | 'SUCCEEDED' | 'FAILED' | 'CANCELLED'; created_at: string; started_at?: string; ended_at?: string; progress?: { stage: string; pct: number; counters: { done: number; total: number } }; error?: { code: string; message: string; details?: string; recoverable: boolean; action_hint?: string } };
   'jobs_cancel': { accepted: boolean };
@@ -241,6 +274,8 @@ export interface GeneratedIpcClient {
   session_list(payload: IpcRequestMap['session_list']): Promise<AppResult<IpcResponseMap['session_list']>>;
   session_get(payload: IpcRequestMap['session_get']): Promise<AppResult<IpcResponseMap['session_get']>>;
   session_close(payload: IpcRequestMap['session_close']): Promise<AppResult<IpcResponseMap['session_close']>>;
+  session_export_archive(payload: IpcRequestMap['session_export_archive']): Promise<AppResult<IpcResponseMap['session_export_archive']>>;
+  session_import_archive(payload: IpcRequestMap['session_import_archive']): Promise<AppResult<IpcResponseMap['session_import_archive']>>;
   timeline_get_keyframes(payload: IpcRequestMap['timeline_get_keyframes']): Promise<AppResult<IpcResponseMap['timeline_get_keyframes']>>;
   timeline_get_events(payload: IpcRequestMap['timeline_get_events']): Promise<AppResult<IpcResponseMap['timeline_get_events']>>;
   timeline_get_thumbnail(payload: IpcRequestMap['timeline_get_thumbnail']): Promise<AppResult<IpcResponseMap['timeline_get_thumbnail']>>;
@@ -257,6 +292,11 @@ export interface GeneratedIpcClient {
   evidence_for_step(payload: IpcRequestMap['evidence_for_step']): Promise<AppResult<IpcResponseMap['evidence_for_step']>>;
   evidence_find_text(payload: IpcRequestMap['evidence_find_text']): Promise<AppResult<IpcResponseMap['evidence_find_text']>>;
   evidence_get_coverage(payload: IpcRequestMap['evidence_get_coverage']): Promise<AppResult<IpcResponseMap['evidence_get_coverage']>>;
+  evidence_get_grounding(payload: IpcRequestMap['evidence_get_grounding']): Promise<AppResult<IpcResponseMap['evidence_get_grounding']>>;
+  evidence_graph_neighbors(payload: IpcRequestMap['evidence_graph_neighbors']): Promise<AppResult<IpcResponseMap['evidence_graph_neighbors']>>;
+  evidence_graph_path(payload: IpcRequestMap['evidence_graph_path']): Promise<AppResult<IpcResponseMap['evidence_graph_path']>>;
+  evidence_graph_supports(payload: IpcRequestMap['evidence_graph_supports']): Promise<AppResult<IpcResponseMap['evidence_graph_supports']>>;
+  search_global(payload: IpcRequestMap['search_global']): Promise<AppResult<IpcResponseMap['search_global']>>;
   steps_generate_candidates(payload: IpcRequestMap['steps_generate_candidates']): Promise<AppResult<IpcResponseMap['steps_generate_candidates']>>;
   steps_list(payload: IpcRequestMap['steps_list']): Promise<AppResult<IpcResponseMap['steps_list']>>;
   steps_get(payload: IpcRequestMap['steps_get']): Promise<AppResult<IpcResponseMap['steps_get']>>;
@@ -294,6 +334,10 @@ export interface GeneratedIpcClient {
   agent_pipeline_report(payload: IpcRequestMap['agent_pipeline_report']): Promise<AppResult<IpcResponseMap['agent_pipeline_report']>>;
   exports_list(payload: IpcRequestMap['exports_list']): Promise<AppResult<IpcResponseMap['exports_list']>>;
   export_verify_bundle(payload: IpcRequestMap['export_verify_bundle']): Promise<AppResult<IpcResponseMap['export_verify_bundle']>>;
+  export_trusted_keys_get(payload: IpcRequestMap['export_trusted_keys_get']): Promise<AppResult<IpcResponseMap['export_trusted_keys_get']>>;
+  export_trusted_keys_set(payload: IpcRequestMap['export_trusted_keys_set']): Promise<AppResult<IpcResponseMap['export_trusted_keys_set']>>;
+  redaction_rules_get(payload: IpcRequestMap['redaction_rules_get']): Promise<AppResult<IpcResponseMap['redaction_rules_get']>>;
+  redaction_rules_set(payload: IpcRequestMap['redaction_rules_set']): Promise<AppResult<IpcResponseMap['redaction_rules_set']>>;
   jobs_list(payload: IpcRequestMap['jobs_list']): Promise<AppResult<IpcResponseMap['jobs_list']>>;
   jobs_get(payload: IpcRequestMap['jobs_get']): Promise<AppResult<IpcResponseMap['jobs_get']>>;
   jobs_cancel(payload: IpcRequestMap['jobs_cancel']): Promise<AppResult<IpcResponseMap['jobs_cancel']>>;
@@ -311,6 +355,8 @@ export function bindGeneratedClient(client: IpcClient): GeneratedIpcClient {
     session_list: (payload: IpcRequestMap['session_list']) => client.invoke<IpcRequestMap['session_list'], IpcResponseMap['session_list']>('session_list', payload),
     session_get: (payload: IpcRequestMap['session_get']) => client.invoke<IpcRequestMap['session_get'], IpcResponseMap['session_get']>('session_get', payload),
     session_close: (payload: IpcRequestMap['session_close']) => client.invoke<IpcRequestMap['session_close'], IpcResponseMap['session_close']>('session_close', payload),
+    session_export_archive: (payload: IpcRequestMap['session_export_archive']) => client.invoke<IpcRequestMap['session_export_archive'], IpcResponseMap['session_export_archive']>('session_export_archive', payload),
+    session_import_archive: (payload: IpcRequestMap['session_import_archive']) => client.invoke<IpcRequestMap['session_import_archive'], IpcResponseMap['session_import_archive']>('session_import_archive', payload),
     timeline_get_keyframes: (payload: IpcRequestMap['timeline_get_keyframes']) => client.invoke<IpcRequestMap['timeline_get_keyframes'], IpcResponseMap['timeline_get_keyframes']>('timeline_get_keyframes', payload),
     timeline_get_events: (payload: IpcRequestMap['timeline_get_events']) => client.invoke<IpcRequestMap['timeline_get_events'], IpcResponseMap['timeline_get_events']>('timeline_get_events', payload),
     timeline_get_thumbnail: (payload: IpcRequestMap['timeline_get_thumbnail']) => client.invoke<IpcRequestMap['timeline_get_thumbnail'], IpcResponseMap['timeline_get_thumbnail']>('timeline_get_thumbnail', payload),
@@ -327,6 +373,11 @@ export function bindGeneratedClient(client: IpcClient): GeneratedIpcClient {
     evidence_for_step: (payload: IpcRequestMap['evidence_for_step']) => client.invoke<IpcRequestMap['evidence_for_step'], IpcResponseMap['evidence_for_step']>('evidence_for_step', payload),
     evidence_find_text: (payload: IpcRequestMap['evidence_find_text']) => client.invoke<IpcRequestMap['evidence_find_text'], IpcResponseMap['evidence_find_text']>('evidence_find_text', payload),
     evidence_get_coverage: (payload: IpcRequestMap['evidence_get_coverage']) => client.invoke<IpcRequestMap['evidence_get_coverage'], IpcResponseMap['evidence_get_coverage']>('evidence_get_coverage', payload),
+    evidence_get_grounding: (payload: IpcRequestMap['evidence_get_grounding']) => client.invoke<IpcRequestMap['evidence_get_grounding'], IpcResponseMap['evidence_get_grounding']>('evidence_get_grounding', payload),
+    evidence_graph_neighbors: (payload: IpcRequestMap['evidence_graph_neighbors']) => client.invoke<IpcRequestMap['evidence_graph_neighbors'], IpcResponseMap['evidence_graph_neighbors']>('evidence_graph_neighbors', payload),
+    evidence_graph_path: (payload: IpcRequestMap['evidence_graph_path']) => client.invoke<IpcRequestMap['evidence_graph_path'], IpcResponseMap['evidence_graph_path']>('evidence_graph_path', payload),
+    evidence_graph_supports: (payload: IpcRequestMap['evidence_graph_supports']) => client.invoke<IpcRequestMap['evidence_graph_supports'], IpcResponseMap['evidence_graph_supports']>('evidence_graph_supports', payload),
+    search_global: (payload: IpcRequestMap['search_global']) => client.invoke<IpcRequestMap['search_global'], IpcResponseMap['search_global']>('search_global', payload),
     steps_generate_candidates: (payload: IpcRequestMap['steps_generate_candidates']) => client.invoke<IpcRequestMap['steps_generate_candidates'], IpcResponseMap['steps_generate_candidates']>('steps_generate_candidates', payload),
     steps_list: (payload: IpcRequestMap['steps_list']) => client.invoke<IpcRequestMap['steps_list'], IpcResponseMap['steps_list']>('steps_list', payload),
     steps_get: (payload: IpcRequestMap['steps_get']) => client.invoke<IpcRequestMap['steps_get'], IpcResponseMap['steps_get']>('steps_get', payload),
@@ -364,6 +415,10 @@ export function bindGeneratedClient(client: IpcClient): GeneratedIpcClient {
     agent_pipeline_report: (payload: IpcRequestMap['agent_pipeline_report']) => client.invoke<IpcRequestMap['agent_pipeline_report'], IpcResponseMap['agent_pipeline_report']>('agent_pipeline_report', payload),
     exports_list: (payload: IpcRequestMap['exports_list']) => client.invoke<IpcRequestMap['exports_list'], IpcResponseMap['exports_list']>('exports_list', payload),
     export_verify_bundle: (payload: IpcRequestMap['export_verify_bundle']) => client.invoke<IpcRequestMap['export_verify_bundle'], IpcResponseMap['export_verify_bundle']>('export_verify_bundle', payload),
+    export_trusted_keys_get: (payload: IpcRequestMap['export_trusted_keys_get']) => client.invoke<IpcRequestMap['export_trusted_keys_get'], IpcResponseMap['export_trusted_keys_get']>('export_trusted_keys_get', payload),
+    export_trusted_keys_set: (payload: IpcRequestMap['export_trusted_keys_set']) => client.invoke<IpcRequestMap['export_trusted_keys_set'], IpcResponseMap['export_trusted_keys_set']>('export_trusted_keys_set', payload),
+    redaction_rules_get: (payload: IpcRequestMap['redaction_rules_get']) => client.invoke<IpcRequestMap['redaction_rules_get'], IpcResponseMap['redaction_rules_get']>('redaction_rules_get', payload),
+    redaction_rules_set: (payload: IpcRequestMap['redaction_rules_set']) => client.invoke<IpcRequestMap['redaction_rules_set'], IpcResponseMap['redaction_rules_set']>('redaction_rules_set', payload),
     jobs_list: (payload: IpcRequestMap['jobs_list']) => client.invoke<IpcRequestMap['jobs_list'], IpcResponseMap['jobs_list']>('jobs_list', payload),
     jobs_get: (payload: IpcRequestMap['jobs_get']) => client.invoke<IpcRequestMap['jobs_get'], IpcResponseMap['jobs_get']>('jobs_get', payload),
     jobs_cancel: (payload: IpcRequestMap['jobs_cancel']) => client.invoke<IpcRequestMap['jobs_cancel'], IpcResponseMap['jobs_cancel']>('jobs_cancel', payload),
@@ -1,5 +1,6 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -27,6 +28,10 @@ pub struct PolicyAttestations {
     pub evidence_coverage_passed: bool,
     pub tutorial_strict_passed: bool,
     pub offline_policy_enforced: bool,
+    /// blake3 of the canonical redaction rules the bundle was scrubbed with;
+    /// absent on bundles exported before redaction existed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redaction_rules_hash: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
@@ -36,6 +41,15 @@ pub struct ModelPin {
     pub digest: String,
 }
 
+/// Ed25519 signature over the canonical manifest JSON with `signature` omitted.
+/// Keys and signatures are lowercase hex.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct ManifestSignature {
+    pub algorithm: String,
+    pub public_key: String,
+    pub signature: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct ExportManifestV1 {
     pub manifest_version: u32,
@@ -48,8 +62,170 @@ pub struct ExportManifestV1 {
     pub model_pins: Vec<ModelPin>,
     pub manifest_hash: String,
     pub bundle_hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<ManifestSignature>,
+}
+
+/// File entry of a v2 manifest. `asset_id` names the asset store entry the file
+/// was copied from, and `evidence_ids` lists the evidence the file backs.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct ManifestFileEntryV2 {
+    pub path: String,
+    pub hash_blake3: String,
+    pub size_bytes: u64,
+    pub media_type: String,
+    pub asset_id: Option<String>,
+    pub evidence_ids: Vec<String>,
+}
+
+/// Inclusive range of session event seqs an export was built from.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct EventSeqRange {
+    pub first_seq: i64,
+    pub last_seq: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct ExportManifestV2 {
+    pub manifest_version: u32,
+    pub bundle_type: BundleType,
+    pub session_id: String,
+    pub created_at_utc: String,
+    pub source_seq_range: EventSeqRange,
+    pub files: Vec<ManifestFileEntryV2>,
+    pub warnings: Vec<ManifestWarning>,
+    pub policy: PolicyAttestations,
+    pub model_pins: Vec<ModelPin>,
+    pub manifest_hash: String,
+    pub bundle_hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<ManifestSignature>,
+}
+
+/// Any readable export manifest. Parse with [`ExportManifest::from_json`], which
+/// dispatches on `manifest_version`; unknown versions fall back to v1 so the
+/// verifier can still report them.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum ExportManifest {
+    V1(ExportManifestV1),
+    V2(ExportManifestV2),
+}
+
+#[derive(Deserialize)]
+struct ManifestVersionProbe {
+    manifest_version: u32,
+}
+
+impl ExportManifest {
+    pub fn from_json(raw: &str) -> serde_json::Result<Self> {
+        let probe: ManifestVersionProbe = serde_json::from_str(raw)?;
+        match probe.manifest_version {
+            2 => Ok(Self::V2(serde_json::from_str(raw)?)),
+            _ => Ok(Self::V1(serde_json::from_str(raw)?)),
+        }
+    }
+
+    pub fn manifest_version(&self) -> u32 {
+        match self {
+            Self::V1(m) => m.manifest_version,
+            Self::V2(m) => m.manifest_version,
+        }
+    }
+
+    pub fn bundle_type(&self) -> &BundleType {
+        match self {
+            Self::V1(m) => &m.bundle_type,
+            Self::V2(m) => &m.bundle_type,
+        }
+    }
+
+    pub fn warnings(&self) -> &[ManifestWarning] {
+        match self {
+            Self::V1(m) => &m.warnings,
+            Self::V2(m) => &m.warnings,
+        }
+    }
+
+    pub fn policy(&self) -> &PolicyAttestations {
+        match self {
+            Self::V1(m) => &m.policy,
+            Self::V2(m) => &m.policy,
+        }
+    }
+
+    pub fn bundle_hash(&self) -> &str {
+        match self {
+            Self::V1(m) => &m.bundle_hash,
+            Self::V2(m) => &m.bundle_hash,
+        }
+    }
+
+    /// `(path, hash_blake3)` for every declared file, in manifest order.
+    pub fn file_hashes(&self) -> Vec<(&str, &str)> {
+        match self {
+            Self::V1(m) => m
+                .files
+                .iter()
+                .map(|f| (f.path.as_str(), f.hash_blake3.as_str()))
+                .collect(),
+            Self::V2(m) => m
+                .files
+                .iter()
+                .map(|f| (f.path.as_str(), f.hash_blake3.as_str()))
+                .collect(),
+        }
+    }
+
+    pub fn signature(&self) -> Option<&ManifestSignature> {
+        match self {
+            Self::V1(m) => m.signature.as_ref(),
+            Self::V2(m) => m.signature.as_ref(),
+        }
+    }
+
+    pub fn set_signature(&mut self, signature: Option<ManifestSignature>) {
+        match self {
+            Self::V1(m) => m.signature = signature,
+            Self::V2(m) => m.signature = signature,
+        }
+    }
+}
+
+impl From<ExportManifestV1> for ExportManifest {
+    fn from(manifest: ExportManifestV1) -> Self {
+        Self::V1(manifest)
+    }
+}
+
+impl From<ExportManifestV2> for ExportManifest {
+    fn from(manifest: ExportManifestV2) -> Self {
+        Self::V2(manifest)
+    }
 }
 
+/// Manifest for a portable `.opscinema` session archive: the raw event log plus
+/// the assets it references, pinned to the hash chain head at export time.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct SessionArchiveManifestV1 {
+    pub archive_version: u32,
+    pub session_id: String,
+    pub label: String,
+    pub session_created_at_utc: String,
+    pub session_closed_at_utc: Option<String>,
+    /// Absent in archives written before session metadata was persisted.
+    #[serde(default)]
+    pub session_metadata: BTreeMap<String, String>,
+    pub created_at_utc: String,
+    pub event_count: u64,
+    pub head_seq: i64,
+    pub head_hash: String,
+    pub files: Vec<ManifestFileEntry>,
+    pub archive_hash: String,
+}
+
+pub const SIGNATURE_ALGORITHM_ED25519: &str = "ed25519";
+
 pub fn compute_bundle_hash(entries_sorted: &[(String, String)]) -> String {
     let mut s = String::new();
     for (path, hash) in entries_sorted {
@@ -78,11 +78,11 @@ fn command_types(command: &str) -> (&'static str, &'static str) {
         ),
         "settings_get" => (
             "Record<string, never>",
-            "{ offline_mode: boolean; allow_input_capture: boolean; allow_window_metadata: boolean }",
+            "{ offline_mode: boolean; allow_input_capture: boolean; allow_window_metadata: boolean; ocr_provider: 'auto' | 'stub' | 'apple_vision' | 'tesseract' }",
         ),
         "settings_set" => (
-            "{ offline_mode: boolean; allow_input_capture: boolean; allow_window_metadata: boolean }",
-            "{ offline_mode: boolean; allow_input_capture: boolean; allow_window_metadata: boolean }",
+            "{ offline_mode: boolean; allow_input_capture: boolean; allow_window_metadata: boolean; ocr_provider?: 'auto' | 'stub' | 'apple_vision' | 'tesseract' }",
+            "{ offline_mode: boolean; allow_input_capture: boolean; allow_window_metadata: boolean; ocr_provider: 'auto' | 'stub' | 'apple_vision' | 'tesseract' }",
         ),
         "network_allowlist_get" => ("Record<string, never>", "{ entries: string[] }"),
         "network_allowlist_set" => ("{ entries: string[] }", "{ entries: string[] }"),
@@ -102,6 +102,14 @@ fn command_types(command: &str) -> (&'static str, &'static str) {
             "{ session_id: string }",
             "{ session_id: string; label: string; created_at: string; closed_at?: string; head_seq: number; head_hash: string }",
         ),
+        "session_export_archive" => (
+            "{ session_id: string; output_dir: string }",
+            "{ session_id: string; output_path: string; event_count: number; asset_count: number; head_seq: number; head_hash: string; archive_hash: string }",
+        ),
+        "session_import_archive" => (
+            "{ archive_path: string }",
+            "{ session_id: string; output_path: string; event_count: number; asset_count: number; head_seq: number; head_hash: string; archive_hash: string }",
+        ),
         "timeline_get_keyframes" => (
             "{ session_id: string; start_ms: number; end_ms: number }",
             "{ keyframes: Array<{ frame_event_id: string; frame_ms: number; asset: { asset_id: string } }> }",
@@ -113,11 +121,11 @@ fn command_types(command: &str) -> (&'static str, &'static str) {
         "timeline_get_thumbnail" => ("{ session_id: string; frame_event_id: string }", "{ asset_id: string }"),
         "capture_get_config" => (
             "Record<string, never>",
-            "{ keyframe_interval_ms: number; include_input: boolean; include_window_meta: boolean }",
+            "{ keyframe_interval_ms: number; include_input: boolean; include_window_meta: boolean; dedup_max_hash_distance?: number }",
         ),
         "capture_set_config" => (
-            "{ keyframe_interval_ms: number; include_input: boolean; include_window_meta: boolean }",
-            "{ keyframe_interval_ms: number; include_input: boolean; include_window_meta: boolean }",
+            "{ keyframe_interval_ms: number; include_input: boolean; include_window_meta: boolean; dedup_max_hash_distance?: number }",
+            "{ keyframe_interval_ms: number; include_input: boolean; include_window_meta: boolean; dedup_max_hash_distance?: number }",
         ),
         "capture_start" | "capture_stop" => (
             "{ session_id: string }",
@@ -137,7 +145,7 @@ fn command_types(command: &str) -> (&'static str, &'static str) {
         ),
         "ocr_search" => (
             "{ session_id: string; query: string }",
-            "{ hits: Array<{ frame_ms: number; block_id: string; snippet: string }> }",
+            "{ hits: Array<{ frame_ms: number; block_id: string; bbox_norm: { x: number; y: number; w: number; h: number }; snippet: string; highlights: Array<{ start: number; end: number }> }> }",
         ),
         "ocr_get_blocks_for_frame" => (
             "{ session_id: string; frame_event_id: string }",
@@ -196,7 +204,27 @@ fn command_types(command: &str) -> (&'static str, &'static str) {
         ),
         "evidence_get_coverage" => (
             "{ session_id: string }",
-            "{ missing_step_ids: string[]; missing_generated_block_ids: string[]; pass: boolean }",
+            "{ missing_step_ids: string[]; missing_generated_block_ids: string[]; invalid_refs: Array<{ step_id: string; block_id: string; evidence_id: string; kind: 'dangling' | 'other_session' | 'invalidated'; owner_session_id?: string }>; pass: boolean }",
+        ),
+        "evidence_get_grounding" => (
+            "{ session_id: string }",
+            "{ blocks: Array<{ step_id: string; block_id: string; score: number; claims: string[]; ungrounded_claims: string[] }>; pass: boolean }",
+        ),
+        "evidence_graph_neighbors" => (
+            "{ session_id: string; node_id: string; direction?: 'outgoing' | 'incoming' | 'both'; edge_types?: Array<'derived_from' | 'located_in' | 'cites' | 'verifies'> }",
+            "{ nodes: Array<{ node_id: string; node_type: 'evidence' | 'step_block'; evidence?: { evidence_id: string; kind: string; source_id: string; locators: Array<{ locator_type: string; asset_id?: string; frame_ms?: number; bbox_norm?: { x: number; y: number; w: number; h: number }; text_offset?: { start: number; end: number }; note?: string }> }; step_id?: string; block_id?: string }>; edges: Array<{ from: string; to: string; edge_type: 'derived_from' | 'located_in' | 'cites' | 'verifies' }> }",
+        ),
+        "evidence_graph_path" => (
+            "{ session_id: string; from_node_id: string; to_node_id: string; max_depth?: number }",
+            "{ nodes: Array<{ node_id: string; node_type: 'evidence' | 'step_block'; evidence?: { evidence_id: string; kind: string; source_id: string; locators: Array<{ locator_type: string; asset_id?: string; frame_ms?: number; bbox_norm?: { x: number; y: number; w: number; h: number }; text_offset?: { start: number; end: number }; note?: string }> }; step_id?: string; block_id?: string }>; edges: Array<{ from: string; to: string; edge_type: 'derived_from' | 'located_in' | 'cites' | 'verifies' }> }",
+        ),
+        "evidence_graph_supports" => (
+            "{ session_id: string; step_id: string; block_id: string }",
+            "{ nodes: Array<{ node_id: string; node_type: 'evidence' | 'step_block'; evidence?: { evidence_id: string; kind: string; source_id: string; locators: Array<{ locator_type: string; asset_id?: string; frame_ms?: number; bbox_norm?: { x: number; y: number; w: number; h: number }; text_offset?: { start: number; end: number }; note?: string }> }; step_id?: string; block_id?: string }>; edges: Array<{ from: string; to: string; edge_type: 'derived_from' | 'located_in' | 'cites' | 'verifies' }> }",
+        ),
+        "search_global" => (
+            "{ query: string; created_after?: string; created_before?: string; bundle_id?: string; offset?: number; limit?: number }",
+            "{ groups: Array<{ session: { session_id: string; label: string; created_at: string; closed_at?: string; head_seq: number; head_hash: string }; hit_count: number; hits: Array<{ kind: 'session_label' | 'session_metadata' | 'step_title' | 'step_body' | 'window_title' | 'ocr_text'; ref_id?: string; frame_ms?: number; bundle_id?: string; snippet: string; highlights: Array<{ start: number; end: number }> }> }>; total_sessions: number; next_offset?: number }",
         ),
         "evidence_for_time_range" => (
             "{ session_id: string; start_ms: number; end_ms: number }",
@@ -229,11 +257,11 @@ fn command_types(command: &str) -> (&'static str, &'static str) {
         ),
         "models_list" => (
             "{ include_unhealthy: boolean }",
-            "{ models: Array<{ model_id: string; provider: string; label: string; digest: string }> }",
+            "{ models: Array<{ model_id: string; provider: string; label: string; model_name: string; digest: string }> }",
         ),
         "models_register" => (
             "{ provider: string; label: string; model_name: string; digest: string }",
-            "{ model_id: string; provider: string; label: string; digest: string }",
+            "{ model_id: string; provider: string; label: string; model_name: string; digest: string }",
         ),
         "models_remove" => ("{ model_id: string }", "{ removed: boolean }"),
         "model_roles_set" => (
@@ -247,7 +275,7 @@ fn command_types(command: &str) -> (&'static str, &'static str) {
         "bench_run" => ("{ model_id: string; benchmark: string }", "{ job_id: string }"),
         "bench_list" => (
             "{ limit?: number }",
-            "{ benches: Array<{ bench_id: string; model_id: string; score: number; created_at: string }> }",
+            "{ benches: Array<{ bench_id: string; model_id: string; benchmark: string; score: number; metrics?: { cases: number; schema_valid_pct: number; citation_valid_pct: number; grounding_pct: number; mean_latency_ms: number; max_latency_ms: number }; report_asset_id?: string; created_at: string }> }",
         ),
         "model_roles_get" => (
             "Record<string, never>",
@@ -271,7 +299,20 @@ fn command_types(command: &str) -> (&'static str, &'static str) {
         ),
         "export_verify_bundle" => (
             "{ bundle_path: string }",
-            "{ valid: boolean; issues: string[] }",
+            "{ valid: boolean; issues: string[]; signature: 'unsigned' | 'invalid' | 'untrusted' | 'trusted'; signer_public_key?: string }",
+        ),
+        "export_trusted_keys_get" => (
+            "Record<string, never>",
+            "{ local_public_key: string; trusted_public_keys: string[] }",
+        ),
+        "export_trusted_keys_set" => (
+            "{ trusted_public_keys: string[] }",
+            "{ local_public_key: string; trusted_public_keys: string[] }",
+        ),
+        "redaction_rules_get" => ("{ session_id: string }", "{ session_id: string; rules: Array<{ kind: 'pii'; detector: 'email' | 'ipv4' | 'ipv6' | 'aws_key' | 'jwt' | 'credit_card' } | { kind: 'regex'; pattern: string; label?: string } | { kind: 'region'; bbox_norm: { x: number; y: number; w: number; h: number }; frame_ms?: number; label?: string }>; is_default: boolean }"),
+        "redaction_rules_set" => (
+            "{ session_id: string; rules: Array<{ kind: 'pii'; detector: 'email' | 'ipv4' | 'ipv6' | 'aws_key' | 'jwt' | 'credit_card' } | { kind: 'regex'; pattern: string; label?: string } | { kind: 'region'; bbox_norm: { x: number; y: number; w: number; h: number }; frame_ms?: number; label?: string }> }",
+            "{ session_id: string; rules: Array<{ kind: 'pii'; detector: 'email' | 'ipv4' | 'ipv6' | 'aws_key' | 'jwt' | 'credit_card' } | { kind: 'regex'; pattern: string; label?: string } | { kind: 'region'; bbox_norm: { x: number; y: number; w: number; h: number }; frame_ms?: number; label?: string }>; is_default: boolean }",
         ),
         "jobs_list" => (
             "{ session_id?: string; status?: 'QUEUED' | 'RUNNING' | 'SUCCEEDED' | 'FAILED' | 'CANCELLED' }",
@@ -307,6 +348,6 @@ mod tests {
 
     #[test]
     fn command_list_is_locked_size() {
-        assert_eq!(IpcCommand::LOCKED_COMMANDS.len(), 66);
+        assert_eq!(IpcCommand::LOCKED_COMMANDS.len(), 77);
     }
 }
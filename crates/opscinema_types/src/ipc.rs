@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
@@ -26,6 +27,17 @@ pub struct SessionCloseRequest {
     pub session_id: SessionId,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct SessionExportArchiveRequest {
+    pub session_id: SessionId,
+    pub output_dir: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct SessionImportArchiveRequest {
+    pub archive_path: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct TimelineKeyframesRequest {
     pub session_id: SessionId,
@@ -109,6 +121,49 @@ pub struct EvidenceCoverageRequest {
     pub session_id: SessionId,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct EvidenceGroundingRequest {
+    pub session_id: SessionId,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct EvidenceGraphNeighborsRequest {
+    pub session_id: SessionId,
+    pub node_id: String,
+    /// Defaults to `both`.
+    pub direction: Option<EvidenceEdgeDirection>,
+    /// Empty keeps every edge type.
+    #[serde(default)]
+    pub edge_types: Vec<EvidenceEdgeType>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct EvidenceGraphPathRequest {
+    pub session_id: SessionId,
+    pub from_node_id: String,
+    pub to_node_id: String,
+    pub max_depth: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct EvidenceGraphSupportsRequest {
+    pub session_id: SessionId,
+    pub step_id: StepId,
+    pub block_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct SearchGlobalRequest {
+    pub query: String,
+    /// Inclusive bounds on the session's `created_at`.
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    /// Only sessions, and timed hits, where this app was frontmost.
+    pub bundle_id: Option<String>,
+    pub offset: Option<u32>,
+    pub limit: Option<u32>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct StepsGenerateCandidatesRequest {
     pub session_id: SessionId,
@@ -314,6 +369,17 @@ pub struct JobsGetRequest {
     pub job_id: JobId,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct RedactionRulesGetRequest {
+    pub session_id: SessionId,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct RedactionRulesSetRequest {
+    pub session_id: SessionId,
+    pub rules: Vec<RedactionRule>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct JobsCancelRequest {
     pub job_id: JobId,
@@ -332,6 +398,8 @@ pub enum IpcCommand {
     SessionList,
     SessionGet,
     SessionClose,
+    SessionExportArchive,
+    SessionImportArchive,
     TimelineGetKeyframes,
     TimelineGetEvents,
     TimelineGetThumbnail,
@@ -348,6 +416,11 @@ pub enum IpcCommand {
     EvidenceForStep,
     EvidenceFindText,
     EvidenceGetCoverage,
+    EvidenceGetGrounding,
+    EvidenceGraphNeighbors,
+    EvidenceGraphPath,
+    EvidenceGraphSupports,
+    SearchGlobal,
     StepsGenerateCandidates,
     StepsList,
     StepsGet,
@@ -385,6 +458,10 @@ pub enum IpcCommand {
     AgentPipelineReport,
     ExportsList,
     ExportVerifyBundle,
+    ExportTrustedKeysGet,
+    ExportTrustedKeysSet,
+    RedactionRulesGet,
+    RedactionRulesSet,
     JobsList,
     JobsGet,
     JobsCancel,
@@ -402,6 +479,8 @@ impl IpcCommand {
         IpcCommand::SessionList,
         IpcCommand::SessionGet,
         IpcCommand::SessionClose,
+        IpcCommand::SessionExportArchive,
+        IpcCommand::SessionImportArchive,
         IpcCommand::TimelineGetKeyframes,
         IpcCommand::TimelineGetEvents,
         IpcCommand::TimelineGetThumbnail,
@@ -418,6 +497,11 @@ impl IpcCommand {
         IpcCommand::EvidenceForStep,
         IpcCommand::EvidenceFindText,
         IpcCommand::EvidenceGetCoverage,
+        IpcCommand::EvidenceGetGrounding,
+        IpcCommand::EvidenceGraphNeighbors,
+        IpcCommand::EvidenceGraphPath,
+        IpcCommand::EvidenceGraphSupports,
+        IpcCommand::SearchGlobal,
         IpcCommand::StepsGenerateCandidates,
         IpcCommand::StepsList,
         IpcCommand::StepsGet,
@@ -455,6 +539,10 @@ impl IpcCommand {
         IpcCommand::AgentPipelineReport,
         IpcCommand::ExportsList,
         IpcCommand::ExportVerifyBundle,
+        IpcCommand::ExportTrustedKeysGet,
+        IpcCommand::ExportTrustedKeysSet,
+        IpcCommand::RedactionRulesGet,
+        IpcCommand::RedactionRulesSet,
         IpcCommand::JobsList,
         IpcCommand::JobsGet,
         IpcCommand::JobsCancel,
@@ -472,6 +560,8 @@ impl IpcCommand {
             IpcCommand::SessionList => "session_list",
             IpcCommand::SessionGet => "session_get",
             IpcCommand::SessionClose => "session_close",
+            IpcCommand::SessionExportArchive => "session_export_archive",
+            IpcCommand::SessionImportArchive => "session_import_archive",
             IpcCommand::TimelineGetKeyframes => "timeline_get_keyframes",
             IpcCommand::TimelineGetEvents => "timeline_get_events",
             IpcCommand::TimelineGetThumbnail => "timeline_get_thumbnail",
@@ -488,6 +578,11 @@ impl IpcCommand {
             IpcCommand::EvidenceForStep => "evidence_for_step",
             IpcCommand::EvidenceFindText => "evidence_find_text",
             IpcCommand::EvidenceGetCoverage => "evidence_get_coverage",
+            IpcCommand::EvidenceGetGrounding => "evidence_get_grounding",
+            IpcCommand::EvidenceGraphNeighbors => "evidence_graph_neighbors",
+            IpcCommand::EvidenceGraphPath => "evidence_graph_path",
+            IpcCommand::EvidenceGraphSupports => "evidence_graph_supports",
+            IpcCommand::SearchGlobal => "search_global",
             IpcCommand::StepsGenerateCandidates => "steps_generate_candidates",
             IpcCommand::StepsList => "steps_list",
             IpcCommand::StepsGet => "steps_get",
@@ -525,6 +620,10 @@ impl IpcCommand {
             IpcCommand::AgentPipelineReport => "agent_pipeline_report",
             IpcCommand::ExportsList => "exports_list",
             IpcCommand::ExportVerifyBundle => "export_verify_bundle",
+            IpcCommand::ExportTrustedKeysGet => "export_trusted_keys_get",
+            IpcCommand::ExportTrustedKeysSet => "export_trusted_keys_set",
+            IpcCommand::RedactionRulesGet => "redaction_rules_get",
+            IpcCommand::RedactionRulesSet => "redaction_rules_set",
             IpcCommand::JobsList => "jobs_list",
             IpcCommand::JobsGet => "jobs_get",
             IpcCommand::JobsCancel => "jobs_cancel",
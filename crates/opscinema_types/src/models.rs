@@ -37,6 +37,21 @@ pub struct AppSettings {
     pub offline_mode: bool,
     pub allow_input_capture: bool,
     pub allow_window_metadata: bool,
+    #[serde(default)]
+    pub ocr_provider: OcrProviderKind,
+}
+
+#[derive(
+    Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum OcrProviderKind {
+    /// Apple Vision on macOS, Tesseract elsewhere when built in, else stub.
+    #[default]
+    Auto,
+    Stub,
+    AppleVision,
+    Tesseract,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
@@ -59,6 +74,17 @@ pub struct SessionSummary {
     pub head_hash: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct SessionArchiveResult {
+    pub session_id: SessionId,
+    pub output_path: String,
+    pub event_count: u64,
+    pub asset_count: u64,
+    pub head_seq: i64,
+    pub head_hash: String,
+    pub archive_hash: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct SessionDetail {
     pub summary: SessionSummary,
@@ -85,6 +111,11 @@ pub struct CaptureConfig {
     pub keyframe_interval_ms: u32,
     pub include_input: bool,
     pub include_window_meta: bool,
+    /// Frames whose dHash is within this many bits of the last kept keyframe
+    /// are recorded as `KeyframeSuppressed` instead of stored. `None` keeps
+    /// every frame.
+    #[serde(default)]
+    pub dedup_max_hash_distance: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
@@ -172,7 +203,11 @@ pub struct OcrStatus {
 pub struct OcrSearchHit {
     pub frame_ms: i64,
     pub block_id: String,
+    pub bbox_norm: BBoxNorm,
+    /// Excerpt of the block text around the match.
     pub snippet: String,
+    /// Matched ranges within `snippet`, in characters.
+    pub highlights: Vec<TextOffset>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
@@ -180,6 +215,49 @@ pub struct OcrSearchResponse {
     pub hits: Vec<OcrSearchHit>,
 }
 
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum GlobalSearchHitKind {
+    SessionLabel,
+    SessionMetadata,
+    StepTitle,
+    StepBody,
+    WindowTitle,
+    OcrText,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct GlobalSearchHit {
+    pub kind: GlobalSearchHitKind,
+    /// OCR block id, step id, window event id or metadata key; absent for
+    /// the session label.
+    pub ref_id: Option<String>,
+    pub frame_ms: Option<i64>,
+    /// Frontmost bundle id at `frame_ms`, when known.
+    pub bundle_id: Option<String>,
+    pub snippet: String,
+    /// Matched ranges within `snippet`, in characters.
+    pub highlights: Vec<TextOffset>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct GlobalSearchSessionGroup {
+    pub session: SessionSummary,
+    /// Hits in the session before `hits` was truncated.
+    pub hit_count: u32,
+    pub hits: Vec<GlobalSearchHit>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct SearchGlobalResponse {
+    pub groups: Vec<GlobalSearchSessionGroup>,
+    /// Sessions with at least one hit, across all pages.
+    pub total_sessions: u32,
+    pub next_offset: Option<u32>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct OcrBlocksForFrameResponse {
     pub blocks: Vec<OcrBlock>,
@@ -242,6 +320,50 @@ pub struct EvidenceFindTextResponse {
 pub struct EvidenceCoverageResponse {
     pub missing_step_ids: Vec<StepId>,
     pub missing_generated_block_ids: Vec<String>,
+    /// Refs on any block that do not resolve to valid evidence of the session.
+    #[serde(default)]
+    pub invalid_refs: Vec<EvidenceRefIssue>,
+    pub pass: bool,
+}
+
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum EvidenceRefIssueKind {
+    /// No session derives this evidence id.
+    Dangling,
+    /// Evidence of another session.
+    OtherSession,
+    /// Evidence the session later invalidated, e.g. an anchor that degraded.
+    Invalidated,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct EvidenceRefIssue {
+    pub step_id: StepId,
+    pub block_id: String,
+    pub evidence_id: Uuid,
+    pub kind: EvidenceRefIssueKind,
+    /// The session that owns an `other_session` ref.
+    pub owner_session_id: Option<SessionId>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct BlockGrounding {
+    pub step_id: StepId,
+    pub block_id: String,
+    /// Percent of `claims` found in the OCR text of the cited frames; 100
+    /// when the block makes no checkable claim.
+    pub score: u8,
+    pub claims: Vec<String>,
+    pub ungrounded_claims: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct EvidenceGroundingResponse {
+    /// Generated blocks in step order.
+    pub blocks: Vec<BlockGrounding>,
     pub pass: bool,
 }
 
@@ -419,6 +541,8 @@ pub struct ModelProfile {
     pub model_id: String,
     pub provider: String,
     pub label: String,
+    /// Name the provider knows the model by (Ollama tag, MLX weights path).
+    pub model_name: String,
     pub digest: String,
 }
 
@@ -455,10 +579,26 @@ pub struct OllamaListResponse {
 pub struct BenchRecord {
     pub bench_id: Uuid,
     pub model_id: String,
+    /// Role benchmarked; empty for records that predate per-role benchmarks.
+    pub benchmark: String,
+    /// Mean of the schema, citation and grounding percentages.
     pub score: i32,
+    pub metrics: Option<BenchMetrics>,
+    /// Canonical JSON report with per-case breakdowns and prompt/output assets.
+    pub report_asset_id: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct BenchMetrics {
+    pub cases: u32,
+    pub schema_valid_pct: u8,
+    pub citation_valid_pct: u8,
+    pub grounding_pct: u8,
+    pub mean_latency_ms: u64,
+    pub max_latency_ms: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct BenchListResponse {
     pub benches: Vec<BenchRecord>,
@@ -484,6 +624,146 @@ pub struct ExportsListResponse {
 pub struct ExportVerifyResponse {
     pub valid: bool,
     pub issues: Vec<String>,
+    pub signature: ExportSignatureStatus,
+    pub signer_public_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportSignatureStatus {
+    Unsigned,
+    Invalid,
+    Untrusted,
+    Trusted,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct ExportTrustedKeys {
+    pub local_public_key: String,
+    pub trusted_public_keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct ExportTrustedKeysUpdate {
+    pub trusted_public_keys: Vec<String>,
+}
+
+/// Built-in secret and PII detectors applied to exports.
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum PiiDetector {
+    Email,
+    Ipv4,
+    Ipv6,
+    AwsKey,
+    Jwt,
+    CreditCard,
+}
+
+impl PiiDetector {
+    pub const ALL: [PiiDetector; 6] = [
+        PiiDetector::Email,
+        PiiDetector::Ipv4,
+        PiiDetector::Ipv6,
+        PiiDetector::AwsKey,
+        PiiDetector::Jwt,
+        PiiDetector::CreditCard,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PiiDetector::Email => "email",
+            PiiDetector::Ipv4 => "ipv4",
+            PiiDetector::Ipv6 => "ipv6",
+            PiiDetector::AwsKey => "aws_key",
+            PiiDetector::Jwt => "jwt",
+            PiiDetector::CreditCard => "credit_card",
+        }
+    }
+}
+
+/// One session redaction rule. Text rules (`pii`, `regex`) scrub step text,
+/// OCR snippets and, through the OCR boxes they match, keyframe pixels;
+/// `region` rules black out a box on one frame (`frame_ms`) or on all frames.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RedactionRule {
+    Pii {
+        detector: PiiDetector,
+    },
+    Regex {
+        pattern: String,
+        label: Option<String>,
+    },
+    Region {
+        bbox_norm: BBoxNorm,
+        frame_ms: Option<i64>,
+        label: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct RedactionRules {
+    pub session_id: SessionId,
+    pub rules: Vec<RedactionRule>,
+    /// True until rules are set for the session; defaults enable every
+    /// [`PiiDetector`].
+    pub is_default: bool,
+}
+
+/// `derived_from`: produced by processing the target (OCR or an anchor
+/// resolved on a keyframe). `located_in`: captured inside the target (a click
+/// on a keyframe or OCR block). `cites`: a step block's evidence ref.
+/// `verifies`: an anchor or verifier result confirming a step block.
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum EvidenceEdgeType {
+    DerivedFrom,
+    LocatedIn,
+    Cites,
+    Verifies,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EvidenceEdgeDirection {
+    Outgoing,
+    Incoming,
+    Both,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EvidenceNodeType {
+    Evidence,
+    StepBlock,
+}
+
+/// Node ids are `evidence:<evidence_id>` and `block:<step_id>:<block_id>`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct EvidenceGraphNode {
+    pub node_id: String,
+    pub node_type: EvidenceNodeType,
+    pub evidence: Option<EvidenceItem>,
+    pub step_id: Option<StepId>,
+    pub block_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord)]
+pub struct EvidenceGraphEdge {
+    pub from: String,
+    pub to: String,
+    pub edge_type: EvidenceEdgeType,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct EvidenceSubgraph {
+    pub nodes: Vec<EvidenceGraphNode>,
+    pub edges: Vec<EvidenceGraphEdge>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]